@@ -2,7 +2,7 @@
 
 use soroban_sdk::{
     contract, contracterror, contractevent, contractimpl, contracttype, Address,
-    Env,
+    Env, Vec,
 };
 
 // ---------------------------------------------------------------------------
@@ -12,6 +12,9 @@ use soroban_sdk::{
 const PERSISTENT_BUMP_LEDGERS: u32 = 518_400; // ~30 days
 const PERSISTENT_BUMP_THRESHOLD: u32 = PERSISTENT_BUMP_LEDGERS - 100_800; // Renew ~7 days early
 
+/// Maximum transitions kept per breaker; the oldest are dropped first.
+const MAX_BREAKER_HISTORY: u32 = 50;
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -36,6 +39,7 @@ pub enum Error {
 pub enum BreakerStatus {
     Closed = 0, // Normal operation
     Open = 1,   // Tripped
+    HalfOpen = 2, // Cooldown elapsed; probing for recovery
 }
 
 #[contracttype]
@@ -44,6 +48,8 @@ pub struct BreakerData {
     pub failure_count: u32,
     pub status: BreakerStatus,
     pub last_failure_ledger: u32,
+    /// Ledger at which the breaker last moved to `Open`.
+    pub opened_ledger: u32,
 }
 
 #[contracttype]
@@ -51,7 +57,9 @@ pub struct BreakerData {
 pub enum DataKey {
     Admin,
     Threshold,
+    Cooldown,         // Ledgers an Open breaker waits before turning HalfOpen
     Breaker(Address), // Keyed by contract_id
+    BreakerHistory(Address), // Vec<(timestamp, status)>, keyed by contract_id
 }
 
 // ---------------------------------------------------------------------------
@@ -81,6 +89,11 @@ pub struct BreakerReset {
     pub contract_id: Address,
 }
 
+#[contractevent]
+pub struct CooldownSet {
+    pub cooldown_ledgers: u32,
+}
+
 // ---------------------------------------------------------------------------
 // Contract
 // ---------------------------------------------------------------------------
@@ -121,25 +134,30 @@ impl ContractCircuitBreaker {
         let threshold: u32 = env.storage().instance().get(&DataKey::Threshold).unwrap();
         let key = DataKey::Breaker(contract_id.clone());
         
-        let mut data: BreakerData = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or(BreakerData {
-                failure_count: 0,
-                status: BreakerStatus::Closed,
-                last_failure_ledger: 0,
-            });
-
-        // Only increment if already closed
-        if data.status == BreakerStatus::Closed {
-            data.failure_count += 1;
-            data.last_failure_ledger = env.ledger().sequence();
-
-            if data.failure_count >= threshold {
+        let mut data = Self::load_breaker_for_update(&env, &contract_id);
+
+        match data.status {
+            BreakerStatus::Closed => {
+                data.failure_count += 1;
+                data.last_failure_ledger = env.ledger().sequence();
+
+                if data.failure_count >= threshold {
+                    data.status = BreakerStatus::Open;
+                    data.opened_ledger = env.ledger().sequence();
+                    BreakerTripped { contract_id: contract_id.clone() }.publish(&env);
+                }
+                Self::append_history(&env, &contract_id, data.status);
+            }
+            // A failed probe re-opens the breaker for another cooldown.
+            BreakerStatus::HalfOpen => {
+                data.failure_count += 1;
+                data.last_failure_ledger = env.ledger().sequence();
                 data.status = BreakerStatus::Open;
+                data.opened_ledger = env.ledger().sequence();
                 BreakerTripped { contract_id: contract_id.clone() }.publish(&env);
+                Self::append_history(&env, &contract_id, data.status);
             }
+            BreakerStatus::Open => {}
         }
 
         env.storage().persistent().set(&key, &data);
@@ -165,18 +183,12 @@ impl ContractCircuitBreaker {
         admin.require_auth();
 
         let key = DataKey::Breaker(contract_id.clone());
-        let mut data: BreakerData = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or(BreakerData {
-                failure_count: 0,
-                status: BreakerStatus::Closed,
-                last_failure_ledger: 0,
-            });
+        let mut data = Self::load_breaker_for_update(&env, &contract_id);
 
         data.status = BreakerStatus::Open;
+        data.opened_ledger = env.ledger().sequence();
         env.storage().persistent().set(&key, &data);
+        Self::append_history(&env, &contract_id, data.status);
         
         BreakerTripped { contract_id }.publish(&env);
 
@@ -193,24 +205,137 @@ impl ContractCircuitBreaker {
             failure_count: 0,
             status: BreakerStatus::Closed,
             last_failure_ledger: 0,
+            opened_ledger: 0,
         };
 
         env.storage().persistent().set(&key, &data);
+        Self::append_history(&env, &contract_id, data.status);
         
         BreakerReset { contract_id }.publish(&env);
 
         Ok(())
     }
 
+    /// Record a successful probe call. A `HalfOpen` breaker is closed with
+    /// its failure count cleared; `Open` and `Closed` breakers are left as
+    /// they are.
+    pub fn record_success(env: Env, contract_id: Address) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        let key = DataKey::Breaker(contract_id.clone());
+        if Self::load_breaker_for_update(&env, &contract_id).status != BreakerStatus::HalfOpen {
+            return Ok(());
+        }
+
+        let data = BreakerData {
+            failure_count: 0,
+            status: BreakerStatus::Closed,
+            last_failure_ledger: 0,
+            opened_ledger: 0,
+        };
+        env.storage().persistent().set(&key, &data);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+        Self::append_history(&env, &contract_id, data.status);
+
+        BreakerReset { contract_id }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Set how many ledgers an `Open` breaker waits before it turns
+    /// `HalfOpen`. Zero (the default) keeps breakers open until `reset`.
+    pub fn set_cooldown(env: Env, cooldown_ledgers: u32) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Cooldown, &cooldown_ledgers);
+
+        CooldownSet { cooldown_ledgers }.publish(&env);
+
+        Ok(())
+    }
+
     /// Query the current state of a circuit breaker.
     pub fn breaker_state(env: Env, contract_id: Address) -> Option<BreakerData> {
-        env.storage().persistent().get(&DataKey::Breaker(contract_id))
+        let key = DataKey::Breaker(contract_id);
+        if !env.storage().persistent().has(&key) {
+            return None;
+        }
+        Some(Self::load_breaker(&env, &key))
+    }
+
+    /// Return the breaker's recorded transitions as `(timestamp, status)`,
+    /// oldest first. Only the last `MAX_BREAKER_HISTORY` are kept.
+    pub fn breaker_history(env: Env, contract_id: Address) -> Vec<(u64, BreakerStatus)> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BreakerHistory(contract_id))
+            .unwrap_or(Vec::new(&env))
     }
 
     // -----------------------------------------------------------------------
     // Internal helpers
     // -----------------------------------------------------------------------
 
+    /// Like `load_breaker`, but records the lazy `Open` -> `HalfOpen`
+    /// transition in the history before the caller changes the breaker.
+    fn load_breaker_for_update(env: &Env, contract_id: &Address) -> BreakerData {
+        let key = DataKey::Breaker(contract_id.clone());
+        let data = Self::load_breaker(env, &key);
+        if data.status == BreakerStatus::HalfOpen {
+            Self::append_history(env, contract_id, BreakerStatus::HalfOpen);
+        }
+        data
+    }
+
+    fn append_history(env: &Env, contract_id: &Address, status: BreakerStatus) {
+        let key = DataKey::BreakerHistory(contract_id.clone());
+        let mut history: Vec<(u64, BreakerStatus)> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if history.len() >= MAX_BREAKER_HISTORY {
+            history.pop_front();
+        }
+        history.push_back((env.ledger().timestamp(), status));
+        env.storage().persistent().set(&key, &history);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+    }
+
+    /// Read a breaker, reporting an `Open` breaker whose cooldown has
+    /// elapsed as `HalfOpen`. The transition is not written back.
+    fn load_breaker(env: &Env, key: &DataKey) -> BreakerData {
+        let mut data: BreakerData = env
+            .storage()
+            .persistent()
+            .get(key)
+            .unwrap_or(BreakerData {
+                failure_count: 0,
+                status: BreakerStatus::Closed,
+                last_failure_ledger: 0,
+                opened_ledger: 0,
+            });
+
+        let cooldown: u32 = env.storage().instance().get(&DataKey::Cooldown).unwrap_or(0);
+        if data.status == BreakerStatus::Open
+            && cooldown > 0
+            && env.ledger().sequence() >= data.opened_ledger.saturating_add(cooldown)
+        {
+            data.status = BreakerStatus::HalfOpen;
+        }
+        data
+    }
+
     fn require_admin(env: &Env) -> Result<Address, Error> {
         env.storage()
             .instance()
@@ -226,7 +351,10 @@ impl ContractCircuitBreaker {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, Env,
+    };
 
     struct Setup<'a> {
         env: Env,
@@ -249,7 +377,7 @@ mod test {
         Setup {
             env,
             client,
-            admin,
+            _admin: admin,
         }
     }
 
@@ -295,4 +423,90 @@ mod test {
         assert_eq!(state.status, BreakerStatus::Closed);
         assert_eq!(state.failure_count, 0);
     }
+
+    #[test]
+    fn test_record_success_closes_half_open_breaker() {
+        let s = setup();
+        let target = Address::generate(&s.env);
+        s.client.set_cooldown(&10);
+
+        for _ in 0..3 {
+            s.client.record_failure(&target, &1);
+        }
+        assert_eq!(s.client.breaker_state(&target).unwrap().status, BreakerStatus::Open);
+
+        s.env.ledger().with_mut(|li| li.sequence_number += 10);
+        assert_eq!(
+            s.client.breaker_state(&target).unwrap().status,
+            BreakerStatus::HalfOpen
+        );
+
+        s.client.record_success(&target);
+        let state = s.client.breaker_state(&target).unwrap();
+        assert_eq!(state.status, BreakerStatus::Closed);
+        assert_eq!(state.failure_count, 0);
+    }
+
+    #[test]
+    fn test_record_success_ignored_while_fully_open() {
+        let s = setup();
+        let target = Address::generate(&s.env);
+        s.client.set_cooldown(&10);
+
+        s.client.trip(&target);
+        s.env.ledger().with_mut(|li| li.sequence_number += 9);
+        s.client.record_success(&target);
+
+        let state = s.client.breaker_state(&target).unwrap();
+        assert_eq!(state.status, BreakerStatus::Open);
+
+        // A failed probe after the cooldown re-opens the breaker.
+        s.env.ledger().with_mut(|li| li.sequence_number += 1);
+        s.client.record_failure(&target, &1);
+        assert_eq!(s.client.breaker_state(&target).unwrap().status, BreakerStatus::Open);
+    }
+
+    #[test]
+    fn test_breaker_history_records_trip_and_reset() {
+        let s = setup();
+        let target = Address::generate(&s.env);
+        assert_eq!(s.client.breaker_history(&target).len(), 0);
+
+        s.env.ledger().with_mut(|li| li.timestamp = 100);
+        for _ in 0..3 {
+            s.client.record_failure(&target, &1);
+        }
+        s.env.ledger().with_mut(|li| li.timestamp = 200);
+        s.client.reset(&target);
+
+        let history = s.client.breaker_history(&target);
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.get(0).unwrap(), (100, BreakerStatus::Closed));
+        assert_eq!(history.get(1).unwrap(), (100, BreakerStatus::Closed));
+        assert_eq!(history.get(2).unwrap(), (100, BreakerStatus::Open));
+        assert_eq!(history.get(3).unwrap(), (200, BreakerStatus::Closed));
+    }
+
+    #[test]
+    fn test_breaker_history_records_half_open_and_is_bounded() {
+        let s = setup();
+        let target = Address::generate(&s.env);
+        s.client.set_cooldown(&10);
+
+        s.client.trip(&target);
+        s.env.ledger().with_mut(|li| li.sequence_number += 10);
+        s.client.record_success(&target);
+
+        let history = s.client.breaker_history(&target);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get(1).unwrap().1, BreakerStatus::HalfOpen);
+        assert_eq!(history.get(2).unwrap().1, BreakerStatus::Closed);
+
+        for _ in 0..MAX_BREAKER_HISTORY {
+            s.client.reset(&target);
+        }
+        let history = s.client.breaker_history(&target);
+        assert_eq!(history.len(), MAX_BREAKER_HISTORY);
+        assert_eq!(history.get(0).unwrap().1, BreakerStatus::Closed);
+    }
 }
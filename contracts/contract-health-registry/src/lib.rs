@@ -1,10 +1,30 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short,
+    contract, contracterror, contractimpl, contracttype, symbol_short,
     Address, Env, Symbol, Vec,
 };
 
+/// Lowest score `report_score` maps to `Healthy`.
+pub const HEALTHY_MIN_SCORE: u32 = 80;
+/// Lowest score `report_score` maps to `Degraded`; anything below is `Critical`.
+pub const DEGRADED_MIN_SCORE: u32 = 50;
+/// Scores are percentages.
+pub const MAX_SCORE: u32 = 100;
+
+// ── Errors ───────────────────────────────────────────────────────
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    UnauthorizedReporter = 2,
+    InvalidPolicy = 3,
+    NoHealthData = 4,
+    InvalidScore = 5,
+    SelfDependency = 6,
+}
+
 // ── Storage Keys ─────────────────────────────────────────────────
 #[contracttype]
 #[derive(Clone)]
@@ -13,6 +33,8 @@ pub enum DataKey {
     HealthPolicy(Address),  // contract_id → HealthPolicy
     LatestHealth(Address),  // contract_id → HealthReport
     HealthHistory(Address), // contract_id → Vec<HealthReport>
+    IncidentStats(Address), // contract_id → IncidentStats
+    Dependencies(Address),  // contract_id → Vec<Address>
 }
 
 // ── Domain Types ─────────────────────────────────────────────────
@@ -46,6 +68,18 @@ pub struct HealthPolicy {
     pub max_history: u32,
 }
 
+/// Incident metrics accumulated from status transitions. A contract is
+/// unhealthy while Degraded or Critical; an incident is an entry into Critical.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IncidentStats {
+    pub incident_count: u32,
+    /// Seconds spent Degraded or Critical, including any ongoing period.
+    pub total_unhealthy_seconds: u64,
+    /// Start of the current unhealthy period, if the contract is unhealthy.
+    pub unhealthy_since: Option<u64>,
+}
+
 // ── Events ────────────────────────────────────────────────────────
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -62,6 +96,13 @@ pub struct PolicySet {
     pub policy_type: Symbol,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DependenciesSet {
+    pub contract_id: Address,
+    pub dependencies: Vec<Address>,
+}
+
 // ── Contract ──────────────────────────────────────────────────────
 #[contract]
 pub struct ContractHealthRegistry;
@@ -84,12 +125,14 @@ impl ContractHealthRegistry {
         contract_id: Address,
         status: HealthStatus,
         details_hash: Symbol,
-    ) {
+    ) -> Result<(), Error> {
         reporter.require_auth();
 
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).ok_or(Error::NotInitialized)?;
         // Only admin may report in this version; circuit-breaker roles can extend this later
-        assert!(reporter == admin, "Unauthorized reporter");
+        if reporter != admin {
+            return Err(Error::UnauthorizedReporter);
+        }
 
         let report = HealthReport {
             contract_id: contract_id.clone(),
@@ -99,6 +142,8 @@ impl ContractHealthRegistry {
             reported_by: reporter,
         };
 
+        Self::record_transition(&env, &contract_id, &status, report.timestamp);
+
         // Update latest report
         env.storage()
             .persistent()
@@ -137,13 +182,31 @@ impl ContractHealthRegistry {
                 timestamp: report.timestamp,
             },
         );
+        Ok(())
+    }
+
+    /// Report health as a 0-100 score and let the registry derive the status:
+    /// at least `HEALTHY_MIN_SCORE` is Healthy, at least `DEGRADED_MIN_SCORE`
+    /// is Degraded, anything lower is Critical. Returns the derived status.
+    pub fn report_score(
+        env: Env,
+        reporter: Address,
+        contract_id: Address,
+        score: u32,
+        details_hash: Symbol,
+    ) -> Result<HealthStatus, Error> {
+        let status = Self::status_for_score(score)?;
+        Self::report_health(env, reporter, contract_id, status.clone(), details_hash)?;
+        Ok(status)
     }
 
     /// Set the health monitoring policy for a contract. Admin-only.
-    pub fn set_health_policy(env: Env, contract_id: Address, policy: HealthPolicy) {
-        Self::require_admin(&env);
+    pub fn set_health_policy(env: Env, contract_id: Address, policy: HealthPolicy) -> Result<(), Error> {
+        Self::require_admin(&env)?;
 
-        assert!(policy.max_history > 0, "max_history must be at least 1");
+        if policy.max_history == 0 {
+            return Err(Error::InvalidPolicy);
+        }
 
         env.storage()
             .persistent()
@@ -153,14 +216,15 @@ impl ContractHealthRegistry {
             (symbol_short!("policy"),),
             PolicySet { contract_id, policy_type: policy.policy_type },
         );
+        Ok(())
     }
 
     /// Get the most recent health report for a contract.
-    pub fn health_of(env: Env, contract_id: Address) -> HealthReport {
+    pub fn health_of(env: Env, contract_id: Address) -> Result<HealthReport, Error> {
         env.storage()
             .persistent()
             .get(&DataKey::LatestHealth(contract_id))
-            .expect("No health data for contract")
+            .ok_or(Error::NoHealthData)
     }
 
     /// Get the full health history for a contract (up to max_history entries).
@@ -171,14 +235,146 @@ impl ContractHealthRegistry {
             .unwrap_or(Vec::new(&env))
     }
 
+    /// Get incident metrics for a contract: how often it entered Critical and
+    /// how long it has spent unhealthy, counting an ongoing period up to now.
+    pub fn incident_stats(env: Env, contract_id: Address) -> IncidentStats {
+        let mut stats = Self::load_stats(&env, &contract_id);
+        if let Some(since) = stats.unhealthy_since {
+            let ongoing = env.ledger().timestamp().saturating_sub(since);
+            stats.total_unhealthy_seconds = stats.total_unhealthy_seconds.saturating_add(ongoing);
+        }
+        stats
+    }
+
+    /// Declare the direct dependencies of a contract, replacing any previous
+    /// list. Admin-only. A contract may not list itself.
+    pub fn set_dependencies(env: Env, contract_id: Address, deps: Vec<Address>) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+
+        if deps.contains(&contract_id) {
+            return Err(Error::SelfDependency);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Dependencies(contract_id.clone()), &deps);
+
+        env.events().publish(
+            (symbol_short!("deps"),),
+            DependenciesSet { contract_id, dependencies: deps },
+        );
+        Ok(())
+    }
+
+    /// Get the declared direct dependencies of a contract.
+    pub fn dependencies(env: Env, contract_id: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Dependencies(contract_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the worst of a contract's own status and the statuses of its
+    /// direct dependencies. Only one level is traversed, so dependency
+    /// cycles cannot recurse. A contract with no report counts as Unknown.
+    pub fn composite_health(env: Env, contract_id: Address) -> HealthStatus {
+        let mut worst = Self::latest_status(&env, &contract_id);
+        for dep in Self::dependencies(env.clone(), contract_id).iter() {
+            let status = Self::latest_status(&env, &dep);
+            if Self::severity(&status) > Self::severity(&worst) {
+                worst = status;
+            }
+        }
+        worst
+    }
+
     // ── Internal ─────────────────────────────────────────────────
-    fn require_admin(env: &Env) {
+    fn status_for_score(score: u32) -> Result<HealthStatus, Error> {
+        if score > MAX_SCORE {
+            return Err(Error::InvalidScore);
+        }
+        Ok(if score >= HEALTHY_MIN_SCORE {
+            HealthStatus::Healthy
+        } else if score >= DEGRADED_MIN_SCORE {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Critical
+        })
+    }
+
+    fn latest_status(env: &Env, contract_id: &Address) -> HealthStatus {
+        env.storage()
+            .persistent()
+            .get::<_, HealthReport>(&DataKey::LatestHealth(contract_id.clone()))
+            .map(|r| r.status)
+            .unwrap_or(HealthStatus::Unknown)
+    }
+
+    /// Ordering used by `composite_health`: Healthy < Unknown < Degraded < Critical.
+    fn severity(status: &HealthStatus) -> u32 {
+        match status {
+            HealthStatus::Healthy => 0,
+            HealthStatus::Unknown => 1,
+            HealthStatus::Degraded => 2,
+            HealthStatus::Critical => 3,
+        }
+    }
+
+    /// Update incident metrics for a report of `status` at `now`, based on the
+    /// status of the previous report.
+    fn record_transition(env: &Env, contract_id: &Address, status: &HealthStatus, now: u64) {
+        let previous: Option<HealthReport> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::LatestHealth(contract_id.clone()));
+        let was_critical = matches!(previous, Some(ref r) if r.status == HealthStatus::Critical);
+
+        let mut stats = Self::load_stats(env, contract_id);
+
+        if *status == HealthStatus::Critical && !was_critical {
+            stats.incident_count = stats.incident_count.checked_add(1).expect("Overflow");
+        }
+
+        match (stats.unhealthy_since, Self::is_unhealthy(status)) {
+            (None, true) => stats.unhealthy_since = Some(now),
+            (Some(since), false) => {
+                stats.total_unhealthy_seconds = stats
+                    .total_unhealthy_seconds
+                    .checked_add(now.saturating_sub(since))
+                    .expect("Overflow");
+                stats.unhealthy_since = None;
+            }
+            _ => {}
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::IncidentStats(contract_id.clone()), &stats);
+    }
+
+    fn load_stats(env: &Env, contract_id: &Address) -> IncidentStats {
+        env.storage()
+            .persistent()
+            .get(&DataKey::IncidentStats(contract_id.clone()))
+            .unwrap_or(IncidentStats {
+                incident_count: 0,
+                total_unhealthy_seconds: 0,
+                unhealthy_since: None,
+            })
+    }
+
+    fn is_unhealthy(status: &HealthStatus) -> bool {
+        matches!(status, HealthStatus::Degraded | HealthStatus::Critical)
+    }
+
+    fn require_admin(env: &Env) -> Result<(), Error> {
         let admin: Address = env
             .storage()
             .instance()
             .get(&DataKey::Admin)
-            .expect("Not initialized");
+            .ok_or(Error::NotInitialized)?;
         admin.require_auth();
+        Ok(())
     }
 }
 
@@ -186,7 +382,10 @@ impl ContractHealthRegistry {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Env, Symbol};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Env, Symbol,
+    };
 
     #[test]
     fn test_report_and_query_health() {
@@ -267,7 +466,6 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Unauthorized reporter")]
     fn test_unauthorized_reporter_fails() {
         let env = Env::default();
         env.mock_all_auths();
@@ -280,7 +478,71 @@ mod test {
         let client = ContractHealthRegistryClient::new(&env, &contract_id);
 
         client.init(&admin);
-        client.report_health(&bad_actor, &monitored, &HealthStatus::Healthy, &Symbol::new(&env, "X"));
+        let result = client.try_report_health(
+            &bad_actor,
+            &monitored,
+            &HealthStatus::Healthy,
+            &Symbol::new(&env, "X"),
+        );
+        assert_eq!(result, Err(Ok(Error::UnauthorizedReporter)));
+        assert_eq!(
+            client.try_report_score(&bad_actor, &monitored, &90, &Symbol::new(&env, "X")),
+            Err(Ok(Error::UnauthorizedReporter))
+        );
+        assert_eq!(client.try_health_of(&monitored), Err(Ok(Error::NoHealthData)));
+    }
+
+    #[test]
+    fn test_report_score_derives_status_at_boundaries() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let monitored = Address::generate(&env);
+
+        let contract_id = env.register_contract(None, ContractHealthRegistry);
+        let client = ContractHealthRegistryClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        let cases = [
+            (100, HealthStatus::Healthy),
+            (80, HealthStatus::Healthy),
+            (79, HealthStatus::Degraded),
+            (50, HealthStatus::Degraded),
+            (49, HealthStatus::Critical),
+            (0, HealthStatus::Critical),
+        ];
+        for (score, expected) in cases {
+            let status = client.report_score(&admin, &monitored, &score, &Symbol::new(&env, "S"));
+            assert_eq!(status, expected);
+            assert_eq!(client.health_of(&monitored).status, expected);
+        }
+
+        assert_eq!(
+            client.try_report_score(&admin, &monitored, &101, &Symbol::new(&env, "S")),
+            Err(Ok(Error::InvalidScore))
+        );
+        assert_eq!(client.history(&monitored).len(), 6);
+    }
+
+    #[test]
+    fn test_zero_max_history_policy_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let monitored = Address::generate(&env);
+
+        let contract_id = env.register_contract(None, ContractHealthRegistry);
+        let client = ContractHealthRegistryClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        let policy = HealthPolicy {
+            contract_id: monitored.clone(),
+            policy_type: Symbol::new(&env, "strict"),
+            max_history: 0,
+        };
+        assert_eq!(client.try_set_health_policy(&monitored, &policy), Err(Ok(Error::InvalidPolicy)));
     }
 
     #[test]
@@ -294,4 +556,125 @@ mod test {
         client.init(&admin);
         client.init(&admin);
     }
+
+    #[test]
+    fn test_incident_stats_track_transitions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let monitored = Address::generate(&env);
+
+        let contract_id = env.register_contract(None, ContractHealthRegistry);
+        let client = ContractHealthRegistryClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        let report = |status: HealthStatus, at: u64| {
+            env.ledger().with_mut(|l| l.timestamp = at);
+            client.report_health(&admin, &monitored, &status, &Symbol::new(&env, "R"));
+        };
+
+        report(HealthStatus::Healthy, 1_000);
+        report(HealthStatus::Critical, 1_100);
+        // Staying Critical is the same incident.
+        report(HealthStatus::Critical, 1_150);
+        report(HealthStatus::Healthy, 1_400);
+
+        let stats = client.incident_stats(&monitored);
+        assert_eq!(stats.incident_count, 1);
+        assert_eq!(stats.total_unhealthy_seconds, 300);
+        assert_eq!(stats.unhealthy_since, None);
+
+        // Degraded counts as unhealthy time but not as an incident until it
+        // escalates to Critical.
+        report(HealthStatus::Degraded, 2_000);
+        report(HealthStatus::Critical, 2_050);
+        report(HealthStatus::Healthy, 2_100);
+
+        let stats = client.incident_stats(&monitored);
+        assert_eq!(stats.incident_count, 2);
+        assert_eq!(stats.total_unhealthy_seconds, 400);
+
+        // An ongoing period is included up to now.
+        report(HealthStatus::Critical, 3_000);
+        env.ledger().with_mut(|l| l.timestamp = 3_050);
+        let stats = client.incident_stats(&monitored);
+        assert_eq!(stats.incident_count, 3);
+        assert_eq!(stats.total_unhealthy_seconds, 450);
+        assert_eq!(stats.unhealthy_since, Some(3_000));
+    }
+
+    #[test]
+    fn test_composite_health_takes_worst_dependency() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let app = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        let vault = Address::generate(&env);
+
+        let contract_id = env.register_contract(None, ContractHealthRegistry);
+        let client = ContractHealthRegistryClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        let ok = Symbol::new(&env, "OK");
+        client.report_health(&admin, &app, &HealthStatus::Healthy, &ok);
+        client.report_health(&admin, &oracle, &HealthStatus::Healthy, &ok);
+        client.report_health(&admin, &vault, &HealthStatus::Healthy, &ok);
+        client.set_dependencies(&app, &Vec::from_array(&env, [oracle.clone(), vault.clone()]));
+
+        assert_eq!(client.composite_health(&app), HealthStatus::Healthy);
+
+        client.report_health(&admin, &vault, &HealthStatus::Critical, &Symbol::new(&env, "DOWN"));
+        assert_eq!(client.composite_health(&app), HealthStatus::Critical);
+        // The contract's own status is unaffected.
+        assert_eq!(client.health_of(&app).status, HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_composite_health_only_follows_direct_dependencies() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+
+        let contract_id = env.register_contract(None, ContractHealthRegistry);
+        let client = ContractHealthRegistryClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        let ok = Symbol::new(&env, "OK");
+        client.report_health(&admin, &a, &HealthStatus::Healthy, &ok);
+        client.report_health(&admin, &b, &HealthStatus::Degraded, &ok);
+
+        // A cycle between a and b resolves without recursion.
+        client.set_dependencies(&a, &Vec::from_array(&env, [b.clone()]));
+        client.set_dependencies(&b, &Vec::from_array(&env, [a.clone()]));
+
+        assert_eq!(client.composite_health(&a), HealthStatus::Degraded);
+        assert_eq!(client.composite_health(&b), HealthStatus::Degraded);
+    }
+
+    #[test]
+    fn test_set_dependencies_errors() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let app = Address::generate(&env);
+        let oracle = Address::generate(&env);
+
+        let contract_id = env.register_contract(None, ContractHealthRegistry);
+        let client = ContractHealthRegistryClient::new(&env, &contract_id);
+
+        let deps = Vec::from_array(&env, [oracle.clone()]);
+        assert_eq!(client.try_set_dependencies(&app, &deps), Err(Ok(Error::NotInitialized)));
+
+        client.init(&admin);
+        let deps = Vec::from_array(&env, [oracle.clone(), app.clone()]);
+        assert_eq!(client.try_set_dependencies(&app, &deps), Err(Ok(Error::SelfDependency)));
+        assert_eq!(client.dependencies(&app).len(), 0);
+    }
 }
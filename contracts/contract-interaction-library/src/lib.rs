@@ -15,7 +15,7 @@
 //!    call outcomes for auditability.
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Env, Map, String, Symbol,
+    contract, contractimpl, contracttype, symbol_short, Address, Env, Map, String, Symbol, Vec,
 };
 
 // ─── Types ────────────────────────────────────────────────────────────────────
@@ -45,6 +45,7 @@ pub enum DataKey {
     Registry,
     CallCounter,
     CallLog,
+    UpgradeHistory(String),
 }
 
 // ─── Events ───────────────────────────────────────────────────────────────────
@@ -53,6 +54,7 @@ const EVT_INIT: Symbol = symbol_short!("init");
 const EVT_REGISTER: Symbol = symbol_short!("register");
 const EVT_DEACTIVATE: Symbol = symbol_short!("deact");
 const EVT_LOGGED: Symbol = symbol_short!("logged");
+const EVT_UPGRADE: Symbol = symbol_short!("upgrade");
 
 // ─── Contract ─────────────────────────────────────────────────────────────────
 
@@ -100,6 +102,7 @@ impl ContractInteractionLibrary {
         };
         registry.set(name.clone(), entry);
         env.storage().instance().set(&DataKey::Registry, &registry);
+        Self::record_deployment(&env, &name, version, &address);
         env.events().publish((EVT_REGISTER,), (name, address, version));
     }
 
@@ -129,6 +132,17 @@ impl ContractInteractionLibrary {
         entry.active = true;
         registry.set(name.clone(), entry);
         env.storage().instance().set(&DataKey::Registry, &registry);
+        Self::record_deployment(&env, &name, new_version, &new_address);
+        env.events().publish((EVT_UPGRADE,), (name, new_address, new_version));
+    }
+
+    /// Every `(version, address)` deployed under `name`, oldest first: the
+    /// initial registration followed by each upgrade.
+    pub fn upgrade_history(env: Env, name: String) -> Vec<(u32, Address)> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UpgradeHistory(name))
+            .unwrap_or(Vec::new(&env))
     }
 
     // ── Lookup ────────────────────────────────────────────────────────────────
@@ -149,6 +163,19 @@ impl ContractInteractionLibrary {
         entry.address
     }
 
+    /// Resolve the address of an active registered contract whose version is
+    /// at least `min_version`, so callers never reach a stale deployment.
+    pub fn resolve_min_version(env: Env, name: String, min_version: u32) -> Address {
+        let entry = Self::get_contract(env.clone(), name);
+        if !entry.active {
+            panic!("Contract is inactive");
+        }
+        if entry.version < min_version {
+            panic!("Version too low");
+        }
+        entry.address
+    }
+
     // ── Call Logging ──────────────────────────────────────────────────────────
 
     /// Record a cross-contract call result and return its log ID.
@@ -189,6 +216,14 @@ impl ContractInteractionLibrary {
 
     // ── Helpers ───────────────────────────────────────────────────────────────
 
+    fn record_deployment(env: &Env, name: &String, version: u32, address: &Address) {
+        let key = DataKey::UpgradeHistory(name.clone());
+        let mut history: Vec<(u32, Address)> =
+            env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+        history.push_back((version, address.clone()));
+        env.storage().persistent().set(&key, &history);
+    }
+
     fn require_admin(env: &Env) {
         let admin: Address = env
             .storage()
@@ -271,6 +306,47 @@ mod tests {
         assert_eq!(client.resolve(&name), addr2);
     }
 
+    #[test]
+    fn test_resolve_min_version_accepts_newer_entry() {
+        let (env, client, _admin) = setup();
+        let addr = Address::generate(&env);
+        let name = String::from_str(&env, "vers");
+        client.register_contract(&name, &addr, &2);
+        assert_eq!(client.resolve_min_version(&name, &1), addr);
+        assert_eq!(client.resolve_min_version(&name, &2), addr);
+    }
+
+    #[test]
+    #[should_panic(expected = "Version too low")]
+    fn test_resolve_min_version_rejects_older_entry() {
+        let (env, client, _admin) = setup();
+        let addr = Address::generate(&env);
+        let name = String::from_str(&env, "vers");
+        client.register_contract(&name, &addr, &2);
+        client.resolve_min_version(&name, &3);
+    }
+
+    #[test]
+    fn test_upgrade_history_tracks_lineage() {
+        let (env, client, _admin) = setup();
+        let v1 = Address::generate(&env);
+        let v2 = Address::generate(&env);
+        let v3 = Address::generate(&env);
+        let name = String::from_str(&env, "game");
+        assert_eq!(client.upgrade_history(&name).len(), 0);
+
+        client.register_contract(&name, &v1, &1);
+        client.upgrade_contract(&name, &v2, &2);
+        client.upgrade_contract(&name, &v3, &3);
+
+        let history = client.upgrade_history(&name);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get(0).unwrap(), (1, v1));
+        assert_eq!(history.get(1).unwrap(), (2, v2));
+        assert_eq!(history.get(2).unwrap(), (3, v3.clone()));
+        assert_eq!(client.resolve(&name), v3);
+    }
+
     #[test]
     #[should_panic(expected = "Invalid name")]
     fn test_empty_name_rejected() {
@@ -12,6 +12,9 @@ use soroban_sdk::{
 const PERSISTENT_BUMP_LEDGERS: u32 = 518_400; // ~30 days
 const PERSISTENT_BUMP_THRESHOLD: u32 = PERSISTENT_BUMP_LEDGERS - 100_800; // Renew ~7 days early
 
+/// Maximum number of entries accepted by a single `register_batch` call.
+pub const MAX_BATCH_SIZE: u32 = 25;
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -26,6 +29,7 @@ pub enum Error {
     ContractAlreadyRegistered = 4,
     ContractNotFound = 5,
     InvalidVersion = 6,
+    BatchTooLarge = 7,
 }
 
 // ---------------------------------------------------------------------------
@@ -105,42 +109,42 @@ impl ContractMetadataRegistry {
         let admin = Self::require_admin(&env)?;
         admin.require_auth();
 
-        if version == 0 {
-            return Err(Error::InvalidVersion);
-        }
-
-        let key = DataKey::Metadata(contract_id.clone());
-        if env.storage().persistent().has(&key) {
-            return Err(Error::ContractAlreadyRegistered);
-        }
-
-        let record = MetadataRecord {
-            version,
-            schema_hash,
-            docs_uri,
-            updated_at: env.ledger().timestamp(),
-        };
+        Self::write_registration(&env, contract_id, version, schema_hash, docs_uri)
+    }
 
-        // Store current
-        env.storage().persistent().set(&key, &record);
-        env.storage().persistent().extend_ttl(
-            &key,
-            PERSISTENT_BUMP_THRESHOLD,
-            PERSISTENT_BUMP_LEDGERS,
-        );
+    /// Register initial metadata for several contracts at once. Admin only.
+    ///
+    /// Each entry is `(contract_id, version, schema_hash, docs_uri)`. Entries
+    /// for contracts that are already registered are skipped when
+    /// `skip_existing` is set, and otherwise fail the whole batch. Returns the
+    /// number of contracts registered.
+    pub fn register_batch(
+        env: Env,
+        entries: Vec<(Address, u32, BytesN<32>, String)>,
+        skip_existing: bool,
+    ) -> Result<u32, Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
 
-        // Store history
-        let history_key = DataKey::History(contract_id.clone(), version);
-        env.storage().persistent().set(&history_key, &record);
-        env.storage().persistent().extend_ttl(
-            &history_key,
-            PERSISTENT_BUMP_THRESHOLD,
-            PERSISTENT_BUMP_LEDGERS,
-        );
+        if entries.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
 
-        MetadataRegistered { contract_id, version }.publish(&env);
+        let mut registered = 0u32;
+        for (contract_id, version, schema_hash, docs_uri) in entries.iter() {
+            if skip_existing
+                && env
+                    .storage()
+                    .persistent()
+                    .has(&DataKey::Metadata(contract_id.clone()))
+            {
+                continue;
+            }
+            Self::write_registration(&env, contract_id, version, schema_hash, docs_uri)?;
+            registered += 1;
+        }
 
-        Ok(())
+        Ok(registered)
     }
 
     /// Update metadata for an existing contract (incrementing version).
@@ -228,6 +232,51 @@ impl ContractMetadataRegistry {
             .get(&DataKey::Admin)
             .ok_or(Error::NotInitialized)
     }
+
+    fn write_registration(
+        env: &Env,
+        contract_id: Address,
+        version: u32,
+        schema_hash: BytesN<32>,
+        docs_uri: String,
+    ) -> Result<(), Error> {
+        if version == 0 {
+            return Err(Error::InvalidVersion);
+        }
+
+        let key = DataKey::Metadata(contract_id.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::ContractAlreadyRegistered);
+        }
+
+        let record = MetadataRecord {
+            version,
+            schema_hash,
+            docs_uri,
+            updated_at: env.ledger().timestamp(),
+        };
+
+        // Store current
+        env.storage().persistent().set(&key, &record);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        // Store history
+        let history_key = DataKey::History(contract_id.clone(), version);
+        env.storage().persistent().set(&history_key, &record);
+        env.storage().persistent().extend_ttl(
+            &history_key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        MetadataRegistered { contract_id, version }.publish(env);
+
+        Ok(())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -237,7 +286,7 @@ impl ContractMetadataRegistry {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::{Address as _, Ledger, LedgerInfo}, Address, Env, BytesN, String};
+    use soroban_sdk::{testutils::{Address as _, Ledger, LedgerInfo}, vec, Address, Env, BytesN, String};
 
     struct Setup<'a> {
         _env: Env,
@@ -315,6 +364,70 @@ mod test {
         assert_eq!(history.get(1).unwrap().version, 2);
     }
 
+    #[test]
+    fn test_register_batch() {
+        let s = setup();
+        let e = &s._env;
+        let targets = [Address::generate(e), Address::generate(e), Address::generate(e)];
+        let entries = vec![
+            e,
+            (targets[0].clone(), 1u32, BytesN::from_array(e, &[1u8; 32]), String::from_str(e, "ipfs://a")),
+            (targets[1].clone(), 2u32, BytesN::from_array(e, &[2u8; 32]), String::from_str(e, "ipfs://b")),
+            (targets[2].clone(), 3u32, BytesN::from_array(e, &[3u8; 32]), String::from_str(e, "ipfs://c")),
+        ];
+
+        assert_eq!(s.client.register_batch(&entries, &false), 3);
+
+        for (i, target) in targets.iter().enumerate() {
+            let meta = s.client.metadata_of(target).unwrap();
+            assert_eq!(meta.version, i as u32 + 1);
+            assert_eq!(meta.schema_hash, BytesN::from_array(e, &[i as u8 + 1; 32]));
+        }
+    }
+
+    #[test]
+    fn test_register_batch_with_already_registered_entry() {
+        let s = setup();
+        let e = &s._env;
+        let existing = Address::generate(e);
+        let fresh = Address::generate(e);
+        let original_uri = String::from_str(e, "ipfs://orig");
+        s.client.register_metadata(&existing, &1, &BytesN::from_array(e, &[9u8; 32]), &original_uri);
+
+        let entries = vec![
+            e,
+            (fresh.clone(), 1u32, BytesN::from_array(e, &[1u8; 32]), String::from_str(e, "ipfs://new")),
+            (existing.clone(), 1u32, BytesN::from_array(e, &[2u8; 32]), String::from_str(e, "ipfs://dup")),
+        ];
+
+        // Without skipping, the duplicate fails the whole batch.
+        assert_eq!(
+            s.client.try_register_batch(&entries, &false),
+            Err(Ok(Error::ContractAlreadyRegistered))
+        );
+        assert!(s.client.metadata_of(&fresh).is_none());
+
+        // With skipping, only the fresh contract is registered.
+        assert_eq!(s.client.register_batch(&entries, &true), 1);
+        assert!(s.client.metadata_of(&fresh).is_some());
+        assert_eq!(s.client.metadata_of(&existing).unwrap().docs_uri, original_uri);
+    }
+
+    #[test]
+    fn test_register_batch_rejects_oversized_batch() {
+        let s = setup();
+        let e = &s._env;
+        let mut entries = Vec::new(e);
+        for _ in 0..=MAX_BATCH_SIZE {
+            entries.push_back((Address::generate(e), 1u32, BytesN::from_array(e, &[1u8; 32]), String::from_str(e, "ipfs://x")));
+        }
+
+        assert_eq!(
+            s.client.try_register_batch(&entries, &false),
+            Err(Ok(Error::BatchTooLarge))
+        );
+    }
+
     #[test]
     fn test_unauthorized_registration() {
         let s = setup();
@@ -1,12 +1,20 @@
 #![no_std]
 #![allow(unexpected_cfgs)]
 
-use soroban_sdk::{contract, contracterror, contractevent, contractimpl, contracttype, Address, Env};
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, Vec,
+};
 
 pub const PERSISTENT_BUMP_LEDGERS: u32 = 518_400;
-const FAILED_SETTLEMENT_ALERT_THRESHOLD: u64 = 3;
-const ERROR_RATE_ALERT_PERCENT: u64 = 20;
-const ERROR_RATE_MIN_SAMPLE: u64 = 10;
+const DEFAULT_FAILED_SETTLEMENT_THRESHOLD: u64 = 3;
+const DEFAULT_ERROR_RATE_PERCENT: u64 = 20;
+const DEFAULT_ERROR_RATE_MIN_SAMPLE: u64 = 10;
+const DEFAULT_SPIKE_WINDOW_SECONDS: u64 = 300;
+const DEFAULT_SPIKE_MULTIPLIER: u32 = 3;
+/// Number of completed windows kept for the trailing average.
+const SPIKE_HISTORY_WINDOWS: u32 = 6;
+/// Number of most recent event kinds kept for windowed counts.
+const RECENT_EVENTS_CAPACITY: u32 = 100;
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -16,6 +24,7 @@ pub enum Error {
     NotInitialized = 2,
     NotAuthorized = 3,
     DuplicateEvent = 4,
+    InvalidConfig = 5,
 }
 
 #[contracttype]
@@ -24,6 +33,10 @@ pub enum DataKey {
     Admin,
     Paused,
     Metrics,
+    SpikeConfig,
+    MonitoringConfig,
+    RateWindow,
+    RecentKinds,
     SeenEvent(u64),
 }
 
@@ -53,6 +66,38 @@ pub struct HealthSnapshot {
     pub paused: bool,
     pub high_error_rate: bool,
     pub failed_settlement_alert: bool,
+    pub spike_alert: bool,
+}
+
+/// Event-rate spike detection settings. A spike is flagged when the current
+/// window's event count exceeds `multiplier` times the trailing average of the
+/// last completed windows.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpikeConfig {
+    pub window_seconds: u64,
+    pub multiplier: u32,
+}
+
+/// Alert thresholds for `get_health`. A failed-settlement alert fires once
+/// `failed_settlement_threshold` failures are recorded; a high-error-rate
+/// alert fires once at least `error_rate_min_sample` events exist and errors
+/// make up `error_rate_percent` percent or more of them.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MonitoringConfig {
+    pub failed_settlement_threshold: u64,
+    pub error_rate_percent: u64,
+    pub error_rate_min_sample: u64,
+}
+
+/// Event counts for the current window and the most recent completed ones.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateWindow {
+    pub window_start: u64,
+    pub current_count: u64,
+    pub history: Vec<u64>,
 }
 
 #[contractevent]
@@ -96,13 +141,32 @@ impl ContractMonitoring {
         let mut metrics: Metrics = env.storage().instance().get(&DataKey::Metrics).unwrap_or_default();
         apply_event(&mut metrics, &kind);
 
+        let config = get_spike_config(&env);
+        let now = env.ledger().timestamp();
+        let mut rate = get_rate_window(&env, now);
+        roll_rate_window(&mut rate, now, config.window_seconds);
+        rate.current_count = rate.current_count.saturating_add(1);
+
+        let mut recent = get_recent_kinds(&env);
+        recent.push_back(kind.clone());
+        while recent.len() > RECENT_EVENTS_CAPACITY {
+            recent.pop_front();
+        }
+
         env.storage().instance().set(&DataKey::Metrics, &metrics);
+        env.storage().instance().set(&DataKey::RateWindow, &rate);
+        env.storage().instance().set(&DataKey::RecentKinds, &recent);
         env.storage().persistent().set(&seen_key, &true);
         env.storage().persistent().extend_ttl(&seen_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
 
         EventIngested { event_id, kind: kind.clone() }.publish(&env);
 
-        let health = evaluate_health(&metrics, is_paused(&env));
+        let health = evaluate_health(
+            &metrics,
+            &get_monitoring_config(&env),
+            is_paused(&env),
+            is_rate_spike(&rate, config.multiplier),
+        );
         if health.failed_settlement_alert {
             AlertRaised { alert: 1 }.publish(&env);
         }
@@ -112,6 +176,9 @@ impl ContractMonitoring {
         if health.paused {
             AlertRaised { alert: 3 }.publish(&env);
         }
+        if health.spike_alert {
+            AlertRaised { alert: 4 }.publish(&env);
+        }
 
         Ok(metrics)
     }
@@ -122,12 +189,78 @@ impl ContractMonitoring {
         Ok(())
     }
 
+    pub fn set_spike_config(
+        env: Env,
+        admin: Address,
+        window_seconds: u64,
+        multiplier: u32,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if window_seconds == 0 || multiplier == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        env.storage().instance().set(
+            &DataKey::SpikeConfig,
+            &SpikeConfig {
+                window_seconds,
+                multiplier,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get_spike_config(env: Env) -> SpikeConfig {
+        get_spike_config(&env)
+    }
+
+    pub fn set_monitoring_config(
+        env: Env,
+        admin: Address,
+        failed_settlement_threshold: u64,
+        error_rate_percent: u64,
+        error_rate_min_sample: u64,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if failed_settlement_threshold == 0 || error_rate_percent > 100 {
+            return Err(Error::InvalidConfig);
+        }
+        env.storage().instance().set(
+            &DataKey::MonitoringConfig,
+            &MonitoringConfig {
+                failed_settlement_threshold,
+                error_rate_percent,
+                error_rate_min_sample,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get_monitoring_config(env: Env) -> MonitoringConfig {
+        get_monitoring_config(&env)
+    }
+
+    /// Per-kind counts over the most recent `window` ingested events, capped at
+    /// the last 100 events kept in the ring buffer.
+    pub fn recent_counts(env: Env, window: u32) -> Metrics {
+        count_recent(&get_recent_kinds(&env), window)
+    }
+
     pub fn get_metrics(env: Env) -> Metrics {
         env.storage().instance().get(&DataKey::Metrics).unwrap_or_default()
     }
 
     pub fn get_health(env: Env) -> HealthSnapshot {
-        evaluate_health(&Self::get_metrics(env.clone()), is_paused(&env))
+        let config = get_spike_config(&env);
+        let now = env.ledger().timestamp();
+        let mut rate = get_rate_window(&env, now);
+        roll_rate_window(&mut rate, now, config.window_seconds);
+        let spike = is_rate_spike(&rate, config.multiplier);
+        evaluate_health(
+            &Self::get_metrics(env.clone()),
+            &get_monitoring_config(&env),
+            is_paused(&env),
+            spike,
+        )
     }
 }
 
@@ -147,6 +280,91 @@ fn is_paused(env: &Env) -> bool {
     env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
 }
 
+fn get_spike_config(env: &Env) -> SpikeConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::SpikeConfig)
+        .unwrap_or(SpikeConfig {
+            window_seconds: DEFAULT_SPIKE_WINDOW_SECONDS,
+            multiplier: DEFAULT_SPIKE_MULTIPLIER,
+        })
+}
+
+fn get_monitoring_config(env: &Env) -> MonitoringConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::MonitoringConfig)
+        .unwrap_or(MonitoringConfig {
+            failed_settlement_threshold: DEFAULT_FAILED_SETTLEMENT_THRESHOLD,
+            error_rate_percent: DEFAULT_ERROR_RATE_PERCENT,
+            error_rate_min_sample: DEFAULT_ERROR_RATE_MIN_SAMPLE,
+        })
+}
+
+fn get_rate_window(env: &Env, now: u64) -> RateWindow {
+    env.storage()
+        .instance()
+        .get(&DataKey::RateWindow)
+        .unwrap_or(RateWindow {
+            window_start: now,
+            current_count: 0,
+            history: Vec::new(env),
+        })
+}
+
+/// Close every window that has fully elapsed by `now`, recording its count
+/// (and a zero for each empty window skipped over) in the history.
+fn roll_rate_window(rate: &mut RateWindow, now: u64, window_seconds: u64) {
+    if now < rate.window_start.saturating_add(window_seconds) {
+        return;
+    }
+    let elapsed = (now - rate.window_start) / window_seconds;
+    rate.history.push_back(rate.current_count);
+    let empty = (elapsed - 1).min(SPIKE_HISTORY_WINDOWS as u64);
+    for _ in 0..empty {
+        rate.history.push_back(0);
+    }
+    while rate.history.len() > SPIKE_HISTORY_WINDOWS {
+        rate.history.pop_front();
+    }
+    rate.window_start = rate
+        .window_start
+        .saturating_add(elapsed.saturating_mul(window_seconds));
+    rate.current_count = 0;
+}
+
+fn is_rate_spike(rate: &RateWindow, multiplier: u32) -> bool {
+    let samples = rate.history.len() as u64;
+    if samples == 0 {
+        return false;
+    }
+    let total = rate
+        .history
+        .iter()
+        .fold(0u64, |acc, count| acc.saturating_add(count));
+    if total == 0 {
+        return false;
+    }
+    // current > multiplier * (total / samples), without the division.
+    rate.current_count.saturating_mul(samples) > total.saturating_mul(multiplier as u64)
+}
+
+fn get_recent_kinds(env: &Env) -> Vec<EventKind> {
+    env.storage()
+        .instance()
+        .get(&DataKey::RecentKinds)
+        .unwrap_or(Vec::new(env))
+}
+
+fn count_recent(recent: &Vec<EventKind>, window: u32) -> Metrics {
+    let mut metrics = Metrics::default();
+    let start = recent.len().saturating_sub(window);
+    for kind in recent.slice(start..).iter() {
+        apply_event(&mut metrics, &kind);
+    }
+    metrics
+}
+
 fn apply_event(metrics: &mut Metrics, kind: &EventKind) {
     metrics.total_events = metrics.total_events.saturating_add(1);
     match kind {
@@ -158,34 +376,98 @@ fn apply_event(metrics: &mut Metrics, kind: &EventKind) {
     }
 }
 
-fn evaluate_health(metrics: &Metrics, paused: bool) -> HealthSnapshot {
-    let high_error_rate = is_high_error_rate(metrics.error_events, metrics.total_events);
-    let failed_settlement_alert = metrics.settlement_failed >= FAILED_SETTLEMENT_ALERT_THRESHOLD;
+fn evaluate_health(
+    metrics: &Metrics,
+    config: &MonitoringConfig,
+    paused: bool,
+    spike_alert: bool,
+) -> HealthSnapshot {
+    let high_error_rate = is_high_error_rate(config, metrics.error_events, metrics.total_events);
+    let failed_settlement_alert = metrics.settlement_failed >= config.failed_settlement_threshold;
 
     HealthSnapshot {
         paused,
         high_error_rate,
         failed_settlement_alert,
+        spike_alert,
     }
 }
 
-fn is_high_error_rate(error_events: u64, total_events: u64) -> bool {
-    if total_events < ERROR_RATE_MIN_SAMPLE || total_events == 0 {
+fn is_high_error_rate(config: &MonitoringConfig, error_events: u64, total_events: u64) -> bool {
+    if total_events < config.error_rate_min_sample || total_events == 0 {
         return false;
     }
-    (error_events.saturating_mul(100) / total_events) >= ERROR_RATE_ALERT_PERCENT
+    (error_events.saturating_mul(100) / total_events) >= config.error_rate_percent
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+
+    fn set_time(env: &Env, timestamp: u64) {
+        env.ledger().with_mut(|l| l.timestamp = timestamp);
+    }
 
     #[test]
     fn marks_error_rate_when_threshold_crossed() {
-        assert!(!is_high_error_rate(1, 5));
-        assert!(!is_high_error_rate(1, 10));
-        assert!(is_high_error_rate(2, 10));
-        assert!(is_high_error_rate(3, 10));
+        let env = Env::default();
+        let config = get_monitoring_config(&env);
+        assert!(!is_high_error_rate(&config, 1, 5));
+        assert!(!is_high_error_rate(&config, 1, 10));
+        assert!(is_high_error_rate(&config, 2, 10));
+        assert!(is_high_error_rate(&config, 3, 10));
+    }
+
+    #[test]
+    fn stricter_config_alerts_on_fewer_failures() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let contract_id = env.register(ContractMonitoring, ());
+        let client = ContractMonitoringClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        client.ingest_event(&admin, &1, &EventKind::SettlementFailed);
+        client.ingest_event(&admin, &2, &EventKind::SettlementFailed);
+        assert!(!client.get_health().failed_settlement_alert);
+
+        client.set_monitoring_config(&admin, &2, &50, &4);
+        assert_eq!(
+            client.get_monitoring_config(),
+            MonitoringConfig {
+                failed_settlement_threshold: 2,
+                error_rate_percent: 50,
+                error_rate_min_sample: 4,
+            }
+        );
+        assert!(client.get_health().failed_settlement_alert);
+
+        // Two errors in four events meets the 50% rate at the smaller sample.
+        client.ingest_event(&admin, &3, &EventKind::Error);
+        assert!(!client.get_health().high_error_rate);
+        client.ingest_event(&admin, &4, &EventKind::Error);
+        assert!(client.get_health().high_error_rate);
+    }
+
+    #[test]
+    fn rejects_invalid_monitoring_config() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let contract_id = env.register(ContractMonitoring, ());
+        let client = ContractMonitoringClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        assert_eq!(
+            client.try_set_monitoring_config(&admin, &3, &101, &10),
+            Err(Ok(Error::InvalidConfig))
+        );
+        assert_eq!(
+            client.try_set_monitoring_config(&admin, &0, &20, &10),
+            Err(Ok(Error::InvalidConfig))
+        );
+        client.set_monitoring_config(&admin, &3, &100, &10);
     }
 
     #[test]
@@ -200,4 +482,126 @@ mod tests {
         assert_eq!(metrics.settlement_failed, 1);
         assert_eq!(metrics.error_events, 1);
     }
+
+    #[test]
+    fn spike_requires_baseline_and_multiple_of_average() {
+        let env = Env::default();
+        let mut rate = RateWindow {
+            window_start: 0,
+            current_count: 50,
+            history: Vec::new(&env),
+        };
+        assert!(!is_rate_spike(&rate, 3));
+
+        rate.history.push_back(2);
+        rate.history.push_back(4);
+        rate.current_count = 9;
+        assert!(!is_rate_spike(&rate, 3));
+        rate.current_count = 10;
+        assert!(is_rate_spike(&rate, 3));
+    }
+
+    #[test]
+    fn spike_alert_sets_on_burst_and_clears_when_rate_normalizes() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let contract_id = env.register(ContractMonitoring, ());
+        let client = ContractMonitoringClient::new(&env, &contract_id);
+        client.init(&admin);
+        client.set_spike_config(&admin, &100, &3);
+
+        let mut event_id = 0u64;
+        let mut ingest = |count: u32| {
+            for _ in 0..count {
+                event_id += 1;
+                client.ingest_event(&admin, &event_id, &EventKind::SettlementSuccess);
+            }
+        };
+
+        // Baseline: two events in each of two windows.
+        set_time(&env, 1_000);
+        ingest(2);
+        set_time(&env, 1_100);
+        ingest(2);
+        set_time(&env, 1_200);
+        ingest(6);
+        assert!(!client.get_health().spike_alert);
+
+        // A seventh event exceeds 3x the trailing average of 2.
+        ingest(1);
+        assert!(client.get_health().spike_alert);
+
+        // The burst window closes and the next one sees normal traffic.
+        set_time(&env, 1_300);
+        assert!(!client.get_health().spike_alert);
+        ingest(2);
+        assert!(!client.get_health().spike_alert);
+    }
+
+    #[test]
+    fn rejects_zero_spike_config() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let contract_id = env.register(ContractMonitoring, ());
+        let client = ContractMonitoringClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        assert_eq!(
+            client.try_set_spike_config(&admin, &0, &3),
+            Err(Ok(Error::InvalidConfig))
+        );
+        assert_eq!(
+            client.try_set_spike_config(&admin, &100, &0),
+            Err(Ok(Error::InvalidConfig))
+        );
+    }
+
+    #[test]
+    fn recent_counts_cover_only_latest_events() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let contract_id = env.register(ContractMonitoring, ());
+        let client = ContractMonitoringClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        let kinds = [
+            EventKind::Error,
+            EventKind::Error,
+            EventKind::SettlementFailed,
+            EventKind::SettlementSuccess,
+            EventKind::SettlementSuccess,
+            EventKind::Error,
+        ];
+        for (i, kind) in kinds.iter().enumerate() {
+            client.ingest_event(&admin, &(i as u64), kind);
+        }
+
+        let lifetime = client.get_metrics();
+        let recent = client.recent_counts(&3);
+        assert_eq!(recent.total_events, 3);
+        assert_eq!(recent.settlement_success, 2);
+        assert_eq!(recent.error_events, 1);
+        assert_eq!(recent.settlement_failed, 0);
+        assert_ne!(recent, lifetime);
+        assert!(recent.error_events <= lifetime.error_events);
+        assert!(recent.settlement_failed <= lifetime.settlement_failed);
+
+        // A window larger than the history covers everything ingested.
+        assert_eq!(client.recent_counts(&50), lifetime);
+    }
+
+    #[test]
+    fn recent_counts_bounded_by_buffer_capacity() {
+        let env = Env::default();
+        let mut recent = Vec::new(&env);
+        for _ in 0..RECENT_EVENTS_CAPACITY {
+            recent.push_back(EventKind::Error);
+        }
+        let metrics = count_recent(&recent, u32::MAX);
+        assert_eq!(metrics.total_events, RECENT_EVENTS_CAPACITY as u64);
+        assert_eq!(count_recent(&recent, 0), Metrics::default());
+    }
 }
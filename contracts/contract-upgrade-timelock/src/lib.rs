@@ -1,10 +1,20 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short,
-    Address, Env, Symbol,
+    contract, contracterror, contractimpl, contracttype, symbol_short, vec,
+    Address, BytesN, Env, IntoVal, Symbol, Vec,
 };
 
+// ── Errors ───────────────────────────────────────────────────────
+/// Reasons `validate_queue` reports for a proposal that would be rejected.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    EtaTooSoon = 2,
+}
+
 // ── Storage Keys ─────────────────────────────────────────────────
 #[contracttype]
 #[derive(Clone)]
@@ -13,6 +23,8 @@ pub enum DataKey {
     MinDelay,
     Upgrade(u64),       // upgrade_id → UpgradeRecord
     NextUpgradeId,
+    Batch(u64),         // batch_id → BatchUpgradeRecord
+    NextBatchId,
 }
 
 // ── Domain Types ─────────────────────────────────────────────────
@@ -35,6 +47,18 @@ pub struct UpgradeRecord {
     pub status: UpgradeStatus,
 }
 
+/// A set of upgrades that share one timelock and execute together.
+/// Each target is paired with the WASM hash it is upgraded to.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchUpgradeRecord {
+    pub batch_id: u64,
+    pub targets: Vec<(Address, BytesN<32>)>,
+    /// Earliest timestamp (in seconds) at which execute_batch_upgrade may be called.
+    pub eta: u64,
+    pub status: UpgradeStatus,
+}
+
 // ── Events ────────────────────────────────────────────────────────
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -57,6 +81,27 @@ pub struct UpgradeExecuted {
     pub target_contract: Address,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchQueued {
+    pub batch_id: u64,
+    pub target_count: u32,
+    pub eta: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchCancelled {
+    pub batch_id: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchExecuted {
+    pub batch_id: u64,
+    pub target_count: u32,
+}
+
 // ── Contract ──────────────────────────────────────────────────────
 #[contract]
 pub struct ContractUpgradeTimelock;
@@ -83,17 +128,7 @@ impl ContractUpgradeTimelock {
     ) -> u64 {
         Self::require_admin(&env);
 
-        let now = env.ledger().timestamp();
-        let min_delay: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::MinDelay)
-            .unwrap_or(0);
-
-        assert!(
-            eta >= now.checked_add(min_delay).expect("Overflow"),
-            "ETA too soon: must respect minimum delay"
-        );
+        assert!(eta_respects_delay(&env, eta), "ETA too soon: must respect minimum delay");
 
         let upgrade_id: u64 = env
             .storage()
@@ -121,6 +156,20 @@ impl ContractUpgradeTimelock {
         upgrade_id
     }
 
+    /// Dry-run `queue_upgrade`: apply the same checks to `eta` without
+    /// queueing anything or emitting events, so a UI can preview why a
+    /// proposal would be rejected. `queue_upgrade` places no restriction
+    /// on the target, so any `target_contract` passes.
+    pub fn validate_queue(env: Env, _target_contract: Address, eta: u64) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        if !eta_respects_delay(&env, eta) {
+            return Err(Error::EtaTooSoon);
+        }
+        Ok(())
+    }
+
     /// Cancel a queued upgrade. Admin-only.
     pub fn cancel_upgrade(env: Env, upgrade_id: u64) {
         Self::require_admin(&env);
@@ -172,6 +221,109 @@ impl ContractUpgradeTimelock {
         );
     }
 
+    /// Queue a batch of upgrades under a single timelock. Admin-only.
+    /// `eta` must be at least `now + min_delay`.
+    pub fn queue_batch_upgrade(
+        env: Env,
+        targets: Vec<(Address, BytesN<32>)>,
+        eta: u64,
+    ) -> u64 {
+        Self::require_admin(&env);
+
+        assert!(!targets.is_empty(), "Batch must contain at least one target");
+
+        assert!(eta_respects_delay(&env, eta), "ETA too soon: must respect minimum delay");
+
+        let batch_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextBatchId)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextBatchId, &batch_id.checked_add(1).expect("Overflow"));
+
+        let target_count = targets.len();
+        let record = BatchUpgradeRecord {
+            batch_id,
+            targets,
+            eta,
+            status: UpgradeStatus::Queued,
+        };
+        env.storage().persistent().set(&DataKey::Batch(batch_id), &record);
+
+        env.events().publish(
+            (symbol_short!("bqueued"),),
+            BatchQueued { batch_id, target_count, eta },
+        );
+
+        batch_id
+    }
+
+    /// Cancel a queued batch. Admin-only.
+    pub fn cancel_batch_upgrade(env: Env, batch_id: u64) {
+        Self::require_admin(&env);
+
+        let mut record = Self::batch_state(env.clone(), batch_id);
+        assert!(
+            record.status == UpgradeStatus::Queued,
+            "Batch is not in Queued state"
+        );
+
+        record.status = UpgradeStatus::Cancelled;
+        env.storage().persistent().set(&DataKey::Batch(batch_id), &record);
+
+        env.events().publish(
+            (symbol_short!("bcancel"),),
+            BatchCancelled { batch_id },
+        );
+    }
+
+    /// Execute every upgrade in a batch after the timelock has elapsed.
+    /// Admin-only.
+    ///
+    /// Each target is upgraded by invoking its `upgrade(new_wasm_hash)`
+    /// entry point. If any call fails the whole invocation traps, so the
+    /// batch either applies in full or leaves every target untouched and
+    /// stays `Queued`.
+    pub fn execute_batch_upgrade(env: Env, batch_id: u64) {
+        Self::require_admin(&env);
+
+        let mut record = Self::batch_state(env.clone(), batch_id);
+        assert!(
+            record.status == UpgradeStatus::Queued,
+            "Batch is not in Queued state"
+        );
+
+        let now = env.ledger().timestamp();
+        assert!(now >= record.eta, "Timelock has not elapsed");
+
+        record.status = UpgradeStatus::Executed;
+        env.storage().persistent().set(&DataKey::Batch(batch_id), &record);
+
+        let upgrade_fn = Symbol::new(&env, "upgrade");
+        for (target, wasm_hash) in record.targets.iter() {
+            env.invoke_contract::<()>(
+                &target,
+                &upgrade_fn,
+                vec![&env, wasm_hash.into_val(&env)],
+            );
+        }
+
+        env.events().publish(
+            (symbol_short!("bexec"),),
+            BatchExecuted { batch_id, target_count: record.targets.len() },
+        );
+    }
+
+    /// Read the state of a batch record.
+    pub fn batch_state(env: Env, batch_id: u64) -> BatchUpgradeRecord {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Batch(batch_id))
+            .expect("Batch not found")
+    }
+
     /// Read the state of an upgrade record.
     pub fn upgrade_state(env: Env, upgrade_id: u64) -> UpgradeRecord {
         env.storage()
@@ -191,15 +343,45 @@ impl ContractUpgradeTimelock {
     }
 }
 
+/// Whether `eta` is at least `now + min_delay`.
+fn eta_respects_delay(env: &Env, eta: u64) -> bool {
+    let min_delay: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MinDelay)
+        .unwrap_or(0);
+    env.ledger()
+        .timestamp()
+        .checked_add(min_delay)
+        .is_some_and(|earliest| eta >= earliest)
+}
+
 // ── Tests ─────────────────────────────────────────────────────────
 #[cfg(test)]
 mod test {
     use super::*;
     use soroban_sdk::{
-        testutils::{Address as _, Ledger, LedgerInfo},
-        Env, Symbol,
+        contract, contractimpl,
+        testutils::{Address as _, Events, Ledger, LedgerInfo},
+        BytesN, Env, Symbol,
     };
 
+    /// Stand-in for an upgradeable contract: records the hash it was
+    /// asked to upgrade to instead of swapping its WASM.
+    #[contract]
+    pub struct MockUpgradeable;
+
+    #[contractimpl]
+    impl MockUpgradeable {
+        pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+            env.storage().instance().set(&symbol_short!("wasm"), &new_wasm_hash);
+        }
+
+        pub fn wasm(env: Env) -> Option<BytesN<32>> {
+            env.storage().instance().get(&symbol_short!("wasm"))
+        }
+    }
+
     fn set_time(env: &Env, ts: u64) {
         env.ledger().set(LedgerInfo {
             timestamp: ts,
@@ -305,6 +487,35 @@ mod test {
         client.queue_upgrade(&target, &Symbol::new(&env, "H4"), &500u64);
     }
 
+    #[test]
+    fn test_validate_queue_previews_eta() {
+        let env = Env::default();
+        env.mock_all_auths();
+        set_time(&env, 1000);
+
+        let admin = Address::generate(&env);
+        let target = Address::generate(&env);
+        let contract_id = env.register_contract(None, ContractUpgradeTimelock);
+        let client = ContractUpgradeTimelockClient::new(&env, &contract_id);
+
+        assert_eq!(
+            client.try_validate_queue(&target, &(1000 + 86400)),
+            Err(Ok(Error::NotInitialized))
+        );
+        client.init(&admin, &86400u64);
+
+        assert_eq!(
+            client.try_validate_queue(&target, &(1000 + 86400 - 1)),
+            Err(Ok(Error::EtaTooSoon))
+        );
+        client.validate_queue(&target, &(1000 + 86400));
+        assert_eq!(env.events().all().filter_by_contract(&contract_id).events().len(), 0);
+
+        // Nothing was queued: the first real proposal still gets id 0.
+        let uid = client.queue_upgrade(&target, &Symbol::new(&env, "H5"), &(1000 + 86400));
+        assert_eq!(uid, 0);
+    }
+
     #[test]
     #[should_panic(expected = "Already initialized")]
     fn test_double_init_fails() {
@@ -316,4 +527,89 @@ mod test {
         client.init(&admin, &0u64);
         client.init(&admin, &0u64);
     }
+
+    #[test]
+    fn test_batch_queue_and_execute() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        set_time(&env, 1000);
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register_contract(None, ContractUpgradeTimelock);
+        let client = ContractUpgradeTimelockClient::new(&env, &contract_id);
+        client.init(&admin, &86400u64);
+
+        let target_a = env.register_contract(None, MockUpgradeable);
+        let target_b = env.register_contract(None, MockUpgradeable);
+        let hash_a = BytesN::from_array(&env, &[1u8; 32]);
+        let hash_b = BytesN::from_array(&env, &[2u8; 32]);
+
+        let bid = client.queue_batch_upgrade(
+            &vec![
+                &env,
+                (target_a.clone(), hash_a.clone()),
+                (target_b.clone(), hash_b.clone()),
+            ],
+            &(1000 + 86400),
+        );
+        assert_eq!(client.batch_state(&bid).targets.len(), 2);
+
+        // Too early: nothing is applied.
+        set_time(&env, 1000 + 86400 - 1);
+        assert!(client.try_execute_batch_upgrade(&bid).is_err());
+
+        set_time(&env, 1000 + 86400);
+        client.execute_batch_upgrade(&bid);
+
+        assert_eq!(client.batch_state(&bid).status, UpgradeStatus::Executed);
+        assert_eq!(MockUpgradeableClient::new(&env, &target_a).wasm(), Some(hash_a));
+        assert_eq!(MockUpgradeableClient::new(&env, &target_b).wasm(), Some(hash_b));
+    }
+
+    #[test]
+    fn test_batch_rolls_back_when_a_target_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        set_time(&env, 1000);
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register_contract(None, ContractUpgradeTimelock);
+        let client = ContractUpgradeTimelockClient::new(&env, &contract_id);
+        client.init(&admin, &3600u64);
+
+        let good = env.register_contract(None, MockUpgradeable);
+        // Not a contract, so its upgrade call fails.
+        let bad = Address::generate(&env);
+
+        let bid = client.queue_batch_upgrade(
+            &vec![
+                &env,
+                (good.clone(), BytesN::from_array(&env, &[7u8; 32])),
+                (bad, BytesN::from_array(&env, &[8u8; 32])),
+            ],
+            &(1000 + 3600),
+        );
+
+        set_time(&env, 1000 + 3600);
+        assert!(client.try_execute_batch_upgrade(&bid).is_err());
+
+        assert_eq!(client.batch_state(&bid).status, UpgradeStatus::Queued);
+        assert_eq!(MockUpgradeableClient::new(&env, &good).wasm(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Batch must contain at least one target")]
+    fn test_empty_batch_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+        set_time(&env, 1000);
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register_contract(None, ContractUpgradeTimelock);
+        let client = ContractUpgradeTimelockClient::new(&env, &contract_id);
+        client.init(&admin, &0u64);
+        client.queue_batch_upgrade(&Vec::new(&env), &1000u64);
+    }
 }
@@ -2,7 +2,7 @@
 
 use soroban_sdk::{
     contract, contracterror, contractevent, contractimpl, contracttype, Address,
-    Env, Symbol,
+    Env, Symbol, Vec,
 };
 
 // ---------------------------------------------------------------------------
@@ -12,6 +12,9 @@ use soroban_sdk::{
 const PERSISTENT_BUMP_LEDGERS: u32 = 518_400; // ~30 days
 const PERSISTENT_BUMP_THRESHOLD: u32 = PERSISTENT_BUMP_LEDGERS - 100_800; // Renew ~7 days early
 
+/// Upper bound on the audit log size, keeping the log a single small entry.
+pub const MAX_AUDIT_LOG_SIZE: u32 = 100;
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -24,6 +27,7 @@ pub enum Error {
     NotInitialized = 2,
     NotAuthorized = 3,
     CallDenied = 4,
+    InvalidLogSize = 5,
 }
 
 // ---------------------------------------------------------------------------
@@ -38,11 +42,34 @@ pub struct PolicyKey {
     pub selector: Symbol,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RolePolicyKey {
+    pub role: Symbol,
+    pub target: Address,
+    pub selector: Symbol,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallCheckRecord {
+    pub source: Address,
+    pub target: Address,
+    pub selector: Symbol,
+    pub allowed: bool,
+    pub timestamp: u64,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Admin,
     Policy(PolicyKey),
+    Role(Address),
+    RolePolicy(RolePolicyKey),
+    AuditLogSize,
+    AuditLog,
+    Auditor(Address),
 }
 
 // ---------------------------------------------------------------------------
@@ -68,6 +95,43 @@ pub struct CallDenied {
     pub selector: Symbol,
 }
 
+#[contractevent]
+pub struct AuditLogConfigured {
+    pub size: u32,
+}
+
+#[contractevent]
+pub struct AuditorSet {
+    pub auditor: Address,
+    pub allowed: bool,
+}
+
+#[contractevent]
+pub struct RoleAssigned {
+    pub address: Address,
+    pub role: Symbol,
+}
+
+#[contractevent]
+pub struct RoleRevoked {
+    pub address: Address,
+    pub role: Symbol,
+}
+
+#[contractevent]
+pub struct RoleCallAllowed {
+    pub role: Symbol,
+    pub target: Address,
+    pub selector: Symbol,
+}
+
+#[contractevent]
+pub struct RoleCallDisallowed {
+    pub role: Symbol,
+    pub target: Address,
+    pub selector: Symbol,
+}
+
 // ---------------------------------------------------------------------------
 // Contract
 // ---------------------------------------------------------------------------
@@ -119,7 +183,8 @@ impl CrossContractCallGuard {
         Ok(())
     }
 
-    /// Deny (remove permission for) a specific cross-contract call. Admin only.
+    /// Deny a specific cross-contract call. Admin only. The deny is stored
+    /// explicitly, so it also overrides any role policy the source holds.
     pub fn deny_call(
         env: Env,
         source: Address,
@@ -135,33 +200,232 @@ impl CrossContractCallGuard {
             selector: selector.clone(),
         });
 
-        env.storage().persistent().remove(&key);
+        env.storage().persistent().set(&key, &false);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
 
         CallDenied { source, target, selector }.publish(&env);
 
         Ok(())
     }
 
+    /// Assign `address` a role, replacing any previous role. Admin only.
+    pub fn assign_role(env: Env, address: Address, role: Symbol) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        let key = DataKey::Role(address.clone());
+        env.storage().persistent().set(&key, &role);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        RoleAssigned { address, role }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Remove `address`'s role, if it has one. Admin only.
+    pub fn revoke_role(env: Env, address: Address) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        let key = DataKey::Role(address.clone());
+        let role: Option<Symbol> = env.storage().persistent().get(&key);
+        if let Some(role) = role {
+            env.storage().persistent().remove(&key);
+            RoleRevoked { address, role }.publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Allow every address holding `role` to call `selector` on `target`.
+    /// Admin only.
+    pub fn allow_role_call(
+        env: Env,
+        role: Symbol,
+        target: Address,
+        selector: Symbol,
+    ) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        let key = DataKey::RolePolicy(RolePolicyKey {
+            role: role.clone(),
+            target: target.clone(),
+            selector: selector.clone(),
+        });
+
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        RoleCallAllowed { role, target, selector }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Withdraw a role's permission to call `selector` on `target`. Admin
+    /// only.
+    pub fn disallow_role_call(
+        env: Env,
+        role: Symbol,
+        target: Address,
+        selector: Symbol,
+    ) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        env.storage().persistent().remove(&DataKey::RolePolicy(RolePolicyKey {
+            role: role.clone(),
+            target: target.clone(),
+            selector: selector.clone(),
+        }));
+
+        RoleCallDisallowed { role, target, selector }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Return the role assigned to `address`, if any.
+    pub fn role_of(env: Env, address: Address) -> Option<Symbol> {
+        env.storage().persistent().get(&DataKey::Role(address))
+    }
+
     /// Assert that a call is allowed. Traps/Errs if not found or explicitly denied.
+    ///
+    /// An exact `(source, target, selector)` policy, allow or deny, decides
+    /// the call; without one, the call is allowed if the source's role has a
+    /// matching role policy. Nothing is logged; use `check_and_log` for an
+    /// audited check.
     pub fn assert_allowed(
         env: Env,
         source: Address,
         target: Address,
         selector: Symbol,
     ) -> Result<(), Error> {
-        let key = DataKey::Policy(PolicyKey {
-            source,
-            target,
-            selector,
-        });
-
-        if !env.storage().persistent().get::<_, bool>(&key).unwrap_or(false) {
+        if !Self::is_allowed(&env, &source, &target, &selector) {
             return Err(Error::CallDenied);
         }
 
         Ok(())
     }
 
+    /// Same check as `assert_allowed`, but returns the outcome instead of
+    /// erroring. Read-only: nothing is logged or emitted.
+    pub fn check_call(env: Env, source: Address, target: Address, selector: Symbol) -> bool {
+        Self::is_allowed(&env, &source, &target, &selector)
+    }
+
+    /// Same check as `check_call`, recording the outcome in the audit log
+    /// (when enabled) and reporting it with `CallAllowed` or `CallDenied`.
+    /// A denial is returned as `false` rather than an error, so its record
+    /// is kept. `auditor` must be the admin or registered with
+    /// `set_auditor`, so the bounded log cannot be flooded by anyone else.
+    pub fn check_and_log(
+        env: Env,
+        auditor: Address,
+        source: Address,
+        target: Address,
+        selector: Symbol,
+    ) -> Result<bool, Error> {
+        let admin = Self::require_admin(&env)?;
+        auditor.require_auth();
+        if auditor != admin && !Self::is_auditor(env.clone(), auditor) {
+            return Err(Error::NotAuthorized);
+        }
+
+        let allowed = Self::is_allowed(&env, &source, &target, &selector);
+        Self::record_check(&env, source, target, selector, allowed);
+
+        Ok(allowed)
+    }
+
+    /// Allow or disallow `auditor` to call `check_and_log`. Admin only.
+    pub fn set_auditor(env: Env, auditor: Address, allowed: bool) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        let key = DataKey::Auditor(auditor.clone());
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(
+                &key,
+                PERSISTENT_BUMP_THRESHOLD,
+                PERSISTENT_BUMP_LEDGERS,
+            );
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        AuditorSet { auditor, allowed }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Query whether `auditor` may call `check_and_log`.
+    pub fn is_auditor(env: Env, auditor: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Auditor(auditor))
+    }
+
+    /// Enable the audit log, keeping the newest `size` checks, or disable it
+    /// with `size == 0`. Shrinking drops the oldest records. Admin only.
+    pub fn set_audit_log(env: Env, size: u32) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        if size > MAX_AUDIT_LOG_SIZE {
+            return Err(Error::InvalidLogSize);
+        }
+
+        env.storage().instance().set(&DataKey::AuditLogSize, &size);
+
+        let mut log: Vec<CallCheckRecord> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::AuditLog)
+            .unwrap_or(Vec::new(&env));
+        while log.len() > size {
+            log.pop_front();
+        }
+        if log.is_empty() {
+            env.storage().persistent().remove(&DataKey::AuditLog);
+        } else {
+            env.storage().persistent().set(&DataKey::AuditLog, &log);
+        }
+
+        AuditLogConfigured { size }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Return up to `limit` of the most recent logged checks, newest first.
+    pub fn recent_checks(env: Env, limit: u32) -> Vec<CallCheckRecord> {
+        let log: Vec<CallCheckRecord> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::AuditLog)
+            .unwrap_or(Vec::new(&env));
+
+        let mut recent = Vec::new(&env);
+        for record in log.iter().rev() {
+            if recent.len() >= limit {
+                break;
+            }
+            recent.push_back(record);
+        }
+        recent
+    }
+
     /// Check the state of a specific policy.
     pub fn policy_state(
         env: Env,
@@ -188,6 +452,70 @@ impl CrossContractCallGuard {
             .get(&DataKey::Admin)
             .ok_or(Error::NotInitialized)
     }
+
+    fn is_allowed(env: &Env, source: &Address, target: &Address, selector: &Symbol) -> bool {
+        let key = DataKey::Policy(PolicyKey {
+            source: source.clone(),
+            target: target.clone(),
+            selector: selector.clone(),
+        });
+
+        if let Some(allowed) = env.storage().persistent().get::<_, bool>(&key) {
+            return allowed;
+        }
+
+        let role: Option<Symbol> = env.storage().persistent().get(&DataKey::Role(source.clone()));
+        let Some(role) = role else {
+            return false;
+        };
+
+        let role_key = DataKey::RolePolicy(RolePolicyKey {
+            role,
+            target: target.clone(),
+            selector: selector.clone(),
+        });
+
+        env.storage().persistent().get::<_, bool>(&role_key).unwrap_or(false)
+    }
+
+    fn record_check(env: &Env, source: Address, target: Address, selector: Symbol, allowed: bool) {
+        let size: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AuditLogSize)
+            .unwrap_or(0);
+        if size == 0 {
+            return;
+        }
+
+        let mut log: Vec<CallCheckRecord> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::AuditLog)
+            .unwrap_or(Vec::new(env));
+        log.push_back(CallCheckRecord {
+            source: source.clone(),
+            target: target.clone(),
+            selector: selector.clone(),
+            allowed,
+            timestamp: env.ledger().timestamp(),
+        });
+        while log.len() > size {
+            log.pop_front();
+        }
+        env.storage().persistent().set(&DataKey::AuditLog, &log);
+        env.storage().persistent().extend_ttl(
+            &DataKey::AuditLog,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        if allowed {
+            CallAllowed { source, target, selector }.publish(env);
+        } else {
+            CallDenied { source, target, selector }.publish(env);
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -197,7 +525,10 @@ impl CrossContractCallGuard {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env, symbol_short};
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger},
+        Address, Env, Event as _, symbol_short, vec,
+    };
 
     struct Setup<'a> {
         _env: Env,
@@ -262,4 +593,226 @@ mod test {
         let result = s.client.try_assert_allowed(&source, &target, &selector);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_role_policy_allows_role_members() {
+        let s = setup();
+        let game_a = Address::generate(&s._env);
+        let game_b = Address::generate(&s._env);
+        let outsider = Address::generate(&s._env);
+        let treasury = Address::generate(&s._env);
+        let role = symbol_short!("game");
+        let selector = symbol_short!("credit");
+
+        s.client.assign_role(&game_a, &role);
+        s.client.assign_role(&game_b, &role);
+        assert_eq!(s.client.role_of(&game_a), Some(role.clone()));
+
+        // No role policy yet
+        assert_eq!(
+            s.client.try_assert_allowed(&game_a, &treasury, &selector),
+            Err(Ok(Error::CallDenied))
+        );
+
+        s.client.allow_role_call(&role, &treasury, &selector);
+
+        assert!(s.client.try_assert_allowed(&game_a, &treasury, &selector).is_ok());
+        assert!(s.client.try_assert_allowed(&game_b, &treasury, &selector).is_ok());
+        assert_eq!(
+            s.client.try_assert_allowed(&outsider, &treasury, &selector),
+            Err(Ok(Error::CallDenied))
+        );
+
+        // The role policy is scoped to its selector.
+        assert_eq!(
+            s.client.try_assert_allowed(&game_a, &treasury, &symbol_short!("debit")),
+            Err(Ok(Error::CallDenied))
+        );
+    }
+
+    #[test]
+    fn test_explicit_deny_overrides_role_and_grants_can_be_revoked() {
+        let s = setup();
+        let game_a = Address::generate(&s._env);
+        let game_b = Address::generate(&s._env);
+        let treasury = Address::generate(&s._env);
+        let role = symbol_short!("game");
+        let selector = symbol_short!("credit");
+
+        s.client.assign_role(&game_a, &role);
+        s.client.assign_role(&game_b, &role);
+        s.client.allow_role_call(&role, &treasury, &selector);
+
+        // A per-pair deny wins over the role grant, for that source only.
+        s.client.deny_call(&game_a, &treasury, &selector);
+        assert_eq!(
+            s.client.try_assert_allowed(&game_a, &treasury, &selector),
+            Err(Ok(Error::CallDenied))
+        );
+        assert!(s.client.try_assert_allowed(&game_b, &treasury, &selector).is_ok());
+
+        // Revoking the role removes game_b's access.
+        s.client.revoke_role(&game_b);
+        assert_eq!(s.client.role_of(&game_b), None);
+        assert_eq!(
+            s.client.try_assert_allowed(&game_b, &treasury, &selector),
+            Err(Ok(Error::CallDenied))
+        );
+
+        // Withdrawing the role policy removes it for every member.
+        let game_c = Address::generate(&s._env);
+        s.client.assign_role(&game_c, &role);
+        assert!(s.client.try_assert_allowed(&game_c, &treasury, &selector).is_ok());
+        s.client.disallow_role_call(&role, &treasury, &selector);
+        assert_eq!(
+            s.client.try_assert_allowed(&game_c, &treasury, &selector),
+            Err(Ok(Error::CallDenied))
+        );
+    }
+
+    #[test]
+    fn test_audit_log_records_allowed_and_denied_checks() {
+        let s = setup();
+        let source = Address::generate(&s._env);
+        let target = Address::generate(&s._env);
+        let allowed_sel = symbol_short!("credit");
+        let denied_sel = symbol_short!("debit");
+
+        let auditor = Address::generate(&s._env);
+        s.client.set_auditor(&auditor, &true);
+        assert!(s.client.is_auditor(&auditor));
+
+        // Logging is off by default.
+        s.client.allow_call(&source, &target, &allowed_sel);
+        assert!(s.client.check_and_log(&auditor, &source, &target, &allowed_sel));
+        assert_eq!(s.client.recent_checks(&10).len(), 0);
+
+        s.client.set_audit_log(&10);
+        s._env.ledger().with_mut(|l| l.timestamp = 1_000);
+        assert!(s.client.check_and_log(&auditor, &source, &target, &allowed_sel));
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(events.events().len(), 1);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &CallAllowed {
+                source: source.clone(),
+                target: target.clone(),
+                selector: allowed_sel.clone(),
+            }
+            .to_xdr(&s._env, &s.client.address)
+        );
+
+        // A denial does not error, so its record is kept.
+        s._env.ledger().with_mut(|l| l.timestamp = 2_000);
+        assert!(!s.client.check_and_log(&auditor, &source, &target, &denied_sel));
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &CallDenied {
+                source: source.clone(),
+                target: target.clone(),
+                selector: denied_sel.clone(),
+            }
+            .to_xdr(&s._env, &s.client.address)
+        );
+
+        // assert_allowed is permissionless and never writes the log.
+        s.client.assert_allowed(&source, &target, &allowed_sel);
+
+        let checks = s.client.recent_checks(&10);
+        assert_eq!(
+            checks,
+            vec![
+                &s._env,
+                CallCheckRecord {
+                    source: source.clone(),
+                    target: target.clone(),
+                    selector: denied_sel,
+                    allowed: false,
+                    timestamp: 2_000,
+                },
+                CallCheckRecord {
+                    source,
+                    target,
+                    selector: allowed_sel,
+                    allowed: true,
+                    timestamp: 1_000,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_and_log_requires_auditor() {
+        let s = setup();
+        let source = Address::generate(&s._env);
+        let target = Address::generate(&s._env);
+        let selector = symbol_short!("credit");
+        s.client.set_audit_log(&10);
+
+        let outsider = Address::generate(&s._env);
+        assert_eq!(
+            s.client.try_check_and_log(&outsider, &source, &target, &selector),
+            Err(Ok(Error::NotAuthorized))
+        );
+        assert_eq!(s.client.recent_checks(&10).len(), 0);
+
+        // The admin may always log; a revoked auditor no longer can.
+        assert!(!s.client.check_and_log(&s._admin, &source, &target, &selector));
+        s.client.set_auditor(&outsider, &true);
+        s.client.set_auditor(&outsider, &false);
+        assert!(!s.client.is_auditor(&outsider));
+        assert_eq!(
+            s.client.try_check_and_log(&outsider, &source, &target, &selector),
+            Err(Ok(Error::NotAuthorized))
+        );
+        assert_eq!(s.client.recent_checks(&10).len(), 1);
+    }
+
+    #[test]
+    fn test_check_call_is_read_only() {
+        let s = setup();
+        let source = Address::generate(&s._env);
+        let target = Address::generate(&s._env);
+
+        s.client.set_audit_log(&10);
+        s.client.allow_call(&source, &target, &symbol_short!("credit"));
+        assert!(s.client.check_call(&source, &target, &symbol_short!("credit")));
+        assert!(!s.client.check_call(&source, &target, &symbol_short!("debit")));
+
+        assert_eq!(s.client.recent_checks(&10).len(), 0);
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(events.events().len(), 0);
+    }
+
+    #[test]
+    fn test_audit_log_is_bounded() {
+        let s = setup();
+        let source = Address::generate(&s._env);
+        let target = Address::generate(&s._env);
+
+        s.client.set_audit_log(&3);
+        s.client.allow_call(&source, &target, &symbol_short!("swap"));
+        for i in 0..5u64 {
+            s._env.ledger().with_mut(|l| l.timestamp = i);
+            s.client.check_and_log(&s._admin, &source, &target, &symbol_short!("swap"));
+        }
+
+        let checks = s.client.recent_checks(&10);
+        assert_eq!(checks.len(), 3);
+        assert_eq!(checks.get(0).unwrap().timestamp, 4);
+        assert_eq!(checks.get(2).unwrap().timestamp, 2);
+        assert_eq!(s.client.recent_checks(&1).len(), 1);
+
+        // Shrinking keeps only the newest records.
+        s.client.set_audit_log(&1);
+        let checks = s.client.recent_checks(&10);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks.get(0).unwrap().timestamp, 4);
+
+        assert_eq!(
+            s.client.try_set_audit_log(&(MAX_AUDIT_LOG_SIZE + 1)),
+            Err(Ok(Error::InvalidLogSize))
+        );
+    }
 }
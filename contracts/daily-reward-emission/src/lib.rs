@@ -2,7 +2,7 @@
 
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env, Symbol,
+    token, Address, Env, Symbol, Vec,
 };
 
 // ── Storage Keys ─────────────────────────────────────────────────
@@ -36,6 +36,8 @@ pub struct EmissionEpochState {
     pub current_epoch: u64,
     pub epoch_start_time: u64,
     pub total_emitted: i128,
+    /// Epoch boundaries fall on `epoch_anchor + k * epoch_duration`.
+    pub epoch_anchor: u64,
 }
 
 // ── Events ────────────────────────────────────────────────────────
@@ -79,15 +81,30 @@ impl DailyRewardEmission {
     }
 
     /// Configure or update an emission schedule. Admin-only.
-    pub fn configure_emission(env: Env, schedule_id: Symbol, config: EmissionConfig) {
+    ///
+    /// Epoch boundaries are aligned to `epoch_anchor + k * epoch_duration`
+    /// (e.g. a past UTC midnight for daily epochs), so the first epoch runs
+    /// only until the next aligned boundary and may be shorter than
+    /// `epoch_duration`. Passing the current time keeps epochs aligned to
+    /// configuration time.
+    pub fn configure_emission(
+        env: Env,
+        schedule_id: Symbol,
+        config: EmissionConfig,
+        epoch_anchor: u64,
+    ) {
         Self::require_admin(&env);
         assert!(config.rewards_per_epoch > 0, "Rewards per epoch must be positive");
         assert!(config.epoch_duration > 0, "Epoch duration must be positive");
 
+        let now = env.ledger().timestamp();
+        assert!(epoch_anchor <= now, "Epoch anchor must not be in the future");
+
         let epoch_state = EmissionEpochState {
             current_epoch: 0,
-            epoch_start_time: env.ledger().timestamp(),
+            epoch_start_time: now,
             total_emitted: 0,
+            epoch_anchor,
         };
 
         env.storage().persistent().set(&DataKey::Schedule(schedule_id.clone()), &config);
@@ -106,6 +123,8 @@ impl DailyRewardEmission {
 
     /// Finalize the current epoch and advance to the next. Admin-only.
     /// Emits rewards from the reward pool into the contract for distribution.
+    /// The epoch ends at the first anchor-aligned boundary after it started;
+    /// the next epoch starts at that boundary rather than at call time.
     pub fn emit_for_epoch(env: Env, schedule_id: Symbol) -> u64 {
         Self::require_admin(&env);
 
@@ -117,42 +136,52 @@ impl DailyRewardEmission {
 
         assert!(config.active, "Schedule is inactive");
 
-        let mut epoch_state: EmissionEpochState = env
+        let epoch_state: EmissionEpochState = env
             .storage()
             .persistent()
             .get(&DataKey::EpochState(schedule_id.clone()))
             .expect("Epoch state not found");
 
         let now = env.ledger().timestamp();
-        assert!(
-            now >= epoch_state.epoch_start_time + config.epoch_duration,
-            "Epoch not yet complete"
-        );
-
-        // Advance epoch
-        epoch_state.current_epoch = epoch_state.current_epoch.checked_add(1).expect("Overflow");
-        epoch_state.epoch_start_time = now;
-        epoch_state.total_emitted = epoch_state
-            .total_emitted
-            .checked_add(config.rewards_per_epoch)
-            .expect("Overflow");
+        let epoch_end = Self::epoch_end(&epoch_state, config.epoch_duration);
+        assert!(now >= epoch_end, "Epoch not yet complete");
 
-        // Pull rewards from pool into this contract
-        let pool: Address = env.storage().instance().get(&DataKey::RewardPool).expect("Not initialized");
-        let token_client = token::Client::new(&env, &config.token);
-        token_client.transfer(&pool, &env.current_contract_address(), &config.rewards_per_epoch);
-
-        env.storage()
-            .persistent()
-            .set(&DataKey::EpochState(schedule_id.clone()), &epoch_state);
+        Self::advance_epoch(&env, schedule_id, &config, epoch_state, epoch_end)
+    }
 
-        let epoch_id = epoch_state.current_epoch;
-        env.events().publish(
-            (symbol_short!("emitted"),),
-            EpochEmitted { schedule_id, epoch_id, amount: config.rewards_per_epoch },
-        );
+    /// Finalize every schedule in `schedule_ids` whose current epoch is
+    /// complete. Admin-only. Inactive or not-yet-complete schedules are
+    /// skipped instead of failing the call. Returns the new epoch id of each
+    /// schedule advanced, in input order.
+    pub fn emit_many(env: Env, schedule_ids: Vec<Symbol>) -> Vec<u64> {
+        Self::require_admin(&env);
 
-        epoch_id
+        let now = env.ledger().timestamp();
+        let mut epoch_ids = Vec::new(&env);
+        for schedule_id in schedule_ids.iter() {
+            let config: EmissionConfig = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Schedule(schedule_id.clone()))
+                .expect("Schedule not found");
+            if !config.active {
+                continue;
+            }
+
+            let epoch_state: EmissionEpochState = env
+                .storage()
+                .persistent()
+                .get(&DataKey::EpochState(schedule_id.clone()))
+                .expect("Epoch state not found");
+            let epoch_end = Self::epoch_end(&epoch_state, config.epoch_duration);
+            if now < epoch_end {
+                continue;
+            }
+
+            let epoch_id = Self::advance_epoch(&env, schedule_id, &config, epoch_state, epoch_end);
+            epoch_ids.push_back(epoch_id);
+        }
+        epoch_ids
     }
 
     /// Claim a daily reward for a specific epoch. User must not have claimed before.
@@ -200,6 +229,51 @@ impl DailyRewardEmission {
     }
 
     // ── Internal ─────────────────────────────────────────────────
+
+    /// Close the current epoch at `epoch_end` and pull its rewards from the pool.
+    fn advance_epoch(
+        env: &Env,
+        schedule_id: Symbol,
+        config: &EmissionConfig,
+        mut epoch_state: EmissionEpochState,
+        epoch_end: u64,
+    ) -> u64 {
+        epoch_state.current_epoch = epoch_state.current_epoch.checked_add(1).expect("Overflow");
+        epoch_state.epoch_start_time = epoch_end;
+        epoch_state.total_emitted = epoch_state
+            .total_emitted
+            .checked_add(config.rewards_per_epoch)
+            .expect("Overflow");
+
+        // Pull rewards from pool into this contract
+        let pool: Address = env.storage().instance().get(&DataKey::RewardPool).expect("Not initialized");
+        let token_client = token::Client::new(env, &config.token);
+        token_client.transfer(&pool, &env.current_contract_address(), &config.rewards_per_epoch);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::EpochState(schedule_id.clone()), &epoch_state);
+
+        let epoch_id = epoch_state.current_epoch;
+        env.events().publish(
+            (symbol_short!("emitted"),),
+            EpochEmitted { schedule_id, epoch_id, amount: config.rewards_per_epoch },
+        );
+
+        epoch_id
+    }
+
+    /// First boundary `epoch_anchor + k * epoch_duration` strictly after the
+    /// current epoch's start.
+    fn epoch_end(state: &EmissionEpochState, epoch_duration: u64) -> u64 {
+        let elapsed = state.epoch_start_time - state.epoch_anchor;
+        let boundaries = (elapsed / epoch_duration).checked_add(1).expect("Overflow");
+        boundaries
+            .checked_mul(epoch_duration)
+            .and_then(|offset| state.epoch_anchor.checked_add(offset))
+            .expect("Overflow")
+    }
+
     fn require_admin(env: &Env) {
         let admin: Address = env
             .storage()
@@ -217,7 +291,7 @@ mod test {
     use soroban_sdk::{
         testutils::{Address as _, Ledger, LedgerInfo},
         token::{Client as TokenClient, StellarAssetClient},
-        Env, Symbol,
+        vec, Env, Symbol,
     };
 
     fn setup_token<'a>(env: &Env, admin: &Address) -> (Address, StellarAssetClient<'a>, TokenClient<'a>) {
@@ -264,7 +338,7 @@ mod test {
             max_entry_ttl: 1_000_000,
         });
 
-        client.configure_emission(&schedule_id, &config);
+        client.configure_emission(&schedule_id, &config, &1000);
 
         // Advance time by 1 epoch
         env.ledger().set(LedgerInfo {
@@ -323,7 +397,7 @@ mod test {
             max_entry_ttl: 1_000_000,
         });
 
-        client.configure_emission(&sid, &config);
+        client.configure_emission(&sid, &config, &1);
 
         env.ledger().set(LedgerInfo {
             timestamp: 10,
@@ -353,4 +427,110 @@ mod test {
         client.init(&admin, &pool);
         client.init(&admin, &pool);
     }
+
+    fn set_time(env: &Env, timestamp: u64) {
+        env.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 25,
+            sequence_number: 1,
+            network_id: [0u8; 32],
+            base_reserve: 10,
+            min_temp_entry_ttl: 1,
+            min_persistent_entry_ttl: 1,
+            max_entry_ttl: 1_000_000,
+        });
+    }
+
+    fn setup_anchored<'a>(env: &'a Env, anchor: u64) -> (DailyRewardEmissionClient<'a>, Symbol) {
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let admin = Address::generate(env);
+        let pool = Address::generate(env);
+        let (token_id, sa, _) = setup_token(env, &admin);
+        sa.mint(&pool, &10_000);
+
+        let contract_id = env.register_contract(None, DailyRewardEmission);
+        let client = DailyRewardEmissionClient::new(env, &contract_id);
+        client.init(&admin, &pool);
+
+        let sid = Symbol::new(env, "daily");
+        let config = EmissionConfig {
+            schedule_id: sid.clone(),
+            rewards_per_epoch: 100,
+            epoch_duration: 86_400,
+            token: token_id,
+            active: true,
+        };
+        client.configure_emission(&sid, &config, &anchor);
+        (client, sid)
+    }
+
+    #[test]
+    fn test_anchor_shortens_first_epoch_to_next_boundary() {
+        let env = Env::default();
+        // Configured at 18:00 on day 10, anchored to UTC midnight: the first
+        // epoch lasts only six hours.
+        let midnight = 10 * 86_400;
+        set_time(&env, midnight + 18 * 3600);
+        let (client, sid) = setup_anchored(&env, 0);
+
+        set_time(&env, midnight + 86_400);
+        assert_eq!(client.emit_for_epoch(&sid), 1);
+
+        // Following epochs run a full day from the aligned boundary.
+        let state = client.emission_state(&sid);
+        assert_eq!(state.epoch_start_time, midnight + 86_400);
+        set_time(&env, midnight + 2 * 86_400);
+        assert_eq!(client.emit_for_epoch(&sid), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Epoch not yet complete")]
+    fn test_anchor_blocks_emission_before_boundary() {
+        let env = Env::default();
+        set_time(&env, 10 * 86_400 + 18 * 3600);
+        let (client, sid) = setup_anchored(&env, 0);
+
+        set_time(&env, 11 * 86_400 - 1);
+        client.emit_for_epoch(&sid);
+    }
+
+    #[test]
+    fn test_emit_many_skips_schedules_not_yet_due() {
+        let env = Env::default();
+        set_time(&env, 10 * 86_400);
+        let (client, daily) = setup_anchored(&env, 0);
+
+        // A second, hourly schedule on the same token.
+        let hourly = Symbol::new(&env, "hourly");
+        let token = env
+            .as_contract(&client.address, || {
+                env.storage()
+                    .persistent()
+                    .get::<_, EmissionConfig>(&DataKey::Schedule(daily.clone()))
+            })
+            .unwrap()
+            .token;
+        let config = EmissionConfig {
+            schedule_id: hourly.clone(),
+            rewards_per_epoch: 100,
+            epoch_duration: 3_600,
+            token,
+            active: true,
+        };
+        client.configure_emission(&hourly, &config, &0);
+
+        // Only the hourly epoch has closed; the daily one is skipped.
+        set_time(&env, 10 * 86_400 + 3_600);
+        let ids = vec![&env, daily.clone(), hourly.clone()];
+        assert_eq!(client.emit_many(&ids), vec![&env, 1u64]);
+        assert_eq!(client.emission_state(&daily).current_epoch, 0);
+        assert_eq!(client.emission_state(&hourly).current_epoch, 1);
+
+        // Once the day is over both advance in one call.
+        set_time(&env, 11 * 86_400);
+        assert_eq!(client.emit_many(&ids), vec![&env, 1u64, 2u64]);
+        assert_eq!(client.emission_state(&daily).total_emitted, 100);
+        assert_eq!(client.emission_state(&hourly).total_emitted, 200);
+    }
 }
@@ -1,8 +1,8 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractevent, contractimpl, contracttype, symbol_short, Address,
-    Env, Symbol, Vec, Map,
+    contract, contracterror, contractevent, contractimpl, contracttype, symbol_short, xdr::ToXdr,
+    Address, Env, Map, Symbol, Vec,
 };
 
 use stellarcade_shared::calculate_fee;
@@ -16,6 +16,9 @@ const PERSISTENT_BUMP_THRESHOLD: u32 = PERSISTENT_BUMP_LEDGERS - 100_800; // Ren
 
 const BASIS_POINTS_DIVISOR: u32 = 10_000;
 
+/// Upper bound on the per-game fee log size.
+pub const MAX_FEE_LOG_SIZE: u32 = 50;
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -50,6 +53,54 @@ pub struct FeeRuleConfig {
     pub base_fee_bps: u32,
     pub tiers: Option<Vec<FeeTier>>,
     pub enabled: bool,
+    /// Maximum total fee that may be collected for the game within one
+    /// period. `None` disables the cap.
+    pub max_fees_per_period: Option<i128>,
+    /// Length of a cap period in seconds. Ignored when no cap is set.
+    pub period: u64,
+    /// Amounts strictly below this value are charged no fee, bypassing
+    /// tiers, the context multiplier and the period cap. `0` disables it.
+    pub fee_free_below: i128,
+    /// Turns the rule into a rebate: `compute_fee` returns a negative fee
+    /// (a credit) of this many bps, scaled by the context multiplier, in
+    /// place of the tiered fee. The credit never exceeds the amount.
+    pub rebate_bps: Option<u32>,
+}
+
+/// Cumulative fees computed for a game within the current cap period.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeePeriodUsage {
+    pub period_start: u64,
+    pub collected: i128,
+}
+
+/// Memoized tier bps for one amount bucket. Only valid while `rule_version`
+/// and `granularity` still match the current values.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeCacheEntry {
+    pub rule_version: u32,
+    pub granularity: i128,
+    pub applied_bps: u32,
+}
+
+/// An A/B fee test for a game. Accounts are bucketed by hash into
+/// `[0, 10000)`; buckets below `split_bps` get `variant_a`, the rest
+/// `variant_b`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeExperiment {
+    pub variant_a: FeeRuleConfig,
+    pub variant_b: FeeRuleConfig,
+    pub split_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FeeVariant {
+    A = 0,
+    B = 1,
 }
 
 #[contracttype]
@@ -59,11 +110,32 @@ pub struct FeeContext {
     pub additional_data: Map<Symbol, i128>,
 }
 
+/// A fee application kept in a game's fee log for reconciliation.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeRecord {
+    pub timestamp: u64,
+    pub amount: i128,
+    pub fee: i128,
+    pub applied_bps: u32,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Admin,
-    FeeRule(Symbol), // Keyed by game_id
+    CacheGranularity,
+    DefaultRule,                           // Fallback rule for games without their own
+    FeeRule(Symbol),                       // Keyed by game_id
+    FeePeriodUsage(Symbol),                // Keyed by game_id
+    RuleVersion(Symbol),                   // Keyed by game_id, bumped on every rule change
+    FeeCache(Symbol, i128),                // Keyed by (game_id, amount bucket)
+    Experiment(Symbol),                    // Keyed by game_id
+    ExperimentAssignment(Symbol, Address), // Keyed by (game_id, account)
+    FeeLogSize(Symbol),                    // Keyed by game_id, 0/absent = logging off
+    FeeLog(Symbol),                        // Keyed by game_id, oldest record first
+    UserFee(Symbol, Address),              // Keyed by (game_id, user), bps replacing the tiers
+    Collector(Address),                    // Set while the address may call `collect_fee`
 }
 
 // ---------------------------------------------------------------------------
@@ -84,6 +156,36 @@ pub struct FeeRuleSet {
     pub has_tiers: bool,
 }
 
+#[contractevent]
+pub struct DefaultRuleSet {
+    pub base_fee_bps: u32,
+    pub has_tiers: bool,
+}
+
+#[contractevent]
+pub struct UserFeeSet {
+    #[topic]
+    pub game_id: Symbol,
+    #[topic]
+    pub user: Address,
+    pub fee_bps: u32,
+}
+
+#[contractevent]
+pub struct UserFeeCleared {
+    #[topic]
+    pub game_id: Symbol,
+    #[topic]
+    pub user: Address,
+}
+
+#[contractevent]
+pub struct CollectorSet {
+    #[topic]
+    pub collector: Address,
+    pub allowed: bool,
+}
+
 #[contractevent]
 pub struct FeeRuleStatusChanged {
     #[topic]
@@ -98,6 +200,32 @@ pub struct FeeComputed {
     pub original_amount: i128,
     pub fee_amount: i128,
     pub applied_bps: u32,
+    pub was_capped: bool,
+    /// Set when `fee_amount` is a negative credit from a rebate rule.
+    pub is_rebate: bool,
+}
+
+#[contractevent]
+pub struct ExperimentSet {
+    #[topic]
+    pub game_id: Symbol,
+    pub split_bps: u32,
+}
+
+#[contractevent]
+pub struct ExperimentAssigned {
+    #[topic]
+    pub game_id: Symbol,
+    #[topic]
+    pub account: Address,
+    pub variant: FeeVariant,
+}
+
+#[contractevent]
+pub struct FeeLogConfigured {
+    #[topic]
+    pub game_id: Symbol,
+    pub size: u32,
 }
 
 // ---------------------------------------------------------------------------
@@ -132,17 +260,7 @@ impl DynamicFeePolicy {
         let admin = Self::require_admin(&env)?;
         admin.require_auth();
 
-        // Basic validation
-        if rule_config.base_fee_bps > BASIS_POINTS_DIVISOR {
-            return Err(Error::InvalidFeeConfig);
-        }
-        if let Some(ref tiers) = rule_config.tiers {
-            for tier in tiers.iter() {
-                if tier.fee_bps > BASIS_POINTS_DIVISOR {
-                    return Err(Error::InvalidFeeConfig);
-                }
-            }
-        }
+        Self::validate_rule(&rule_config)?;
 
         let key = DataKey::FeeRule(game_id.clone());
         env.storage().persistent().set(&key, &rule_config);
@@ -151,6 +269,7 @@ impl DynamicFeePolicy {
             PERSISTENT_BUMP_THRESHOLD,
             PERSISTENT_BUMP_LEDGERS,
         );
+        Self::bump_rule_version(&env, &game_id);
 
         FeeRuleSet {
             game_id,
@@ -162,233 +281,1741 @@ impl DynamicFeePolicy {
         Ok(())
     }
 
+    /// Set the fallback rule used by every game that has no rule of its
+    /// own. Validated like `set_fee_rule`. Games with an explicit rule keep
+    /// using it.
+    pub fn set_default_rule(env: Env, admin: Address, rule: FeeRuleConfig) -> Result<(), Error> {
+        let stored_admin = Self::require_admin(&env)?;
+        admin.require_auth();
+        if admin != stored_admin {
+            return Err(Error::NotAuthorized);
+        }
+
+        Self::validate_rule(&rule)?;
+
+        env.storage().instance().set(&DataKey::DefaultRule, &rule);
+
+        DefaultRuleSet {
+            base_fee_bps: rule.base_fee_bps,
+            has_tiers: rule.tiers.is_some(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Query the fallback rule, if one is set.
+    pub fn default_rule(env: Env) -> Option<FeeRuleConfig> {
+        env.storage().instance().get(&DataKey::DefaultRule)
+    }
+
     /// Compute the fee for a given amount and context.
+    ///
+    /// Uses the game's own rule, or the default rule when the game has none.
+    /// Default-rule tiers bypass the tier cache.
+    ///
+    /// If the rule has a `max_fees_per_period` cap, the returned fee is
+    /// reduced to whatever headroom remains in the current period (zero once
+    /// the cap is exhausted) and the emitted event has `was_capped` set. The
+    /// cumulative total resets when `period` seconds have elapsed since the
+    /// period started. Only `collect_fee` uses up the cap; this is a quote.
     pub fn compute_fee(
         env: Env,
         game_id: Symbol,
         amount: i128,
         context: FeeContext,
     ) -> Result<i128, Error> {
-        let key = DataKey::FeeRule(game_id.clone());
-        let rule: FeeRuleConfig = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .ok_or(Error::RuleNotFound)?;
-
-        if !rule.enabled {
-            return Err(Error::RuleDisabled);
-        }
+        Self::resolve_fee(&env, game_id, None, amount, context, false)
+    }
 
-        // 1. Determine base bps (check tiers)
-        let mut applied_bps = rule.base_fee_bps;
-        if let Some(tiers) = rule.tiers {
-            let mut highest_threshold = -1i128;
-            for tier in tiers.iter() {
-                if amount >= tier.threshold && tier.threshold > highest_threshold {
-                    highest_threshold = tier.threshold;
-                    applied_bps = tier.fee_bps;
-                }
-            }
+    /// Compute the fee like `compute_fee`, or like `compute_fee_for` when
+    /// `account` is set, and count it against the game's period cap.
+    /// `collector` must be the admin or an address registered with
+    /// `set_collector`, so only fees actually charged use up the cap.
+    pub fn collect_fee(
+        env: Env,
+        collector: Address,
+        game_id: Symbol,
+        account: Option<Address>,
+        amount: i128,
+        context: FeeContext,
+    ) -> Result<i128, Error> {
+        let admin = Self::require_admin(&env)?;
+        collector.require_auth();
+        if collector != admin && !Self::is_collector(env.clone(), collector) {
+            return Err(Error::NotAuthorized);
         }
 
-        // 2. Apply context multiplier
-        // final_bps = (applied_bps * multiplier_bps) / 10000
-        let final_bps = applied_bps
-            .checked_mul(context.multiplier_bps)
-            .and_then(|v| v.checked_div(BASIS_POINTS_DIVISOR))
-            .ok_or(Error::Overflow)?;
+        Self::resolve_fee(&env, game_id, account, amount, context, true)
+    }
 
-        // 3. Calculate actual fee
-        let fee_amount = match calculate_fee(amount, final_bps) {
-            Ok(fee) => fee,
-            Err(_) => return Err(Error::Overflow),
-        };
+    /// Allow or disallow `collector` to call `collect_fee`. Admin only.
+    pub fn set_collector(env: Env, collector: Address, allowed: bool) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
 
-        FeeComputed {
-            game_id,
-            original_amount: amount,
-            fee_amount,
-            applied_bps: final_bps,
+        let key = DataKey::Collector(collector.clone());
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(
+                &key,
+                PERSISTENT_BUMP_THRESHOLD,
+                PERSISTENT_BUMP_LEDGERS,
+            );
+        } else {
+            env.storage().persistent().remove(&key);
         }
-        .publish(&env);
 
-        Ok(fee_amount)
-    }
+        CollectorSet { collector, allowed }.publish(&env);
 
-    /// Enable a fee rule.
-    pub fn enable_rule(env: Env, game_id: Symbol) -> Result<(), Error> {
-        Self::set_enabled_status(env, game_id, true)
+        Ok(())
     }
 
-    /// Disable a fee rule.
-    pub fn disable_rule(env: Env, game_id: Symbol) -> Result<(), Error> {
-        Self::set_enabled_status(env, game_id, false)
+    /// Query whether `collector` may call `collect_fee`.
+    pub fn is_collector(env: Env, collector: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Collector(collector))
     }
 
-    /// Query the state of a fee rule.
-    pub fn fee_rule_state(env: Env, game_id: Symbol) -> Option<FeeRuleConfig> {
-        env.storage().persistent().get(&DataKey::FeeRule(game_id))
+    /// The final bps `compute_fee` would apply to `amount` under `context`,
+    /// after tier selection, the fee-free band, any rebate and the context
+    /// multiplier. Computes no fee, does not touch the period cap and emits
+    /// no events. For a rebate rule the value is the rebate rate.
+    pub fn effective_bps(
+        env: Env,
+        game_id: Symbol,
+        amount: i128,
+        context: FeeContext,
+    ) -> Result<u32, Error> {
+        let (rule, _) = Self::load_enabled_rule(&env, &game_id)?;
+        let tier_bps = Self::tier_bps(&rule, amount);
+        Self::select_bps(&rule, tier_bps, amount, context.multiplier_bps)
     }
 
-    // -----------------------------------------------------------------------
-    // Internal helpers
-    // -----------------------------------------------------------------------
+    /// Configure an A/B fee experiment for a game. Both variants are
+    /// validated like `set_fee_rule`. Replacing an experiment keeps the
+    /// variants already recorded for accounts.
+    pub fn set_experiment(
+        env: Env,
+        game_id: Symbol,
+        experiment: FeeExperiment,
+    ) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
 
-    fn require_admin(env: &Env) -> Result<Address, Error> {
-        env.storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(Error::NotInitialized)
+        if experiment.split_bps > BASIS_POINTS_DIVISOR {
+            return Err(Error::InvalidFeeConfig);
+        }
+        Self::validate_rule(&experiment.variant_a)?;
+        Self::validate_rule(&experiment.variant_b)?;
+
+        let key = DataKey::Experiment(game_id.clone());
+        env.storage().persistent().set(&key, &experiment);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        ExperimentSet {
+            game_id,
+            split_bps: experiment.split_bps,
+        }
+        .publish(&env);
+
+        Ok(())
     }
 
-    fn set_enabled_status(env: Env, game_id: Symbol, status: bool) -> Result<(), Error> {
+    /// Compute the fee for `account`. A per-user fee set with
+    /// `set_user_fee` replaces the tier bps of the game's rule; otherwise the
+    /// account's experiment variant applies when the game runs an experiment,
+    /// and the regular rule when it does not.
+    ///
+    /// An account's variant is derived from a hash of `(game_id, account)`,
+    /// so it is the same on every call, and is recorded on first use.
+    /// Variant tiers bypass the tier cache; the period cap, if any, is
+    /// checked against the game's shared usage but, as in `compute_fee`,
+    /// only `collect_fee` uses it up.
+    pub fn compute_fee_for(
+        env: Env,
+        game_id: Symbol,
+        account: Address,
+        amount: i128,
+        context: FeeContext,
+    ) -> Result<i128, Error> {
+        Self::resolve_fee(&env, game_id, Some(account), amount, context, false)
+    }
+
+    /// Give `user` a custom fee rate for a game, e.g. for VIPs or partners.
+    /// In `compute_fee_for` it replaces the tier selection; the fee-free
+    /// band, context multiplier and period cap of the game's rule still
+    /// apply. Admin only.
+    pub fn set_user_fee(env: Env, game_id: Symbol, user: Address, bps: u32) -> Result<(), Error> {
         let admin = Self::require_admin(&env)?;
         admin.require_auth();
 
-        let key = DataKey::FeeRule(game_id.clone());
-        let mut rule: FeeRuleConfig = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .ok_or(Error::RuleNotFound)?;
+        if bps > BASIS_POINTS_DIVISOR {
+            return Err(Error::InvalidFeeConfig);
+        }
 
-        rule.enabled = status;
-        env.storage().persistent().set(&key, &rule);
+        let key = DataKey::UserFee(game_id.clone(), user.clone());
+        env.storage().persistent().set(&key, &bps);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
 
-        FeeRuleStatusChanged {
+        UserFeeSet {
             game_id,
-            enabled: status,
+            user,
+            fee_bps: bps,
         }
         .publish(&env);
 
         Ok(())
     }
-}
 
-// ---------------------------------------------------------------------------
-// Tests
-// ---------------------------------------------------------------------------
+    /// Remove a user's custom fee rate, reverting to the game's rule. Admin
+    /// only.
+    pub fn clear_user_fee(env: Env, game_id: Symbol, user: Address) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env, vec, Map};
+        env.storage()
+            .persistent()
+            .remove(&DataKey::UserFee(game_id.clone(), user.clone()));
 
-    struct Setup<'a> {
-        _env: Env,
-        client: DynamicFeePolicyClient<'a>,
-        _admin: Address,
+        UserFeeCleared { game_id, user }.publish(&env);
+
+        Ok(())
     }
 
-    fn setup() -> Setup<'static> {
-        let env = Env::default();
-        env.mock_all_auths();
+    /// Query a user's custom fee rate for a game, if set.
+    pub fn user_fee(env: Env, game_id: Symbol, user: Address) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UserFee(game_id, user))
+    }
 
-        let contract_id = env.register(DynamicFeePolicy, ());
-        let client = DynamicFeePolicyClient::new(&env, &contract_id);
+    /// Query the variant recorded for an account, if it has been bucketed.
+    pub fn experiment_variant(env: Env, game_id: Symbol, account: Address) -> Option<FeeVariant> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ExperimentAssignment(game_id, account))
+    }
 
-        let admin = Address::generate(&env);
-        client.init(&admin);
+    /// Query a game's experiment configuration.
+    pub fn experiment_state(env: Env, game_id: Symbol) -> Option<FeeExperiment> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Experiment(game_id))
+    }
 
-        let client: DynamicFeePolicyClient<'static> = unsafe { core::mem::transmute(client) };
+    /// Set the amount granularity used to memoize tier lookups.
+    ///
+    /// Amounts are bucketed by rounding down to a multiple of `granularity`
+    /// and the resolved tier bps is cached per `(game_id, bucket)`. A bucket
+    /// that straddles a tier threshold is never cached, so lookups stay exact
+    /// for any granularity. `0` disables caching.
+    pub fn set_cache_granularity(env: Env, granularity: i128) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
 
-        Setup {
-            _env: env,
-            client,
-            _admin: admin,
+        if granularity < 0 {
+            return Err(Error::InvalidFeeConfig);
         }
-    }
-
-    #[test]
-    fn test_compute_base_fee() {
-        let s = setup();
-        let game = symbol_short!("game1");
-        
-        s.client.set_fee_rule(&game, &FeeRuleConfig {
-            base_fee_bps: 500, // 5%
-            tiers: None,
-            enabled: true,
-        });
+        env.storage()
+            .instance()
+            .set(&DataKey::CacheGranularity, &granularity);
 
-        let context = FeeContext {
-            multiplier_bps: 10_000, // 1x
-            additional_data: Map::new(&s._env),
-        };
+        Ok(())
+    }
 
-        let fee = s.client.compute_fee(&game, &1000, &context);
-        assert_eq!(fee, 50);
+    /// Query the memoized tier bps for the bucket containing `amount`, if the
+    /// cache holds a valid entry for the current rule.
+    pub fn cached_bps(env: Env, game_id: Symbol, amount: i128) -> Option<u32> {
+        let granularity = Self::cache_granularity(&env);
+        if granularity == 0 {
+            return None;
+        }
+        Self::read_cache(&env, &game_id, granularity, amount)
     }
 
-    #[test]
-    fn test_compute_tiered_fee() {
+    /// Enable a fee rule.
+    pub fn enable_rule(env: Env, game_id: Symbol) -> Result<(), Error> {
+        Self::set_enabled_status(env, game_id, true)
+    }
+
+    /// Disable a fee rule.
+    pub fn disable_rule(env: Env, game_id: Symbol) -> Result<(), Error> {
+        Self::set_enabled_status(env, game_id, false)
+    }
+
+    /// Query the state of a fee rule.
+    pub fn fee_rule_state(env: Env, game_id: Symbol) -> Option<FeeRuleConfig> {
+        env.storage().persistent().get(&DataKey::FeeRule(game_id))
+    }
+
+    /// Keep the last `size` fees collected through `collect_fee` for a game
+    /// in an on-chain log, or turn logging off with `size == 0`. Quotes are
+    /// not logged. Shrinking drops the oldest records. `size` is capped at
+    /// `MAX_FEE_LOG_SIZE`.
+    pub fn set_fee_log_size(env: Env, game_id: Symbol, size: u32) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        if size > MAX_FEE_LOG_SIZE {
+            return Err(Error::InvalidFeeConfig);
+        }
+
+        let size_key = DataKey::FeeLogSize(game_id.clone());
+        let log_key = DataKey::FeeLog(game_id.clone());
+        if size == 0 {
+            env.storage().persistent().remove(&size_key);
+            env.storage().persistent().remove(&log_key);
+        } else {
+            env.storage().persistent().set(&size_key, &size);
+            env.storage().persistent().extend_ttl(
+                &size_key,
+                PERSISTENT_BUMP_THRESHOLD,
+                PERSISTENT_BUMP_LEDGERS,
+            );
+            if let Some(mut log) = env
+                .storage()
+                .persistent()
+                .get::<_, Vec<FeeRecord>>(&log_key)
+            {
+                while log.len() > size {
+                    log.pop_front();
+                }
+                env.storage().persistent().set(&log_key, &log);
+            }
+        }
+
+        FeeLogConfigured { game_id, size }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Return up to `limit` of a game's most recent collected fees,
+    /// newest first. Empty when logging is off for the game.
+    pub fn recent_fees(env: Env, game_id: Symbol, limit: u32) -> Vec<FeeRecord> {
+        let log: Vec<FeeRecord> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::FeeLog(game_id))
+            .unwrap_or(Vec::new(&env));
+
+        let mut recent = Vec::new(&env);
+        for record in log.iter().rev() {
+            if recent.len() >= limit {
+                break;
+            }
+            recent.push_back(record);
+        }
+        recent
+    }
+
+    /// Query the fees accumulated for a game in its current cap period.
+    pub fn fee_period_usage(env: Env, game_id: Symbol) -> Option<FeePeriodUsage> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::FeePeriodUsage(game_id))
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    /// Pick the rule and tier bps for `amount` (per `account` if given) and
+    /// apply it. Period-cap usage is only written when `record` is set.
+    fn resolve_fee(
+        env: &Env,
+        game_id: Symbol,
+        account: Option<Address>,
+        amount: i128,
+        context: FeeContext,
+        record: bool,
+    ) -> Result<i128, Error> {
+        if let Some(account) = account {
+            if let Some(user_bps) = Self::user_fee(env.clone(), game_id.clone(), account.clone()) {
+                let (rule, _) = Self::load_enabled_rule(env, &game_id)?;
+                return Self::apply_rule(env, game_id, &rule, user_bps, amount, context, record);
+            }
+
+            let experiment: Option<FeeExperiment> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Experiment(game_id.clone()));
+            if let Some(experiment) = experiment {
+                let variant = Self::assign_variant(env, &game_id, &account, experiment.split_bps);
+                let rule = match variant {
+                    FeeVariant::A => experiment.variant_a,
+                    FeeVariant::B => experiment.variant_b,
+                };
+                if !rule.enabled {
+                    return Err(Error::RuleDisabled);
+                }
+                let applied_bps = Self::tier_bps(&rule, amount);
+                return Self::apply_rule(env, game_id, &rule, applied_bps, amount, context, record);
+            }
+        }
+
+        let (rule, is_default) = Self::load_enabled_rule(env, &game_id)?;
+
+        // 1. Determine base bps (check tiers, memoized per amount bucket)
+        let applied_bps = if is_default {
+            Self::tier_bps(&rule, amount)
+        } else {
+            Self::resolve_tier_bps(env, &game_id, &rule, amount)
+        };
+
+        Self::apply_rule(env, game_id, &rule, applied_bps, amount, context, record)
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Load the game's rule, falling back to the default rule. The flag is
+    /// set when the default rule was used.
+    fn load_enabled_rule(env: &Env, game_id: &Symbol) -> Result<(FeeRuleConfig, bool), Error> {
+        let (rule, is_default) = match env
+            .storage()
+            .persistent()
+            .get::<_, FeeRuleConfig>(&DataKey::FeeRule(game_id.clone()))
+        {
+            Some(rule) => (rule, false),
+            None => {
+                let rule: FeeRuleConfig = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::DefaultRule)
+                    .ok_or(Error::RuleNotFound)?;
+                (rule, true)
+            }
+        };
+        if !rule.enabled {
+            return Err(Error::RuleDisabled);
+        }
+        Ok((rule, is_default))
+    }
+
+    fn validate_rule(rule_config: &FeeRuleConfig) -> Result<(), Error> {
+        if rule_config.base_fee_bps > BASIS_POINTS_DIVISOR {
+            return Err(Error::InvalidFeeConfig);
+        }
+        if let Some(ref tiers) = rule_config.tiers {
+            for tier in tiers.iter() {
+                if tier.fee_bps > BASIS_POINTS_DIVISOR {
+                    return Err(Error::InvalidFeeConfig);
+                }
+            }
+        }
+        if let Some(cap) = rule_config.max_fees_per_period {
+            if cap < 0 || rule_config.period == 0 {
+                return Err(Error::InvalidFeeConfig);
+            }
+        }
+        if rule_config.fee_free_below < 0 {
+            return Err(Error::InvalidFeeConfig);
+        }
+        // Rebates pay out instead of collecting, so a collection cap has
+        // no meaning for them.
+        if let Some(rebate_bps) = rule_config.rebate_bps {
+            if rebate_bps == 0
+                || rebate_bps > BASIS_POINTS_DIVISOR
+                || rule_config.max_fees_per_period.is_some()
+            {
+                return Err(Error::InvalidFeeConfig);
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply the context multiplier and period cap on top of the tier bps
+    /// and publish the result. Amounts inside the rule's fee-free band are
+    /// charged a zero fee and never count against the cap. The fee log and
+    /// cap usage are only written when `record` is set.
+    fn apply_rule(
+        env: &Env,
+        game_id: Symbol,
+        rule: &FeeRuleConfig,
+        applied_bps: u32,
+        amount: i128,
+        context: FeeContext,
+        record: bool,
+    ) -> Result<i128, Error> {
+        // 2. Apply the fee-free band, rebate and context multiplier
+        let final_bps = Self::select_bps(rule, applied_bps, amount, context.multiplier_bps)?;
+
+        if amount < rule.fee_free_below {
+            if record {
+                Self::log_fee(env, &game_id, amount, 0, final_bps);
+            }
+            FeeComputed {
+                game_id,
+                original_amount: amount,
+                fee_amount: 0,
+                applied_bps: final_bps,
+                was_capped: false,
+                is_rebate: false,
+            }
+            .publish(env);
+            return Ok(0);
+        }
+
+        if rule.rebate_bps.is_some() {
+            return Self::apply_rebate(env, game_id, final_bps, amount, record);
+        }
+
+        // 3. Calculate actual fee
+        let fee_amount = match calculate_fee(amount, final_bps) {
+            Ok(fee) => fee,
+            Err(_) => return Err(Error::Overflow),
+        };
+
+        // 4. Enforce the per-period cap
+        let (fee_amount, was_capped) =
+            Self::apply_period_cap(env, &game_id, rule, fee_amount, record)?;
+
+        if record {
+            Self::log_fee(env, &game_id, amount, fee_amount, final_bps);
+        }
+
+        FeeComputed {
+            game_id,
+            original_amount: amount,
+            fee_amount,
+            applied_bps: final_bps,
+            was_capped,
+            is_rebate: false,
+        }
+        .publish(env);
+
+        Ok(fee_amount)
+    }
+
+    /// Select the final bps for `amount` from the tier bps. Amounts in the
+    /// fee-free band get 0; otherwise the rebate rate, if any, replaces the
+    /// tier bps and the result is scaled by the context multiplier
+    /// (`bps * multiplier_bps / 10000`). Rebates are clamped to 100% so the
+    /// credit never exceeds the amount. Shared by `compute_fee`,
+    /// `compute_fee_for` and `effective_bps`.
+    fn select_bps(
+        rule: &FeeRuleConfig,
+        tier_bps: u32,
+        amount: i128,
+        multiplier_bps: u32,
+    ) -> Result<u32, Error> {
+        if amount < rule.fee_free_below {
+            return Ok(0);
+        }
+        let bps = rule.rebate_bps.unwrap_or(tier_bps);
+        let final_bps = bps
+            .checked_mul(multiplier_bps)
+            .and_then(|v| v.checked_div(BASIS_POINTS_DIVISOR))
+            .ok_or(Error::Overflow)?;
+        if rule.rebate_bps.is_some() {
+            return Ok(final_bps.min(BASIS_POINTS_DIVISOR));
+        }
+        Ok(final_bps)
+    }
+
+    /// Compute a rebate as a negative fee at `final_bps`, logging it only
+    /// if `record` is set.
+    fn apply_rebate(
+        env: &Env,
+        game_id: Symbol,
+        final_bps: u32,
+        amount: i128,
+        record: bool,
+    ) -> Result<i128, Error> {
+        let rebate = calculate_fee(amount, final_bps).map_err(|_| Error::Overflow)?;
+        let fee_amount = -rebate;
+
+        if record {
+            Self::log_fee(env, &game_id, amount, fee_amount, final_bps);
+        }
+
+        FeeComputed {
+            game_id,
+            original_amount: amount,
+            fee_amount,
+            applied_bps: final_bps,
+            was_capped: false,
+            is_rebate: true,
+        }
+        .publish(env);
+
+        Ok(fee_amount)
+    }
+
+    /// Append a record to the game's fee log if logging is enabled,
+    /// dropping the oldest record once the log is full.
+    fn log_fee(env: &Env, game_id: &Symbol, amount: i128, fee: i128, applied_bps: u32) {
+        let size: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::FeeLogSize(game_id.clone()))
+            .unwrap_or(0);
+        if size == 0 {
+            return;
+        }
+
+        let key = DataKey::FeeLog(game_id.clone());
+        let mut log: Vec<FeeRecord> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        log.push_back(FeeRecord {
+            timestamp: env.ledger().timestamp(),
+            amount,
+            fee,
+            applied_bps,
+        });
+        while log.len() > size {
+            log.pop_front();
+        }
+        env.storage().persistent().set(&key, &log);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+    }
+
+    /// Return the account's recorded variant, bucketing and recording it on
+    /// first use.
+    fn assign_variant(
+        env: &Env,
+        game_id: &Symbol,
+        account: &Address,
+        split_bps: u32,
+    ) -> FeeVariant {
+        let key = DataKey::ExperimentAssignment(game_id.clone(), account.clone());
+        if let Some(variant) = env.storage().persistent().get(&key) {
+            return variant;
+        }
+
+        let seed = (game_id.clone(), account.clone()).to_xdr(env);
+        let digest = env.crypto().sha256(&seed).to_array();
+        let bucket =
+            u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) % BASIS_POINTS_DIVISOR;
+        let variant = if bucket < split_bps {
+            FeeVariant::A
+        } else {
+            FeeVariant::B
+        };
+
+        env.storage().persistent().set(&key, &variant);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+        ExperimentAssigned {
+            game_id: game_id.clone(),
+            account: account.clone(),
+            variant,
+        }
+        .publish(env);
+
+        variant
+    }
+
+    fn tier_bps(rule: &FeeRuleConfig, amount: i128) -> u32 {
+        let mut applied_bps = rule.base_fee_bps;
+        if let Some(ref tiers) = rule.tiers {
+            let mut highest_threshold = -1i128;
+            for tier in tiers.iter() {
+                if amount >= tier.threshold && tier.threshold > highest_threshold {
+                    highest_threshold = tier.threshold;
+                    applied_bps = tier.fee_bps;
+                }
+            }
+        }
+        applied_bps
+    }
+
+    fn resolve_tier_bps(env: &Env, game_id: &Symbol, rule: &FeeRuleConfig, amount: i128) -> u32 {
+        let granularity = Self::cache_granularity(env);
+        if granularity == 0 {
+            return Self::tier_bps(rule, amount);
+        }
+        if let Some(bps) = Self::read_cache(env, game_id, granularity, amount) {
+            return bps;
+        }
+
+        let applied_bps = Self::tier_bps(rule, amount);
+        let Some(bucket) = Self::cache_bucket(amount, granularity) else {
+            return applied_bps;
+        };
+        if !Self::bucket_has_one_tier(rule, bucket, granularity) {
+            return applied_bps;
+        }
+        let key = DataKey::FeeCache(game_id.clone(), bucket);
+        env.storage().temporary().set(
+            &key,
+            &FeeCacheEntry {
+                rule_version: Self::rule_version(env, game_id),
+                granularity,
+                applied_bps,
+            },
+        );
+        applied_bps
+    }
+
+    fn read_cache(env: &Env, game_id: &Symbol, granularity: i128, amount: i128) -> Option<u32> {
+        let key = DataKey::FeeCache(game_id.clone(), Self::cache_bucket(amount, granularity)?);
+        let entry: FeeCacheEntry = env.storage().temporary().get(&key)?;
+        if entry.rule_version != Self::rule_version(env, game_id)
+            || entry.granularity != granularity
+        {
+            return None;
+        }
+        Some(entry.applied_bps)
+    }
+
+    /// The multiple of `granularity` at or below `amount`, or `None` if it
+    /// does not fit in an `i128`.
+    fn cache_bucket(amount: i128, granularity: i128) -> Option<i128> {
+        amount.div_euclid(granularity).checked_mul(granularity)
+    }
+
+    /// Whether every amount in `[bucket, bucket + granularity)` resolves to
+    /// the same tier, i.e. no tier threshold lies above the bucket's first
+    /// amount but within the bucket.
+    fn bucket_has_one_tier(rule: &FeeRuleConfig, bucket: i128, granularity: i128) -> bool {
+        let Some(last) = bucket.checked_add(granularity - 1) else {
+            return false;
+        };
+        match rule.tiers {
+            Some(ref tiers) => !tiers
+                .iter()
+                .any(|tier| tier.threshold > bucket && tier.threshold <= last),
+            None => true,
+        }
+    }
+
+    fn cache_granularity(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::CacheGranularity)
+            .unwrap_or(0)
+    }
+
+    fn rule_version(env: &Env, game_id: &Symbol) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RuleVersion(game_id.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Invalidate every cached entry for the game by moving to a new version.
+    fn bump_rule_version(env: &Env, game_id: &Symbol) {
+        let key = DataKey::RuleVersion(game_id.clone());
+        let version = Self::rule_version(env, game_id).wrapping_add(1);
+        env.storage().persistent().set(&key, &version);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+    }
+
+    /// Reduce `fee_amount` to the headroom left in the current cap period.
+    /// The charged amount is added to the period's usage only if `record`.
+    fn apply_period_cap(
+        env: &Env,
+        game_id: &Symbol,
+        rule: &FeeRuleConfig,
+        fee_amount: i128,
+        record: bool,
+    ) -> Result<(i128, bool), Error> {
+        let cap = match rule.max_fees_per_period {
+            Some(cap) => cap,
+            None => return Ok((fee_amount, false)),
+        };
+
+        let key = DataKey::FeePeriodUsage(game_id.clone());
+        let now = env.ledger().timestamp();
+        let mut usage: FeePeriodUsage =
+            env.storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or(FeePeriodUsage {
+                    period_start: now,
+                    collected: 0,
+                });
+
+        if now >= usage.period_start.saturating_add(rule.period) {
+            usage = FeePeriodUsage {
+                period_start: now,
+                collected: 0,
+            };
+        }
+
+        let headroom = cap.saturating_sub(usage.collected).max(0);
+        let (charged, was_capped) = if fee_amount > headroom {
+            (headroom, true)
+        } else {
+            (fee_amount, false)
+        };
+        if !record {
+            return Ok((charged, was_capped));
+        }
+
+        usage.collected = usage
+            .collected
+            .checked_add(charged)
+            .ok_or(Error::Overflow)?;
+        env.storage().persistent().set(&key, &usage);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        Ok((charged, was_capped))
+    }
+
+    fn set_enabled_status(env: Env, game_id: Symbol, status: bool) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        let key = DataKey::FeeRule(game_id.clone());
+        let mut rule: FeeRuleConfig = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::RuleNotFound)?;
+
+        rule.enabled = status;
+        env.storage().persistent().set(&key, &rule);
+        Self::bump_rule_version(&env, &game_id);
+
+        FeeRuleStatusChanged {
+            game_id,
+            enabled: status,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger},
+        vec, Address, Env, Event as _, Map,
+    };
+
+    struct Setup<'a> {
+        _env: Env,
+        client: DynamicFeePolicyClient<'a>,
+        _admin: Address,
+    }
+
+    fn setup() -> Setup<'static> {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(DynamicFeePolicy, ());
+        let client = DynamicFeePolicyClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        client.init(&admin);
+
+        let client: DynamicFeePolicyClient<'static> = unsafe { core::mem::transmute(client) };
+
+        Setup {
+            _env: env,
+            client,
+            _admin: admin,
+        }
+    }
+
+    #[test]
+    fn test_compute_base_fee() {
+        let s = setup();
+        let game = symbol_short!("game1");
+
+        s.client.set_fee_rule(
+            &game,
+            &FeeRuleConfig {
+                base_fee_bps: 500, // 5%
+                tiers: None,
+                enabled: true,
+                max_fees_per_period: None,
+                period: 0,
+                fee_free_below: 0,
+                rebate_bps: None,
+            },
+        );
+
+        let context = FeeContext {
+            multiplier_bps: 10_000, // 1x
+            additional_data: Map::new(&s._env),
+        };
+
+        let fee = s.client.compute_fee(&game, &1000, &context);
+        assert_eq!(fee, 50);
+    }
+
+    #[test]
+    fn test_compute_tiered_fee() {
+        let s = setup();
+        let game = symbol_short!("game1");
+
+        let tiers = vec![
+            &s._env,
+            FeeTier {
+                threshold: 1000,
+                fee_bps: 300,
+            }, // 3% for >= 1000
+            FeeTier {
+                threshold: 5000,
+                fee_bps: 100,
+            }, // 1% for >= 5000
+        ];
+
+        s.client.set_fee_rule(
+            &game,
+            &FeeRuleConfig {
+                base_fee_bps: 500, // 5% base
+                tiers: Some(tiers),
+                enabled: true,
+                max_fees_per_period: None,
+                period: 0,
+                fee_free_below: 0,
+                rebate_bps: None,
+            },
+        );
+
+        let context = FeeContext {
+            multiplier_bps: 10_000, // 1x
+            additional_data: Map::new(&s._env),
+        };
+
+        // Case 1: Below threshold
+        assert_eq!(s.client.compute_fee(&game, &500, &context), 25); // 5% of 500
+
+        // Case 2: In first tier
+        assert_eq!(s.client.compute_fee(&game, &2000, &context), 60); // 3% of 2000
+
+        // Case 3: In second tier
+        assert_eq!(s.client.compute_fee(&game, &10000, &context), 100); // 1% of 10000
+    }
+
+    #[test]
+    fn test_compute_context_multiplier() {
+        let s = setup();
+        let game = symbol_short!("game1");
+
+        s.client.set_fee_rule(
+            &game,
+            &FeeRuleConfig {
+                base_fee_bps: 1000, // 10%
+                tiers: None,
+                enabled: true,
+                max_fees_per_period: None,
+                period: 0,
+                fee_free_below: 0,
+                rebate_bps: None,
+            },
+        );
+
+        // Promo: half fees
+        let context = FeeContext {
+            multiplier_bps: 5000, // 0.5x
+            additional_data: Map::new(&s._env),
+        };
+
+        let fee = s.client.compute_fee(&game, &1000, &context);
+        assert_eq!(fee, 50); // 10% halved = 5% -> 5% of 1000 = 50
+    }
+
+    #[test]
+    fn test_disabled_rule() {
         let s = setup();
         let game = symbol_short!("game1");
-        
-        let tiers = vec![&s._env, 
-            FeeTier { threshold: 1000, fee_bps: 300 }, // 3% for >= 1000
-            FeeTier { threshold: 5000, fee_bps: 100 }, // 1% for >= 5000
-        ];
 
-        s.client.set_fee_rule(&game, &FeeRuleConfig {
-            base_fee_bps: 500, // 5% base
-            tiers: Some(tiers),
+        s.client.set_fee_rule(
+            &game,
+            &FeeRuleConfig {
+                base_fee_bps: 500,
+                tiers: None,
+                enabled: false,
+                max_fees_per_period: None,
+                period: 0,
+                fee_free_below: 0,
+                rebate_bps: None,
+            },
+        );
+
+        let context = FeeContext {
+            multiplier_bps: 10_000,
+            additional_data: Map::new(&s._env),
+        };
+
+        let result = s.client.try_compute_fee(&game, &1000, &context);
+        assert_eq!(result, Err(Ok(Error::RuleDisabled)));
+    }
+
+    #[test]
+    fn test_default_rule_covers_unconfigured_games() {
+        let s = setup();
+        let configured = symbol_short!("game1");
+        let unconfigured = symbol_short!("game2");
+        let context = FeeContext {
+            multiplier_bps: 10_000,
+            additional_data: Map::new(&s._env),
+        };
+        let rule = |base_fee_bps: u32| FeeRuleConfig {
+            base_fee_bps,
+            tiers: None,
             enabled: true,
-        });
+            max_fees_per_period: None,
+            period: 0,
+            fee_free_below: 0,
+            rebate_bps: None,
+        };
+
+        // Without a default, unconfigured games still have no rule.
+        assert_eq!(s.client.default_rule(), None);
+        assert_eq!(
+            s.client.try_compute_fee(&unconfigured, &1000, &context),
+            Err(Ok(Error::RuleNotFound))
+        );
+
+        s.client.set_fee_rule(&configured, &rule(500));
+        s.client.set_default_rule(&s._admin, &rule(200));
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &DefaultRuleSet {
+                base_fee_bps: 200,
+                has_tiers: false,
+            }
+            .to_xdr(&s._env, &s.client.address)
+        );
+        assert_eq!(s.client.default_rule(), Some(rule(200)));
+
+        assert_eq!(s.client.compute_fee(&unconfigured, &1000, &context), 20);
+        assert_eq!(s.client.effective_bps(&unconfigured, &1000, &context), 200);
+        assert_eq!(s.client.compute_fee(&configured, &1000, &context), 50);
+    }
+
+    #[test]
+    fn test_default_rule_requires_admin_and_valid_config() {
+        let s = setup();
+        let mut rule = FeeRuleConfig {
+            base_fee_bps: 200,
+            tiers: None,
+            enabled: true,
+            max_fees_per_period: None,
+            period: 0,
+            fee_free_below: 0,
+            rebate_bps: None,
+        };
+
+        let stranger = Address::generate(&s._env);
+        assert_eq!(
+            s.client.try_set_default_rule(&stranger, &rule),
+            Err(Ok(Error::NotAuthorized))
+        );
+
+        rule.base_fee_bps = 10_001;
+        assert_eq!(
+            s.client.try_set_default_rule(&s._admin, &rule),
+            Err(Ok(Error::InvalidFeeConfig))
+        );
+        assert_eq!(s.client.default_rule(), None);
+    }
+
+    #[test]
+    fn test_user_fee_overrides_tiers_until_cleared() {
+        let s = setup();
+        let game = symbol_short!("game1");
+        let vip = Address::generate(&s._env);
+        let regular = Address::generate(&s._env);
+
+        s.client.set_fee_rule(
+            &game,
+            &FeeRuleConfig {
+                base_fee_bps: 500,
+                tiers: Some(vec![
+                    &s._env,
+                    FeeTier {
+                        threshold: 1000,
+                        fee_bps: 300,
+                    },
+                ]),
+                enabled: true,
+                max_fees_per_period: None,
+                period: 0,
+                fee_free_below: 0,
+                rebate_bps: None,
+            },
+        );
+        let context = |multiplier_bps: u32| FeeContext {
+            multiplier_bps,
+            additional_data: Map::new(&s._env),
+        };
+
+        assert_eq!(
+            s.client
+                .compute_fee_for(&game, &vip, &2000, &context(10_000)),
+            60
+        );
+
+        s.client.set_user_fee(&game, &vip, &100);
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &UserFeeSet {
+                game_id: game.clone(),
+                user: vip.clone(),
+                fee_bps: 100,
+            }
+            .to_xdr(&s._env, &s.client.address)
+        );
+        assert_eq!(s.client.user_fee(&game, &vip), Some(100));
+
+        // 1% instead of the 3% tier; the multiplier still applies on top.
+        assert_eq!(
+            s.client
+                .compute_fee_for(&game, &vip, &2000, &context(10_000)),
+            20
+        );
+        assert_eq!(
+            s.client
+                .compute_fee_for(&game, &vip, &2000, &context(5_000)),
+            10
+        );
+        assert_eq!(
+            s.client
+                .compute_fee_for(&game, &regular, &2000, &context(10_000)),
+            60
+        );
+
+        s.client.clear_user_fee(&game, &vip);
+        assert_eq!(s.client.user_fee(&game, &vip), None);
+        assert_eq!(
+            s.client
+                .compute_fee_for(&game, &vip, &2000, &context(10_000)),
+            60
+        );
+
+        assert_eq!(
+            s.client.try_set_user_fee(&game, &vip, &10_001),
+            Err(Ok(Error::InvalidFeeConfig))
+        );
+    }
+
+    #[test]
+    fn test_fee_cap_per_period() {
+        let s = setup();
+        let game = symbol_short!("game1");
+
+        s.client.set_fee_rule(
+            &game,
+            &FeeRuleConfig {
+                base_fee_bps: 500, // 5%
+                tiers: None,
+                enabled: true,
+                max_fees_per_period: Some(120),
+                period: 3_600,
+                fee_free_below: 0,
+                rebate_bps: None,
+            },
+        );
 
         let context = FeeContext {
-            multiplier_bps: 10_000, // 1x
+            multiplier_bps: 10_000,
             additional_data: Map::new(&s._env),
         };
 
-        // Case 1: Below threshold
-        assert_eq!(s.client.compute_fee(&game, &500, &context), 25); // 5% of 500
+        let collect = |amount: i128| {
+            s.client
+                .collect_fee(&s._admin, &game, &None, &amount, &context)
+        };
 
-        // Case 2: In first tier
-        assert_eq!(s.client.compute_fee(&game, &2000, &context), 60); // 3% of 2000
+        // Quoting a fee leaves the cap alone
+        assert_eq!(s.client.compute_fee(&game, &10_000, &context), 120);
+        assert_eq!(s.client.fee_period_usage(&game), None);
+
+        // Accumulate up to the cap within the period
+        assert_eq!(collect(1000), 50);
+        assert_eq!(collect(1000), 50);
+        assert_eq!(s.client.fee_period_usage(&game).unwrap().collected, 100);
+        assert_eq!(s.client.compute_fee(&game, &1000, &context), 20);
+
+        // Only the remaining headroom is charged, then nothing
+        assert_eq!(collect(1000), 20);
+        assert_eq!(collect(1000), 0);
+        assert_eq!(s.client.fee_period_usage(&game).unwrap().collected, 120);
+
+        // A new period resets the cumulative total
+        let now = s._env.ledger().timestamp();
+        s._env.ledger().with_mut(|l| l.timestamp = now + 3_600);
+        assert_eq!(collect(1000), 50);
+        let usage = s.client.fee_period_usage(&game).unwrap();
+        assert_eq!(usage.collected, 50);
+        assert_eq!(usage.period_start, now + 3_600);
+    }
 
-        // Case 3: In second tier
-        assert_eq!(s.client.compute_fee(&game, &10000, &context), 100); // 1% of 10000
+    #[test]
+    fn test_collect_fee_requires_registered_collector() {
+        let s = setup();
+        let game = symbol_short!("game1");
+        let mut rule = flat_rule(500);
+        rule.max_fees_per_period = Some(120);
+        rule.period = 3_600;
+        s.client.set_fee_rule(&game, &rule);
+        let context = FeeContext {
+            multiplier_bps: 10_000,
+            additional_data: Map::new(&s._env),
+        };
+
+        let collector = Address::generate(&s._env);
+        assert_eq!(
+            s.client
+                .try_collect_fee(&collector, &game, &None, &10_000, &context),
+            Err(Ok(Error::NotAuthorized))
+        );
+        assert_eq!(s.client.fee_period_usage(&game), None);
+
+        s.client.set_collector(&collector, &true);
+        assert!(s.client.is_collector(&collector));
+        assert_eq!(
+            s.client
+                .collect_fee(&collector, &game, &None, &1000, &context),
+            50
+        );
+        assert_eq!(s.client.fee_period_usage(&game).unwrap().collected, 50);
+
+        s.client.set_collector(&collector, &false);
+        assert!(!s.client.is_collector(&collector));
+        assert_eq!(
+            s.client
+                .try_collect_fee(&collector, &game, &None, &1000, &context),
+            Err(Ok(Error::NotAuthorized))
+        );
     }
 
     #[test]
-    fn test_compute_context_multiplier() {
+    fn test_fee_cap_requires_period() {
+        let s = setup();
+        let game = symbol_short!("game1");
+
+        let result = s.client.try_set_fee_rule(
+            &game,
+            &FeeRuleConfig {
+                base_fee_bps: 500,
+                tiers: None,
+                enabled: true,
+                max_fees_per_period: Some(100),
+                period: 0,
+                fee_free_below: 0,
+                rebate_bps: None,
+            },
+        );
+        assert_eq!(result, Err(Ok(Error::InvalidFeeConfig)));
+    }
+
+    #[test]
+    fn test_fee_free_band_bypasses_tiers_and_multiplier() {
+        let s = setup();
+        let game = symbol_short!("game1");
+
+        let tiers = vec![
+            &s._env,
+            FeeTier {
+                threshold: 0,
+                fee_bps: 300,
+            },
+        ];
+        s.client.set_fee_rule(
+            &game,
+            &FeeRuleConfig {
+                base_fee_bps: 500,
+                tiers: Some(tiers),
+                enabled: true,
+                max_fees_per_period: None,
+                period: 0,
+                fee_free_below: 1_000,
+                rebate_bps: None,
+            },
+        );
+
+        let context = FeeContext {
+            multiplier_bps: 20_000, // 2x
+            additional_data: Map::new(&s._env),
+        };
+
+        // Just below the threshold pays nothing
+        assert_eq!(s.client.compute_fee(&game, &999, &context), 0);
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &FeeComputed {
+                game_id: game.clone(),
+                original_amount: 999,
+                fee_amount: 0,
+                applied_bps: 0,
+                was_capped: false,
+                is_rebate: false,
+            }
+            .to_xdr(&s._env, &s.client.address)
+        );
+
+        // At the threshold the tier and multiplier apply as usual: 1000 * 6%
+        assert_eq!(s.client.compute_fee(&game, &1_000, &context), 60);
+    }
+
+    #[test]
+    fn test_fee_free_band_rejects_negative_threshold() {
+        let s = setup();
+        let mut rule = flat_rule(500);
+        rule.fee_free_below = -1;
+
+        let result = s.client.try_set_fee_rule(&symbol_short!("game1"), &rule);
+        assert_eq!(result, Err(Ok(Error::InvalidFeeConfig)));
+    }
+
+    #[test]
+    fn test_rebate_rule_returns_negative_fee() {
+        let s = setup();
+        let fee_game = symbol_short!("fee");
+        let promo = symbol_short!("promo");
+
+        s.client.set_fee_rule(&fee_game, &flat_rule(500));
+        let mut rebate = flat_rule(0);
+        rebate.rebate_bps = Some(200); // 2% credit
+        s.client.set_fee_rule(&promo, &rebate);
+
+        let context = FeeContext {
+            multiplier_bps: 10_000,
+            additional_data: Map::new(&s._env),
+        };
+
+        assert_eq!(s.client.compute_fee(&fee_game, &1_000, &context), 50);
+        assert_eq!(s.client.compute_fee(&promo, &1_000, &context), -20);
+
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &FeeComputed {
+                game_id: promo.clone(),
+                original_amount: 1_000,
+                fee_amount: -20,
+                applied_bps: 200,
+                was_capped: false,
+                is_rebate: true,
+            }
+            .to_xdr(&s._env, &s.client.address)
+        );
+
+        // A large multiplier cannot push the credit past the amount
+        let boosted = FeeContext {
+            multiplier_bps: 1_000_000, // 100x
+            additional_data: Map::new(&s._env),
+        };
+        assert_eq!(s.client.compute_fee(&promo, &1_000, &boosted), -1_000);
+    }
+
+    #[test]
+    fn test_rebate_rule_validation() {
+        let s = setup();
+        let game = symbol_short!("promo");
+
+        let mut rule = flat_rule(0);
+        rule.rebate_bps = Some(0);
+        assert_eq!(
+            s.client.try_set_fee_rule(&game, &rule),
+            Err(Ok(Error::InvalidFeeConfig))
+        );
+
+        rule.rebate_bps = Some(10_001);
+        assert_eq!(
+            s.client.try_set_fee_rule(&game, &rule),
+            Err(Ok(Error::InvalidFeeConfig))
+        );
+
+        rule.rebate_bps = Some(100);
+        rule.max_fees_per_period = Some(1_000);
+        rule.period = 3_600;
+        assert_eq!(
+            s.client.try_set_fee_rule(&game, &rule),
+            Err(Ok(Error::InvalidFeeConfig))
+        );
+    }
+
+    fn last_logged_bps(s: &Setup, game: &Symbol) -> u32 {
+        s.client.recent_fees(game, &1).get(0).unwrap().applied_bps
+    }
+
+    #[test]
+    fn test_effective_bps_matches_compute_fee() {
+        let s = setup();
+        let game = symbol_short!("game1");
+
+        let tiers = vec![
+            &s._env,
+            FeeTier {
+                threshold: 1000,
+                fee_bps: 300,
+            },
+            FeeTier {
+                threshold: 5000,
+                fee_bps: 100,
+            },
+        ];
+        let mut rule = flat_rule(500);
+        rule.tiers = Some(tiers);
+        s.client.set_fee_rule(&game, &rule);
+        s.client.set_fee_log_size(&game, &5);
+
+        let flat = FeeContext {
+            multiplier_bps: 10_000,
+            additional_data: Map::new(&s._env),
+        };
+        let boosted = FeeContext {
+            multiplier_bps: 15_000, // 1.5x
+            additional_data: Map::new(&s._env),
+        };
+
+        // Base, tiered and multiplied rates
+        for (amount, context, expected) in [
+            (500i128, &flat, 500u32),
+            (2_000, &flat, 300),
+            (6_000, &flat, 100),
+            (2_000, &boosted, 450),
+        ] {
+            let bps = s.client.effective_bps(&game, &amount, context);
+            assert_eq!(bps, expected);
+            s.client
+                .collect_fee(&s._admin, &game, &None, &amount, context);
+            assert_eq!(last_logged_bps(&s, &game), bps);
+        }
+
+        // Querying the rate leaves the log and period usage alone
+        let logged = s.client.recent_fees(&game, &10).len();
+        s.client.effective_bps(&game, &2_000, &flat);
+        assert_eq!(s.client.recent_fees(&game, &10).len(), logged);
+    }
+
+    #[test]
+    fn test_effective_bps_for_free_band_and_rebate() {
+        let s = setup();
+        let game = symbol_short!("promo");
+
+        let mut rule = flat_rule(0);
+        rule.rebate_bps = Some(200);
+        rule.fee_free_below = 100;
+        s.client.set_fee_rule(&game, &rule);
+        s.client.set_fee_log_size(&game, &5);
+
+        let context = FeeContext {
+            multiplier_bps: 20_000,
+            additional_data: Map::new(&s._env),
+        };
+
+        assert_eq!(s.client.effective_bps(&game, &99, &context), 0);
+        assert_eq!(s.client.effective_bps(&game, &1_000, &context), 400);
+        s.client
+            .collect_fee(&s._admin, &game, &None, &1_000, &context);
+        assert_eq!(last_logged_bps(&s, &game), 400);
+
+        assert_eq!(
+            s.client
+                .try_effective_bps(&symbol_short!("none"), &1_000, &context),
+            Err(Ok(Error::RuleNotFound))
+        );
+    }
+
+    #[test]
+    fn test_fee_cache_invalidated_on_rule_change() {
+        let s = setup();
+        let game = symbol_short!("game1");
+        s.client.set_cache_granularity(&100);
+
+        let tiers = vec![
+            &s._env,
+            FeeTier {
+                threshold: 1000,
+                fee_bps: 300,
+            },
+        ];
+        s.client.set_fee_rule(
+            &game,
+            &FeeRuleConfig {
+                base_fee_bps: 500,
+                tiers: Some(tiers),
+                enabled: true,
+                max_fees_per_period: None,
+                period: 0,
+                fee_free_below: 0,
+                rebate_bps: None,
+            },
+        );
+
+        let context = FeeContext {
+            multiplier_bps: 10_000,
+            additional_data: Map::new(&s._env),
+        };
+
+        // First computation populates the cache for the [1000, 1100) bucket
+        assert_eq!(s.client.cached_bps(&game, &1050), None);
+        assert_eq!(s.client.compute_fee(&game, &1000, &context), 30);
+        assert_eq!(s.client.cached_bps(&game, &1050), Some(300));
+        assert_eq!(s.client.compute_fee(&game, &1050, &context), 31);
+
+        // Changing the rule invalidates the cached bps
+        let tiers = vec![
+            &s._env,
+            FeeTier {
+                threshold: 1000,
+                fee_bps: 100,
+            },
+        ];
+        s.client.set_fee_rule(
+            &game,
+            &FeeRuleConfig {
+                base_fee_bps: 500,
+                tiers: Some(tiers),
+                enabled: true,
+                max_fees_per_period: None,
+                period: 0,
+                fee_free_below: 0,
+                rebate_bps: None,
+            },
+        );
+        assert_eq!(s.client.cached_bps(&game, &1000), None);
+        assert_eq!(s.client.compute_fee(&game, &1000, &context), 10);
+        assert_eq!(s.client.cached_bps(&game, &1000), Some(100));
+
+        // Toggling the rule also invalidates it
+        s.client.disable_rule(&game);
+        s.client.enable_rule(&game);
+        assert_eq!(s.client.cached_bps(&game, &1000), None);
+    }
+
+    #[test]
+    fn test_fee_cache_skips_buckets_straddling_a_threshold() {
         let s = setup();
         let game = symbol_short!("game1");
-        
-        s.client.set_fee_rule(&game, &FeeRuleConfig {
-            base_fee_bps: 1000, // 10%
+        s.client.set_cache_granularity(&100);
+
+        // 1050 is not a multiple of the granularity, so the [1000, 1100)
+        // bucket holds amounts on both sides of it.
+        let mut rule = flat_rule(500);
+        rule.tiers = Some(vec![
+            &s._env,
+            FeeTier {
+                threshold: 1050,
+                fee_bps: 300,
+            },
+        ]);
+        s.client.set_fee_rule(&game, &rule);
+
+        let context = FeeContext {
+            multiplier_bps: 10_000,
+            additional_data: Map::new(&s._env),
+        };
+
+        for (amount, expected_bps) in [(1_000i128, 500u32), (1_060, 300), (1_010, 500)] {
+            assert_eq!(
+                s.client.compute_fee(&game, &amount, &context),
+                amount * expected_bps as i128 / 10_000
+            );
+            assert_eq!(
+                s.client.effective_bps(&game, &amount, &context),
+                expected_bps
+            );
+        }
+        assert_eq!(s.client.cached_bps(&game, &1_000), None);
+
+        // Buckets wholly on one side of the threshold are still cached
+        s.client.compute_fee(&game, &1_150, &context);
+        assert_eq!(s.client.cached_bps(&game, &1_199), Some(300));
+        s.client.compute_fee(&game, &950, &context);
+        assert_eq!(s.client.cached_bps(&game, &900), Some(500));
+    }
+
+    fn flat_rule(fee_bps: u32) -> FeeRuleConfig {
+        FeeRuleConfig {
+            base_fee_bps: fee_bps,
             tiers: None,
             enabled: true,
-        });
+            max_fees_per_period: None,
+            period: 0,
+            fee_free_below: 0,
+            rebate_bps: None,
+        }
+    }
+
+    #[test]
+    fn test_experiment_bucketing_is_deterministic() {
+        let s = setup();
+        let game = symbol_short!("game1");
+        s.client.set_fee_rule(&game, &flat_rule(500));
+        s.client.set_experiment(
+            &game,
+            &FeeExperiment {
+                variant_a: flat_rule(100),
+                variant_b: flat_rule(300),
+                split_bps: 5_000,
+            },
+        );
 
-        // Promo: half fees
         let context = FeeContext {
-            multiplier_bps: 5000, // 0.5x
+            multiplier_bps: 10_000,
             additional_data: Map::new(&s._env),
         };
 
-        let fee = s.client.compute_fee(&game, &1000, &context);
-        assert_eq!(fee, 50); // 10% halved = 5% -> 5% of 1000 = 50
+        let account = Address::generate(&s._env);
+        assert_eq!(s.client.experiment_variant(&game, &account), None);
+        let first = s.client.compute_fee_for(&game, &account, &1000, &context);
+        let variant = s.client.experiment_variant(&game, &account).unwrap();
+        let expected = match variant {
+            FeeVariant::A => 10,
+            FeeVariant::B => 30,
+        };
+        assert_eq!(first, expected);
+
+        for _ in 0..3 {
+            assert_eq!(
+                s.client.compute_fee_for(&game, &account, &1000, &context),
+                first
+            );
+            assert_eq!(s.client.experiment_variant(&game, &account), Some(variant));
+        }
+
+        // Games without an experiment fall back to the regular rule.
+        let other = symbol_short!("game2");
+        s.client.set_fee_rule(&other, &flat_rule(500));
+        assert_eq!(
+            s.client.compute_fee_for(&other, &account, &1000, &context),
+            50
+        );
+        assert_eq!(s.client.experiment_variant(&other, &account), None);
     }
 
     #[test]
-    fn test_disabled_rule() {
+    fn test_experiment_split_roughly_matches() {
         let s = setup();
         let game = symbol_short!("game1");
-        
-        s.client.set_fee_rule(&game, &FeeRuleConfig {
-            base_fee_bps: 500,
-            tiers: None,
-            enabled: false,
-        });
+        s.client.set_experiment(
+            &game,
+            &FeeExperiment {
+                variant_a: flat_rule(100),
+                variant_b: flat_rule(300),
+                split_bps: 3_000,
+            },
+        );
 
         let context = FeeContext {
             multiplier_bps: 10_000,
             additional_data: Map::new(&s._env),
         };
 
-        let result = s.client.try_compute_fee(&game, &1000, &context);
-        assert_eq!(result, Err(Ok(Error::RuleDisabled)));
+        let total = 200u32;
+        let mut in_a = 0u32;
+        for _ in 0..total {
+            let account = Address::generate(&s._env);
+            s.client.compute_fee_for(&game, &account, &1000, &context);
+            if s.client.experiment_variant(&game, &account) == Some(FeeVariant::A) {
+                in_a += 1;
+            }
+        }
+
+        // Expect ~30% in A; allow a generous margin for hash variance.
+        assert!((35..=85).contains(&in_a), "{in_a} of {total} in variant A");
+    }
+
+    #[test]
+    fn test_experiment_split_out_of_range_rejected() {
+        let s = setup();
+        let result = s.client.try_set_experiment(
+            &symbol_short!("game1"),
+            &FeeExperiment {
+                variant_a: flat_rule(100),
+                variant_b: flat_rule(300),
+                split_bps: 10_001,
+            },
+        );
+        assert_eq!(result, Err(Ok(Error::InvalidFeeConfig)));
+    }
+
+    #[test]
+    fn test_fee_log_keeps_most_recent_records() {
+        let s = setup();
+        let game = symbol_short!("game1");
+        s.client.set_fee_rule(&game, &flat_rule(100));
+        let context = FeeContext {
+            multiplier_bps: 10_000,
+            additional_data: Map::new(&s._env),
+        };
+
+        // Logging is opt-in.
+        s.client
+            .collect_fee(&s._admin, &game, &None, &1_000, &context);
+        assert_eq!(s.client.recent_fees(&game, &10).len(), 0);
+
+        s.client.set_fee_log_size(&game, &3);
+        for (i, amount) in [1_000i128, 2_000, 3_000, 4_000, 5_000].iter().enumerate() {
+            s._env.ledger().with_mut(|l| l.timestamp = 100 + i as u64);
+            s.client
+                .collect_fee(&s._admin, &game, &None, amount, &context);
+        }
+
+        // Permissionless quotes are not logged.
+        s.client.compute_fee(&game, &6_000, &context);
+        s.client
+            .compute_fee_for(&game, &Address::generate(&s._env), &6_000, &context);
+
+        let record = |timestamp: u64, amount: i128| FeeRecord {
+            timestamp,
+            amount,
+            fee: amount / 100,
+            applied_bps: 100,
+        };
+        assert_eq!(
+            s.client.recent_fees(&game, &10),
+            vec![
+                &s._env,
+                record(104, 5_000),
+                record(103, 4_000),
+                record(102, 3_000)
+            ]
+        );
+        assert_eq!(
+            s.client.recent_fees(&game, &2),
+            vec![&s._env, record(104, 5_000), record(103, 4_000)]
+        );
+
+        // Other games are unaffected.
+        assert_eq!(s.client.recent_fees(&symbol_short!("game2"), &10).len(), 0);
+
+        assert_eq!(
+            s.client
+                .try_set_fee_log_size(&game, &(MAX_FEE_LOG_SIZE + 1)),
+            Err(Ok(Error::InvalidFeeConfig))
+        );
     }
 }
@@ -28,6 +28,7 @@ pub enum Error {
     TaskAlreadyExecuted = 6,
     TaskNotFound = 7,
     EpochNotReached = 8,
+    Overflow = 9,
 }
 
 // ---------------------------------------------------------------------------
@@ -71,6 +72,13 @@ pub struct TaskExecuted {
     pub task_id: Symbol,
 }
 
+#[contractevent]
+pub struct TaskRescheduled {
+    pub task_id: Symbol,
+    pub old_epoch: u64,
+    pub new_epoch: u64,
+}
+
 // ---------------------------------------------------------------------------
 // Contract
 // ---------------------------------------------------------------------------
@@ -152,6 +160,21 @@ impl EpochScheduler {
         Ok(())
     }
 
+    /// Schedule a task `epochs_ahead` epochs after the current one, so
+    /// callers need not read `current_epoch` first. Zero schedules it for
+    /// the current epoch.
+    pub fn schedule_in(
+        env: Env,
+        task_id: Symbol,
+        epochs_ahead: u64,
+        payload_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        let epoch = Self::current_epoch(env.clone())
+            .checked_add(epochs_ahead)
+            .ok_or(Error::Overflow)?;
+        Self::schedule_task(env, task_id, epoch, payload_hash)
+    }
+
     /// Mark a task as executed. Restricted to Admin.
     pub fn mark_executed(env: Env, task_id: Symbol) -> Result<(), Error> {
         let admin = Self::require_admin(&env)?;
@@ -182,6 +205,50 @@ impl EpochScheduler {
         Ok(())
     }
 
+    /// Move an unexecuted task to another epoch. Restricted to Admin.
+    ///
+    /// `new_epoch` may be earlier or later than the task's scheduled epoch,
+    /// but not before the current epoch (`InvalidScheduleEpoch`).
+    /// Scheduling is open, so there is no per-task scheduler to authorize.
+    pub fn reschedule_task(env: Env, task_id: Symbol, new_epoch: u64) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        admin.require_auth();
+
+        let key = DataKey::Task(task_id.clone());
+        let mut task: TaskData = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::TaskNotFound)?;
+
+        if task.executed {
+            return Err(Error::TaskAlreadyExecuted);
+        }
+
+        let current = Self::current_epoch(env.clone());
+        if new_epoch < current {
+            return Err(Error::InvalidScheduleEpoch);
+        }
+
+        let old_epoch = task.epoch;
+        task.epoch = new_epoch;
+        env.storage().persistent().set(&key, &task);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        TaskRescheduled {
+            task_id,
+            old_epoch,
+            new_epoch,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
     /// Query the state of a task.
     pub fn task_state(env: Env, task_id: Symbol) -> Option<TaskData> {
         env.storage().persistent().get(&DataKey::Task(task_id))
@@ -206,7 +273,7 @@ impl EpochScheduler {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env, BytesN, symbol_short};
+    use soroban_sdk::{testutils::{Address as _, Events, Ledger}, Address, Env, BytesN, symbol_short, Event as _};
 
     struct Setup<'a> {
         env: Env,
@@ -350,4 +417,124 @@ mod test {
         let result = s.client.try_schedule_task(&task_id, &4, &hash);
         assert_eq!(result, Err(Ok(Error::InvalidScheduleEpoch)));
     }
+
+    fn set_sequence(env: &Env, sequence_number: u32) {
+        env.ledger().set(soroban_sdk::testutils::LedgerInfo {
+            timestamp: 0,
+            protocol_version: 25,
+            sequence_number,
+            network_id: [0u8; 32],
+            base_reserve: 0,
+            min_temp_entry_ttl: 0,
+            min_persistent_entry_ttl: 0,
+            max_entry_ttl: 1000000,
+        });
+    }
+
+    #[test]
+    fn test_reschedule_task_forward() {
+        let s = setup();
+        let task_id = symbol_short!("task1");
+        let hash = BytesN::from_array(&s.env, &[0u8; 32]);
+
+        set_sequence(&s.env, 50); // Epoch 0
+        s.client.schedule_task(&task_id, &2, &hash);
+        s.client.reschedule_task(&task_id, &4);
+
+        let state = s.client.task_state(&task_id).unwrap();
+        assert_eq!(state.epoch, 4);
+        assert!(!state.executed);
+
+        // The old epoch no longer allows execution
+        set_sequence(&s.env, 250); // Epoch 2
+        let result = s.client.try_mark_executed(&task_id);
+        assert_eq!(result, Err(Ok(Error::EpochNotReached)));
+
+        set_sequence(&s.env, 450); // Epoch 4
+        s.client.mark_executed(&task_id);
+    }
+
+    #[test]
+    fn test_reschedule_task_earlier_but_not_into_past() {
+        let s = setup();
+        let task_id = symbol_short!("task1");
+        let hash = BytesN::from_array(&s.env, &[0u8; 32]);
+
+        set_sequence(&s.env, 150); // Epoch 1
+        s.client.schedule_task(&task_id, &5, &hash);
+
+        // In the past relative to the current epoch
+        let result = s.client.try_reschedule_task(&task_id, &0);
+        assert_eq!(result, Err(Ok(Error::InvalidScheduleEpoch)));
+        assert_eq!(s.client.task_state(&task_id).unwrap().epoch, 5);
+
+        // Earlier than the scheduled epoch but not in the past is fine
+        s.client.reschedule_task(&task_id, &1);
+        assert_eq!(s.client.task_state(&task_id).unwrap().epoch, 1);
+        s.client.mark_executed(&task_id);
+    }
+
+    #[test]
+    fn test_reschedule_overdue_task_keeps_identity() {
+        let s = setup();
+        let task_id = symbol_short!("task1");
+        let hash = BytesN::from_array(&s.env, &[7u8; 32]);
+
+        set_sequence(&s.env, 50); // Epoch 0
+        s.client.schedule_task(&task_id, &2, &hash);
+
+        // The current epoch has moved past the task's epoch without it running.
+        set_sequence(&s.env, 350); // Epoch 3
+        let result = s.client.try_reschedule_task(&task_id, &2);
+        assert_eq!(result, Err(Ok(Error::InvalidScheduleEpoch)));
+
+        // A reschedule is reported as such, not as a new schedule.
+        s.client.reschedule_task(&task_id, &6);
+        let events = s.env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(events.events().len(), 1);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &TaskRescheduled {
+                task_id: task_id.clone(),
+                old_epoch: 2,
+                new_epoch: 6,
+            }
+            .to_xdr(&s.env, &s.client.address)
+        );
+
+        let state = s.client.task_state(&task_id).unwrap();
+        assert_eq!(state.epoch, 6);
+        assert_eq!(state.payload_hash, hash);
+        assert!(!state.executed);
+    }
+
+    #[test]
+    fn test_reschedule_executed_task_rejected() {
+        let s = setup();
+        let task_id = symbol_short!("task1");
+        let hash = BytesN::from_array(&s.env, &[0u8; 32]);
+
+        set_sequence(&s.env, 50);
+        s.client.schedule_task(&task_id, &0, &hash);
+        s.client.mark_executed(&task_id);
+
+        let result = s.client.try_reschedule_task(&task_id, &3);
+        assert_eq!(result, Err(Ok(Error::TaskAlreadyExecuted)));
+    }
+
+    #[test]
+    fn test_schedule_in_is_relative_to_current_epoch() {
+        let s = setup();
+        let task_id = symbol_short!("task1");
+        let hash = BytesN::from_array(&s.env, &[0u8; 32]);
+
+        set_sequence(&s.env, 750); // Epoch 7
+        s.client.schedule_in(&task_id, &3, &hash);
+        assert_eq!(s.client.task_state(&task_id).unwrap().epoch, 10);
+
+        let result = s
+            .client
+            .try_schedule_in(&symbol_short!("task2"), &u64::MAX, &hash);
+        assert_eq!(result, Err(Ok(Error::Overflow)));
+    }
 }
@@ -1,9 +1,10 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env, Symbol,
+    contract, contractclient, contractimpl, contracttype, symbol_short,
+    token, Address, Bytes, BytesN, Env, Symbol, Vec,
 };
+use stellarcade_shared::check_transition;
 
 // ── Storage Keys ─────────────────────────────────────────────────
 #[contracttype]
@@ -12,7 +13,18 @@ pub enum DataKey {
     Admin,
     Token,
     Escrow(u64),         // escrow_id → EscrowState
+    Milestones(u64),     // escrow_id → Vec<Milestone>
     NextId,
+    TotalLocked,         // sum still held across active escrows
+    Condition(u64),      // escrow_id → EscrowCondition
+}
+
+// ── External Contracts ───────────────────────────────────────────
+/// The subset of the OracleIntegration interface used to resolve escrow
+/// conditions.
+#[contractclient(name = "ConditionOracleClient")]
+pub trait ConditionOracle {
+    fn latest(env: Env, feed_id: BytesN<32>) -> Option<Bytes>;
 }
 
 // ── Domain Types ─────────────────────────────────────────────────
@@ -24,6 +36,11 @@ pub enum EscrowStatus {
     Cancelled,
 }
 
+const ESCROW_TRANSITIONS: &[(EscrowStatus, EscrowStatus)] = &[
+    (EscrowStatus::Active, EscrowStatus::Released),
+    (EscrowStatus::Active, EscrowStatus::Cancelled),
+];
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EscrowState {
@@ -33,6 +50,28 @@ pub struct EscrowState {
     pub amount: i128,
     pub terms_hash: Symbol,
     pub status: EscrowStatus,
+    /// Ledger timestamp at which the escrow was funded.
+    pub created_at: u64,
+    /// Ledger timestamp at which the escrow was fully released or
+    /// cancelled; `None` while still active.
+    pub released_at: Option<u64>,
+}
+
+/// One predefined release tranche of a milestone escrow.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Milestone {
+    pub amount: i128,
+    pub released: bool,
+}
+
+/// External condition gating `release_if_met`. The condition is met once
+/// `oracle.latest(condition_id)` holds a payload whose first byte is non-zero.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowCondition {
+    pub oracle: Address,
+    pub condition_id: BytesN<32>,
 }
 
 // ── Events ────────────────────────────────────────────────────────
@@ -62,6 +101,15 @@ pub struct EscrowCancelled {
     pub amount: i128,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneReleased {
+    pub escrow_id: u64,
+    pub index: u32,
+    pub payee: Address,
+    pub amount: i128,
+}
+
 // ── Contract ──────────────────────────────────────────────────────
 #[contract]
 pub struct EscrowVault;
@@ -94,36 +142,92 @@ impl EscrowVault {
         let token_client = token::Client::new(&env, &token_addr);
         token_client.transfer(&payer, &env.current_contract_address(), &amount);
 
-        // Assign ID
-        let escrow_id: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::NextId)
-            .unwrap_or(0);
-        env.storage()
-            .instance()
-            .set(&DataKey::NextId, &(escrow_id.checked_add(1).expect("Overflow")));
-
-        let state = EscrowState {
-            escrow_id,
-            payer: payer.clone(),
-            payee: payee.clone(),
-            amount,
-            terms_hash: terms_hash.clone(),
-            status: EscrowStatus::Active,
-        };
-        env.storage().persistent().set(&DataKey::Escrow(escrow_id), &state);
+        Self::open_escrow(&env, payer, payee, amount, terms_hash)
+    }
 
-        env.events().publish(
-            (symbol_short!("created"),),
-            EscrowCreated { escrow_id, payer, payee, amount, terms_hash },
+    /// Create an escrow whose release is gated on an external condition, such
+    /// as a game result published to an OracleIntegration feed. Anyone may
+    /// call `release_if_met` once the condition resolves; the payer and admin
+    /// can still release or cancel it as usual.
+    pub fn create_conditional_escrow(
+        env: Env,
+        payer: Address,
+        payee: Address,
+        amount: i128,
+        terms_hash: Symbol,
+        condition_oracle: Address,
+        condition_id: BytesN<32>,
+    ) -> u64 {
+        let escrow_id = Self::create_escrow(env.clone(), payer, payee, amount, terms_hash);
+        env.storage().persistent().set(
+            &DataKey::Condition(escrow_id),
+            &EscrowCondition { oracle: condition_oracle, condition_id },
         );
+        escrow_id
+    }
+
+    /// Create one escrow per `(payee, amount, terms_hash)` entry, funded by a
+    /// single transfer of the combined amount from `payer`. All amounts are
+    /// validated before anything moves. Returns the new ids in entry order.
+    pub fn create_escrow_batch(
+        env: Env,
+        payer: Address,
+        entries: Vec<(Address, i128, Symbol)>,
+    ) -> Vec<u64> {
+        assert!(!entries.is_empty(), "Entries must not be empty");
+        payer.require_auth();
+
+        let mut total: i128 = 0;
+        for (_, amount, _) in entries.iter() {
+            assert!(amount > 0, "Amount must be positive");
+            total = total.checked_add(amount).expect("Overflow");
+        }
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::Token).expect("Not initialized");
+        let token_client = token::Client::new(&env, &token_addr);
+        token_client.transfer(&payer, &env.current_contract_address(), &total);
+
+        let mut ids: Vec<u64> = Vec::new(&env);
+        for (payee, amount, terms_hash) in entries.iter() {
+            ids.push_back(Self::open_escrow(&env, payer.clone(), payee, amount, terms_hash));
+        }
+        ids
+    }
+
+    /// Create an escrow that releases in predefined tranches. The payer locks
+    /// the sum of `milestones` into the contract; each entry is one tranche
+    /// released independently through `release_milestone`.
+    pub fn create_milestone_escrow(
+        env: Env,
+        payer: Address,
+        payee: Address,
+        milestones: Vec<i128>,
+        terms_hash: Symbol,
+    ) -> u64 {
+        assert!(!milestones.is_empty(), "Milestones must not be empty");
+        payer.require_auth();
+
+        let mut amount: i128 = 0;
+        let mut schedule: Vec<Milestone> = Vec::new(&env);
+        for tranche in milestones.iter() {
+            assert!(tranche > 0, "Milestone amount must be positive");
+            amount = amount.checked_add(tranche).expect("Overflow");
+            schedule.push_back(Milestone { amount: tranche, released: false });
+        }
 
+        let token_addr: Address = env.storage().instance().get(&DataKey::Token).expect("Not initialized");
+        let token_client = token::Client::new(&env, &token_addr);
+        token_client.transfer(&payer, &env.current_contract_address(), &amount);
+
+        let escrow_id = Self::open_escrow(&env, payer, payee, amount, terms_hash);
+        env.storage().persistent().set(&DataKey::Milestones(escrow_id), &schedule);
         escrow_id
     }
 
-    /// Release escrow funds to the payee. Only the admin or payer may release.
-    pub fn release_escrow(env: Env, caller: Address, escrow_id: u64) {
+    /// Release a single milestone tranche to the payee. Only the admin or
+    /// payer may approve a release. Milestones may be released in any order,
+    /// each at most once; the escrow becomes `Released` once all have fired.
+    pub fn release_milestone(env: Env, caller: Address, escrow_id: u64, index: u32) {
         caller.require_auth();
 
         let mut state: EscrowState = env
@@ -132,10 +236,9 @@ impl EscrowVault {
             .get(&DataKey::Escrow(escrow_id))
             .expect("Escrow not found");
 
-        assert!(
-            state.status == EscrowStatus::Active,
-            "Escrow is not active"
-        );
+        if check_transition(&state.status, &EscrowStatus::Released, ESCROW_TRANSITIONS).is_err() {
+            panic!("Escrow is not active");
+        }
 
         let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
         assert!(
@@ -143,25 +246,111 @@ impl EscrowVault {
             "Unauthorized: must be admin or payer"
         );
 
-        state.status = EscrowStatus::Released;
-        env.storage().persistent().set(&DataKey::Escrow(escrow_id), &state);
+        let mut schedule: Vec<Milestone> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Milestones(escrow_id))
+            .expect("Not a milestone escrow");
+        let mut milestone = schedule.get(index).expect("Milestone out of range");
+        assert!(!milestone.released, "Milestone already released");
+
+        milestone.released = true;
+        schedule.set(index, milestone.clone());
+        env.storage().persistent().set(&DataKey::Milestones(escrow_id), &schedule);
+
+        Self::adjust_total_locked(&env, -milestone.amount);
+
+        if schedule.iter().all(|m| m.released) {
+            state.status = EscrowStatus::Released;
+            state.released_at = Some(env.ledger().timestamp());
+            env.storage().persistent().set(&DataKey::Escrow(escrow_id), &state);
+        }
 
-        // Transfer to payee
         let token_addr: Address = env.storage().instance().get(&DataKey::Token).expect("Not initialized");
         let token_client = token::Client::new(&env, &token_addr);
         token_client.transfer(
             &env.current_contract_address(),
             &state.payee,
-            &state.amount,
+            &milestone.amount,
         );
 
         env.events().publish(
-            (symbol_short!("released"),),
-            EscrowReleased { escrow_id, payee: state.payee, amount: state.amount },
+            (symbol_short!("mile_rel"),),
+            MilestoneReleased { escrow_id, index, payee: state.payee, amount: milestone.amount },
+        );
+    }
+
+    /// Read the milestone schedule of a milestone escrow.
+    pub fn milestones(env: Env, escrow_id: u64) -> Vec<Milestone> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Milestones(escrow_id))
+            .expect("Not a milestone escrow")
+    }
+
+    /// Release escrow funds to the payee. Only the admin or payer may release.
+    /// Milestone escrows must be released through `release_milestone`.
+    pub fn release_escrow(env: Env, caller: Address, escrow_id: u64) {
+        caller.require_auth();
+
+        let state: EscrowState = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(escrow_id))
+            .expect("Escrow not found");
+
+        if check_transition(&state.status, &EscrowStatus::Released, ESCROW_TRANSITIONS).is_err() {
+            panic!("Escrow is not active");
+        }
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        assert!(
+            caller == admin || caller == state.payer,
+            "Unauthorized: must be admin or payer"
+        );
+        assert!(
+            !env.storage().persistent().has(&DataKey::Milestones(escrow_id)),
+            "Use release_milestone for milestone escrows"
         );
+
+        Self::pay_out(&env, state);
+    }
+
+    /// Release a conditional escrow to the payee once its oracle reports the
+    /// condition as met. Callable by anyone; the oracle is the only gate.
+    pub fn release_if_met(env: Env, escrow_id: u64) {
+        let state: EscrowState = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(escrow_id))
+            .expect("Escrow not found");
+
+        if check_transition(&state.status, &EscrowStatus::Released, ESCROW_TRANSITIONS).is_err() {
+            panic!("Escrow is not active");
+        }
+
+        let condition: EscrowCondition = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Condition(escrow_id))
+            .expect("Escrow has no condition");
+
+        let outcome = ConditionOracleClient::new(&env, &condition.oracle).latest(&condition.condition_id);
+        let met = outcome.and_then(|payload| payload.first()).is_some_and(|flag| flag != 0);
+        if !met {
+            panic!("ConditionNotMet");
+        }
+
+        Self::pay_out(&env, state);
+    }
+
+    /// Read the release condition of a conditional escrow, if any.
+    pub fn escrow_condition(env: Env, escrow_id: u64) -> Option<EscrowCondition> {
+        env.storage().persistent().get(&DataKey::Condition(escrow_id))
     }
 
     /// Cancel an active escrow and return funds to the payer. Admin-only.
+    /// For milestone escrows only the unreleased tranches are refunded.
     pub fn cancel_escrow(env: Env, escrow_id: u64) {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
         admin.require_auth();
@@ -172,25 +361,28 @@ impl EscrowVault {
             .get(&DataKey::Escrow(escrow_id))
             .expect("Escrow not found");
 
-        assert!(
-            state.status == EscrowStatus::Active,
-            "Escrow is not active"
-        );
+        if check_transition(&state.status, &EscrowStatus::Cancelled, ESCROW_TRANSITIONS).is_err() {
+            panic!("Escrow is not active");
+        }
 
         state.status = EscrowStatus::Cancelled;
+        state.released_at = Some(env.ledger().timestamp());
         env.storage().persistent().set(&DataKey::Escrow(escrow_id), &state);
 
+        let refund = Self::unreleased_amount(&env, &state);
+        Self::adjust_total_locked(&env, -refund);
+
         let token_addr: Address = env.storage().instance().get(&DataKey::Token).expect("Not initialized");
         let token_client = token::Client::new(&env, &token_addr);
         token_client.transfer(
             &env.current_contract_address(),
             &state.payer,
-            &state.amount,
+            &refund,
         );
 
         env.events().publish(
             (symbol_short!("cancel"),),
-            EscrowCancelled { escrow_id, payer: state.payer, amount: state.amount },
+            EscrowCancelled { escrow_id, payer: state.payer, amount: refund },
         );
     }
 
@@ -201,6 +393,121 @@ impl EscrowVault {
             .get(&DataKey::Escrow(escrow_id))
             .expect("Escrow not found")
     }
+
+    /// Seconds the escrow's funds have been locked: up to now while active,
+    /// or the final duration once released or cancelled. Intended for
+    /// off-chain interest settlement.
+    pub fn lock_duration(env: Env, escrow_id: u64) -> u64 {
+        let state = Self::escrow_state(env.clone(), escrow_id);
+        let end = state.released_at.unwrap_or_else(|| env.ledger().timestamp());
+        end.saturating_sub(state.created_at)
+    }
+
+    /// Total amount currently held across all active escrows, including the
+    /// unreleased tranches of milestone escrows.
+    pub fn total_locked(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(0)
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn adjust_total_locked(env: &Env, delta: i128) {
+        let total: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(0);
+        let total = total.checked_add(delta).expect("Overflow");
+        assert!(total >= 0, "Total locked underflow");
+        env.storage().instance().set(&DataKey::TotalLocked, &total);
+    }
+
+    /// Mark a non-milestone escrow released and transfer its amount to the
+    /// payee. Callers must already have checked the transition.
+    fn pay_out(env: &Env, mut state: EscrowState) {
+        let escrow_id = state.escrow_id;
+        state.status = EscrowStatus::Released;
+        state.released_at = Some(env.ledger().timestamp());
+        env.storage().persistent().set(&DataKey::Escrow(escrow_id), &state);
+        Self::adjust_total_locked(env, -state.amount);
+
+        // Transfer to payee
+        let token_addr: Address = env.storage().instance().get(&DataKey::Token).expect("Not initialized");
+        let token_client = token::Client::new(env, &token_addr);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &state.payee,
+            &state.amount,
+        );
+
+        env.events().publish(
+            (symbol_short!("released"),),
+            EscrowReleased { escrow_id, payee: state.payee, amount: state.amount },
+        );
+    }
+
+    /// Record a freshly funded escrow and announce it. The caller must
+    /// already have moved `amount` into the vault.
+    fn open_escrow(
+        env: &Env,
+        payer: Address,
+        payee: Address,
+        amount: i128,
+        terms_hash: Symbol,
+    ) -> u64 {
+        let escrow_id = Self::next_escrow_id(env);
+        Self::adjust_total_locked(env, amount);
+
+        let state = EscrowState {
+            escrow_id,
+            payer: payer.clone(),
+            payee: payee.clone(),
+            amount,
+            terms_hash: terms_hash.clone(),
+            status: EscrowStatus::Active,
+            created_at: env.ledger().timestamp(),
+            released_at: None,
+        };
+        env.storage().persistent().set(&DataKey::Escrow(escrow_id), &state);
+
+        env.events().publish(
+            (symbol_short!("created"),),
+            EscrowCreated { escrow_id, payer, payee, amount, terms_hash },
+        );
+
+        escrow_id
+    }
+
+    fn next_escrow_id(env: &Env) -> u64 {
+        let escrow_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextId)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextId, &(escrow_id.checked_add(1).expect("Overflow")));
+        escrow_id
+    }
+
+    /// Amount still held for an escrow: the full amount, or for milestone
+    /// escrows the sum of tranches not yet released.
+    fn unreleased_amount(env: &Env, state: &EscrowState) -> i128 {
+        let schedule: Option<Vec<Milestone>> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Milestones(state.escrow_id));
+        match schedule {
+            Some(schedule) => schedule
+                .iter()
+                .filter(|m| !m.released)
+                .fold(0i128, |acc, m| acc + m.amount),
+            None => state.amount,
+        }
+    }
 }
 
 // ── Tests ─────────────────────────────────────────────────────────
@@ -208,11 +515,49 @@ impl EscrowVault {
 mod test {
     use super::*;
     use soroban_sdk::{
+        contract,
         testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
         token::{Client as TokenClient, StellarAssetClient},
         Env,
     };
 
+    /// Stand-in for OracleIntegration: serves whatever payload was last set.
+    #[contract]
+    pub struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn set(env: Env, feed_id: BytesN<32>, payload: Bytes) {
+            env.storage().persistent().set(&feed_id, &payload);
+        }
+
+        pub fn latest(env: Env, feed_id: BytesN<32>) -> Option<Bytes> {
+            env.storage().persistent().get(&feed_id)
+        }
+    }
+
+    fn setup_conditional_escrow<'a>(
+        env: &Env,
+    ) -> (EscrowVaultClient<'a>, MockOracleClient<'a>, TokenClient<'a>, Address, u64) {
+        env.mock_all_auths();
+        let admin = Address::generate(env);
+        let payer = Address::generate(env);
+        let payee = Address::generate(env);
+
+        let (token_id, sa_client, token_client) = create_token(env, &admin);
+        sa_client.mint(&payer, &1000);
+
+        let client = EscrowVaultClient::new(env, &env.register_contract(None, EscrowVault));
+        let oracle = MockOracleClient::new(env, &env.register_contract(None, MockOracle));
+        client.init(&admin, &token_id);
+
+        let condition_id = BytesN::from_array(env, &[7u8; 32]);
+        let id = client.create_conditional_escrow(
+            &payer, &payee, &400, &symbol_short!("GAME1"), &oracle.address, &condition_id,
+        );
+        (client, oracle, token_client, payee, id)
+    }
+
     fn create_token<'a>(env: &Env, admin: &Address) -> (Address, StellarAssetClient<'a>, TokenClient<'a>) {
         let sac = env.register_stellar_asset_contract_v2(admin.clone());
         let addr = sac.address();
@@ -312,4 +657,271 @@ mod test {
         client.init(&admin, &token);
         client.init(&admin, &token);
     }
+
+    fn setup_milestone_escrow<'a>(
+        env: &'a Env,
+    ) -> (EscrowVaultClient<'a>, TokenClient<'a>, Address, Address, Address, u64) {
+        let admin = Address::generate(env);
+        let payer = Address::generate(env);
+        let payee = Address::generate(env);
+
+        let (token_id, sa_client, token_client) = create_token(env, &admin);
+        sa_client.mint(&payer, &1000);
+
+        let contract_id = env.register_contract(None, EscrowVault);
+        let client = EscrowVaultClient::new(env, &contract_id);
+        client.init(&admin, &token_id);
+
+        let tranches = soroban_sdk::vec![env, 100i128, 200i128, 300i128];
+        let id = client.create_milestone_escrow(&payer, &payee, &tranches, &symbol_short!("BUILD"));
+        (client, token_client, admin, payer, payee, id)
+    }
+
+    #[test]
+    fn test_milestones_release_out_of_order() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, token_client, admin, payer, payee, id) = setup_milestone_escrow(&env);
+
+        assert_eq!(client.escrow_state(&id).amount, 600);
+        assert_eq!(token_client.balance(&payer), 400);
+
+        client.release_milestone(&admin, &id, &2);
+        assert_eq!(token_client.balance(&payee), 300);
+        client.release_milestone(&payer, &id, &0);
+        assert_eq!(token_client.balance(&payee), 400);
+        assert_eq!(client.escrow_state(&id).status, EscrowStatus::Active);
+
+        client.release_milestone(&admin, &id, &1);
+        assert_eq!(token_client.balance(&payee), 600);
+        assert_eq!(client.escrow_state(&id).status, EscrowStatus::Released);
+        assert!(client.milestones(&id).iter().all(|m| m.released));
+    }
+
+    #[test]
+    #[should_panic(expected = "Milestone already released")]
+    fn test_milestone_releases_only_once() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _, admin, _, _, id) = setup_milestone_escrow(&env);
+
+        client.release_milestone(&admin, &id, &1);
+        client.release_milestone(&admin, &id, &1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Milestone out of range")]
+    fn test_milestone_out_of_range_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _, admin, _, _, id) = setup_milestone_escrow(&env);
+
+        client.release_milestone(&admin, &id, &3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Escrow is not active")]
+    fn test_milestone_release_rejected_after_cancel() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _, admin, _, _, id) = setup_milestone_escrow(&env);
+
+        client.cancel_escrow(&id);
+        client.release_milestone(&admin, &id, &0);
+    }
+
+    #[test]
+    fn test_cancel_milestone_escrow_refunds_unreleased() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, token_client, admin, payer, payee, id) = setup_milestone_escrow(&env);
+
+        client.release_milestone(&admin, &id, &1);
+        client.cancel_escrow(&id);
+
+        assert_eq!(token_client.balance(&payee), 200);
+        assert_eq!(token_client.balance(&payer), 800);
+    }
+
+    #[test]
+    fn test_total_locked_tracks_escrows() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let payer = Address::generate(&env);
+        let payee = Address::generate(&env);
+
+        let (token_id, sa_client, token_client) = create_token(&env, &admin);
+        sa_client.mint(&payer, &1000);
+
+        let contract_id = env.register_contract(None, EscrowVault);
+        let client = EscrowVaultClient::new(&env, &contract_id);
+        client.init(&admin, &token_id);
+        assert_eq!(client.total_locked(), 0);
+
+        let a = client.create_escrow(&payer, &payee, &100, &symbol_short!("A"));
+        let b = client.create_escrow(&payer, &payee, &200, &symbol_short!("B"));
+        let tranches = soroban_sdk::vec![&env, 50i128, 150i128];
+        let m = client.create_milestone_escrow(&payer, &payee, &tranches, &symbol_short!("M"));
+        assert_eq!(client.total_locked(), 500);
+
+        client.release_escrow(&payer, &a);
+        assert_eq!(client.total_locked(), 400);
+
+        client.cancel_escrow(&b);
+        assert_eq!(client.total_locked(), 200);
+
+        client.release_milestone(&admin, &m, &1);
+        assert_eq!(client.total_locked(), 50);
+
+        client.cancel_escrow(&m);
+        assert_eq!(client.total_locked(), 0);
+        assert_eq!(token_client.balance(&contract_id), client.total_locked());
+    }
+
+    #[test]
+    fn test_create_escrow_batch_single_transfer() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let payer = Address::generate(&env);
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        let p3 = Address::generate(&env);
+
+        let (token_id, sa_client, token_client) = create_token(&env, &admin);
+        sa_client.mint(&payer, &1000);
+
+        let contract_id = env.register_contract(None, EscrowVault);
+        let client = EscrowVaultClient::new(&env, &contract_id);
+        client.init(&admin, &token_id);
+
+        let entries = soroban_sdk::vec![
+            &env,
+            (p1.clone(), 100i128, symbol_short!("A")),
+            (p2.clone(), 200i128, symbol_short!("B")),
+            (p3.clone(), 300i128, symbol_short!("C")),
+        ];
+        let ids = client.create_escrow_batch(&payer, &entries);
+
+        assert_eq!(ids, soroban_sdk::vec![&env, 0u64, 1u64, 2u64]);
+        assert_eq!(token_client.balance(&payer), 400);
+        assert_eq!(token_client.balance(&contract_id), 600);
+        assert_eq!(client.total_locked(), 600);
+
+        let second = client.escrow_state(&ids.get(1).unwrap());
+        assert_eq!(second.payee, p2);
+        assert_eq!(second.amount, 200);
+
+        client.release_escrow(&payer, &ids.get(2).unwrap());
+        assert_eq!(token_client.balance(&p3), 300);
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount must be positive")]
+    fn test_create_escrow_batch_rejects_non_positive_amount() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let payer = Address::generate(&env);
+        let payee = Address::generate(&env);
+
+        let (token_id, sa_client, _) = create_token(&env, &admin);
+        sa_client.mint(&payer, &1000);
+
+        let contract_id = env.register_contract(None, EscrowVault);
+        let client = EscrowVaultClient::new(&env, &contract_id);
+        client.init(&admin, &token_id);
+
+        let entries = soroban_sdk::vec![
+            &env,
+            (payee.clone(), 100i128, symbol_short!("A")),
+            (payee, 0i128, symbol_short!("B")),
+        ];
+        client.create_escrow_batch(&payer, &entries);
+    }
+
+    #[test]
+    fn test_lock_duration_tracks_timestamps() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|l| l.timestamp = 1_000);
+
+        let admin = Address::generate(&env);
+        let payer = Address::generate(&env);
+        let payee = Address::generate(&env);
+
+        let (token_id, sa_client, _) = create_token(&env, &admin);
+        sa_client.mint(&payer, &1000);
+
+        let contract_id = env.register_contract(None, EscrowVault);
+        let client = EscrowVaultClient::new(&env, &contract_id);
+        client.init(&admin, &token_id);
+
+        let released = client.create_escrow(&payer, &payee, &100, &symbol_short!("LOCK1"));
+        let cancelled = client.create_escrow(&payer, &payee, &100, &symbol_short!("LOCK2"));
+        let state = client.escrow_state(&released);
+        assert_eq!(state.created_at, 1_000);
+        assert_eq!(state.released_at, None);
+
+        // Active escrows report time locked so far.
+        env.ledger().with_mut(|l| l.timestamp = 4_600);
+        assert_eq!(client.lock_duration(&released), 3_600);
+
+        client.release_escrow(&payer, &released);
+        env.ledger().with_mut(|l| l.timestamp = 8_200);
+        client.cancel_escrow(&cancelled);
+
+        // Closed escrows report the final duration.
+        env.ledger().with_mut(|l| l.timestamp = 100_000);
+        assert_eq!(client.escrow_state(&released).released_at, Some(4_600));
+        assert_eq!(client.lock_duration(&released), 3_600);
+        assert_eq!(client.lock_duration(&cancelled), 7_200);
+    }
+
+    #[test]
+    fn test_release_if_met_pays_payee() {
+        let env = Env::default();
+        let (client, oracle, token_client, payee, id) = setup_conditional_escrow(&env);
+        let condition_id = BytesN::from_array(&env, &[7u8; 32]);
+        assert_eq!(
+            client.escrow_condition(&id),
+            Some(EscrowCondition { oracle: oracle.address.clone(), condition_id: condition_id.clone() })
+        );
+
+        oracle.set(&condition_id, &Bytes::from_array(&env, &[1]));
+        client.release_if_met(&id);
+
+        assert_eq!(token_client.balance(&payee), 400);
+        assert_eq!(client.escrow_state(&id).status, EscrowStatus::Released);
+        assert_eq!(client.total_locked(), 0);
+    }
+
+    #[test]
+    fn test_release_if_met_blocked_while_unmet() {
+        let env = Env::default();
+        let (client, oracle, token_client, payee, id) = setup_conditional_escrow(&env);
+        let condition_id = BytesN::from_array(&env, &[7u8; 32]);
+
+        // Unresolved, then resolved as not met.
+        assert!(client.try_release_if_met(&id).is_err());
+        oracle.set(&condition_id, &Bytes::from_array(&env, &[0]));
+        assert!(client.try_release_if_met(&id).is_err());
+
+        assert_eq!(token_client.balance(&payee), 0);
+        assert_eq!(client.escrow_state(&id).status, EscrowStatus::Active);
+        assert_eq!(client.total_locked(), 400);
+    }
+
+    #[test]
+    #[should_panic(expected = "ConditionNotMet")]
+    fn test_release_if_met_panics_when_unmet() {
+        let env = Env::default();
+        let (client, oracle, _, _, id) = setup_conditional_escrow(&env);
+        oracle.set(&BytesN::from_array(&env, &[7u8; 32]), &Bytes::from_array(&env, &[0]));
+        client.release_if_met(&id);
+    }
 }
@@ -19,6 +19,7 @@ pub enum DataKey {
     Admin,
     Methods,
     MethodProfile(Symbol),
+    Reporter(Address),
 }
 
 #[contracttype]
@@ -74,17 +75,54 @@ impl GasOptimizationAnalysis {
             return Err(Error::InvalidMetric);
         }
 
-        let key = DataKey::MethodProfile(method.clone());
-        let mut profile: MethodProfile = env.storage().persistent().get(&key).unwrap_or_default();
+        Ok(apply_sample(&env, method, cpu, read_bytes, write_bytes))
+    }
 
-        profile.calls = profile.calls.saturating_add(1);
-        profile.total_cpu = profile.total_cpu.saturating_add(cpu);
-        profile.total_read_bytes = profile.total_read_bytes.saturating_add(read_bytes);
-        profile.total_write_bytes = profile.total_write_bytes.saturating_add(write_bytes);
+    pub fn set_reporter(
+        env: Env,
+        admin: Address,
+        reporter: Address,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        let key = DataKey::Reporter(reporter);
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        Ok(())
+    }
 
-        env.storage().persistent().set(&key, &profile);
-        register_method(&env, method);
-        Ok(profile)
+    pub fn is_reporter(env: Env, reporter: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Reporter(reporter))
+    }
+
+    /// Batch variant of `record_sample` for allowlisted profiler contracts.
+    /// Each entry is `(method, cpu, read_bytes, write_bytes)`. The whole
+    /// batch is rejected if any sample has `cpu == 0`.
+    pub fn report_from(
+        env: Env,
+        reporter: Address,
+        samples: Vec<(Symbol, u64, u64, u64)>,
+    ) -> Result<u32, Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        reporter.require_auth();
+        if !Self::is_reporter(env.clone(), reporter) {
+            return Err(Error::NotAuthorized);
+        }
+
+        for (_, cpu, _, _) in samples.iter() {
+            if cpu == 0 {
+                return Err(Error::InvalidMetric);
+            }
+        }
+        for (method, cpu, read_bytes, write_bytes) in samples.iter() {
+            apply_sample(&env, method, cpu, read_bytes, write_bytes);
+        }
+        Ok(samples.len())
     }
 
     pub fn get_method_profile(env: Env, method: Symbol) -> MethodProfile {
@@ -145,6 +183,26 @@ fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
     Ok(())
 }
 
+fn apply_sample(
+    env: &Env,
+    method: Symbol,
+    cpu: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+) -> MethodProfile {
+    let key = DataKey::MethodProfile(method.clone());
+    let mut profile: MethodProfile = env.storage().persistent().get(&key).unwrap_or_default();
+
+    profile.calls = profile.calls.saturating_add(1);
+    profile.total_cpu = profile.total_cpu.saturating_add(cpu);
+    profile.total_read_bytes = profile.total_read_bytes.saturating_add(read_bytes);
+    profile.total_write_bytes = profile.total_write_bytes.saturating_add(write_bytes);
+
+    env.storage().persistent().set(&key, &profile);
+    register_method(env, method);
+    profile
+}
+
 fn register_method(env: &Env, method: Symbol) {
     let mut methods: Vec<Symbol> = env.storage().instance().get(&DataKey::Methods).unwrap_or(vec![env]);
     if !methods.contains(&method) {
@@ -188,7 +246,16 @@ fn recommend_for_profile(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::Env;
+    use soroban_sdk::{testutils::Address as _, Env};
+
+    fn setup(env: &Env) -> (GasOptimizationAnalysisClient<'_>, Address) {
+        env.mock_all_auths();
+        let contract_id = env.register(GasOptimizationAnalysis, ());
+        let client = GasOptimizationAnalysisClient::new(env, &contract_id);
+        let admin = Address::generate(env);
+        client.init(&admin);
+        (client, admin)
+    }
 
     #[test]
     fn recommends_split_for_high_cpu_methods() {
@@ -220,4 +287,59 @@ mod tests {
         let rec = recommend_for_profile(&env, method, &profile).unwrap();
         assert_eq!(rec.recommendation, Symbol::new(&env, "cache_writes"));
     }
+
+    #[test]
+    fn report_from_aggregates_batch_per_method() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let profiler = Address::generate(&env);
+        client.set_reporter(&admin, &profiler, &true);
+
+        let resolve = Symbol::new(&env, "resolve_game");
+        let settle = Symbol::new(&env, "settle");
+        let samples = vec![
+            &env,
+            (resolve.clone(), 40_000u64, 100u64, 200u64),
+            (settle.clone(), 10_000u64, 50u64, 400u64),
+            (resolve.clone(), 60_000u64, 300u64, 0u64),
+        ];
+        assert_eq!(client.report_from(&profiler, &samples), 3);
+
+        let profile = client.get_method_profile(&resolve);
+        assert_eq!(profile.calls, 2);
+        assert_eq!(profile.total_cpu, 100_000);
+        assert_eq!(profile.total_read_bytes, 400);
+        assert_eq!(profile.total_write_bytes, 200);
+
+        let profile = client.get_method_profile(&settle);
+        assert_eq!(profile.calls, 1);
+        assert_eq!(profile.total_write_bytes, 400);
+        assert_eq!(client.get_hotspots(&0).len(), 2);
+    }
+
+    #[test]
+    fn report_from_rejects_unlisted_reporter() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let profiler = Address::generate(&env);
+        let samples = vec![&env, (Symbol::new(&env, "settle"), 1_000u64, 0u64, 0u64)];
+
+        assert_eq!(
+            client.try_report_from(&profiler, &samples),
+            Err(Ok(Error::NotAuthorized))
+        );
+
+        client.set_reporter(&admin, &profiler, &true);
+        client.set_reporter(&admin, &profiler, &false);
+        assert_eq!(
+            client.try_report_from(&profiler, &samples),
+            Err(Ok(Error::NotAuthorized))
+        );
+        assert_eq!(
+            client
+                .get_method_profile(&Symbol::new(&env, "settle"))
+                .calls,
+            0
+        );
+    }
 }
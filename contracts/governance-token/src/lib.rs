@@ -1,9 +1,15 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, 
-    Address, Env, String, Symbol,
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, Address,
+    BytesN, Env, String, Vec,
 };
 
+/// The part of the session nonce manager used by `transfer_with_nonce`.
+#[contractclient(name = "NonceManagerClient")]
+pub trait NonceManager {
+    fn consume_nonce(env: Env, account: Address, nonce: u64, purpose: String);
+}
+
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -13,8 +19,19 @@ pub enum Error {
     InsufficientBalance = 3,
     InvalidAmount = 4,
     Overflow = 5,
+    InsufficientAllowance = 6,
+    TransferPaused = 7,
+    ProposalNotFound = 8,
+    InvalidVotingPeriod = 9,
+    VotingClosed = 10,
+    AlreadyVoted = 11,
+    NoVotingPower = 12,
+    BatchTooLarge = 13,
 }
 
+/// Maximum number of accounts read by one `balance_of_many` call.
+pub const MAX_BALANCE_BATCH: u32 = 100;
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
@@ -24,6 +41,118 @@ pub enum DataKey {
     Decimals,
     Balance(Address),
     TotalSupply,
+    Allowance(Address, Address),
+    Paused,
+    CheckpointCount(Address),
+    Checkpoint(Address, u32),
+    NextProposalId,
+    Proposal(u64),
+    Voted(u64, Address),
+    HolderCount,
+    Excluded(Address),
+    ExcludedSupply,
+    HeldSince(Address),
+}
+
+/// An account's balance as of the end of ledger `ledger`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub balance: i128,
+}
+
+/// A proposal voted on with balances from before `snapshot_ledger`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub proposer: Address,
+    pub description_hash: BytesN<32>,
+    pub snapshot_ledger: u32,
+    pub voting_ends: u64,
+    pub votes_for: i128,
+    pub votes_against: i128,
+}
+
+#[contractevent]
+pub struct Initialized {
+    #[topic]
+    pub admin: Address,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+#[contractevent]
+pub struct Minted {
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct Burned {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct Transferred {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// Emitted with the resulting allowance whenever it changes.
+#[contractevent]
+pub struct Approved {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub spender: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct TransfersPaused {
+    #[topic]
+    pub admin: Address,
+}
+
+#[contractevent]
+pub struct TransfersUnpaused {
+    #[topic]
+    pub admin: Address,
+}
+
+#[contractevent]
+pub struct SupplyExclusionSet {
+    #[topic]
+    pub address: Address,
+    pub excluded: bool,
+}
+
+#[contractevent]
+pub struct ProposalCreated {
+    #[topic]
+    pub proposal_id: u64,
+    #[topic]
+    pub proposer: Address,
+    pub description_hash: BytesN<32>,
+    pub voting_ends: u64,
+}
+
+#[contractevent]
+pub struct VoteCast {
+    #[topic]
+    pub proposal_id: u64,
+    #[topic]
+    pub voter: Address,
+    pub support: bool,
+    pub weight: i128,
 }
 
 #[contract]
@@ -34,16 +163,16 @@ impl GovernanceToken {
     /// Initializes the contract with the admin address and token setup.
     /// Requires admin authorization to prevent arbitrary initialization.
     pub fn init(
-        env: Env, 
-        admin: Address, 
-        name: String, 
-        symbol: String, 
-        decimals: u32
+        env: Env,
+        admin: Address,
+        name: String,
+        symbol: String,
+        decimals: u32,
     ) -> Result<(), Error> {
         if env.storage().instance().has(&DataKey::Admin) {
             return Err(Error::AlreadyInitialized);
         }
-        
+
         // Security Fix: Require admin auth during initialization
         admin.require_auth();
 
@@ -53,10 +182,13 @@ impl GovernanceToken {
         env.storage().instance().set(&DataKey::Decimals, &decimals);
         env.storage().instance().set(&DataKey::TotalSupply, &0i128);
 
-        env.events().publish(
-            (symbol_short!("init"), admin),
-            (name, symbol, decimals)
-        );
+        Initialized {
+            admin,
+            name,
+            symbol,
+            decimals,
+        }
+        .publish(&env);
         Ok(())
     }
 
@@ -66,18 +198,24 @@ impl GovernanceToken {
             return Err(Error::InvalidAmount);
         }
 
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).ok_or(Error::NotAuthorized)?;
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotAuthorized)?;
         admin.require_auth();
 
         let balance = Self::balance(env.clone(), to.clone());
         let new_balance = balance.checked_add(amount).ok_or(Error::Overflow)?;
-        env.storage().persistent().set(&DataKey::Balance(to.clone()), &new_balance);
+        Self::write_balance(&env, &to, balance, new_balance);
 
         let total_supply = Self::total_supply(env.clone());
         let new_total_supply = total_supply.checked_add(amount).ok_or(Error::Overflow)?;
-        env.storage().instance().set(&DataKey::TotalSupply, &new_total_supply);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalSupply, &new_total_supply);
 
-        env.events().publish((symbol_short!("mint"), to), amount);
+        Minted { to, amount }.publish(&env);
         Ok(())
     }
 
@@ -87,22 +225,30 @@ impl GovernanceToken {
             return Err(Error::InvalidAmount);
         }
 
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).ok_or(Error::NotAuthorized)?;
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotAuthorized)?;
         admin.require_auth();
 
-        let balance = Self::balance(env.clone(), from.clone());
-        if balance < amount {
-            return Err(Error::InsufficientBalance);
-        }
+        Self::burn_balance(&env, &from, amount)?;
 
-        let new_balance = balance.checked_sub(amount).ok_or(Error::Overflow)?;
-        env.storage().persistent().set(&DataKey::Balance(from.clone()), &new_balance);
+        Burned { from, amount }.publish(&env);
+        Ok(())
+    }
 
-        let total_supply = Self::total_supply(env.clone());
-        let new_total_supply = total_supply.checked_sub(amount).ok_or(Error::Overflow)?;
-        env.storage().instance().set(&DataKey::TotalSupply, &new_total_supply);
+    /// Burns tokens from the caller's own balance, e.g. for buyback-and-burn.
+    /// Requires `from` authorization; the admin is not involved.
+    pub fn burn_self(env: Env, from: Address, amount: i128) -> Result<(), Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        from.require_auth();
+
+        Self::burn_balance(&env, &from, amount)?;
 
-        env.events().publish((symbol_short!("burn"), from), amount);
+        Burned { from, amount }.publish(&env);
         Ok(())
     }
 
@@ -112,29 +258,407 @@ impl GovernanceToken {
             return Err(Error::InvalidAmount);
         }
         from.require_auth();
+        Self::require_not_paused(&env)?;
 
-        let balance_from = Self::balance(env.clone(), from.clone());
-        if balance_from < amount {
-            return Err(Error::InsufficientBalance);
+        Self::move_balance(&env, &from, &to, amount)?;
+
+        Transferred { from, to, amount }.publish(&env);
+        Ok(())
+    }
+
+    /// Transfers tokens after consuming `nonce` for `from` on the session
+    /// nonce manager at `nonce_manager`. The nonce is consumed under
+    /// `purpose`, so a replayed, revoked or unknown nonce fails the whole
+    /// call and no balance moves. Requires sender authorization.
+    pub fn transfer_with_nonce(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+        nonce: u64,
+        purpose: String,
+        nonce_manager: Address,
+    ) -> Result<(), Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
         }
+        from.require_auth();
+        Self::require_not_paused(&env)?;
 
-        let new_balance_from = balance_from.checked_sub(amount).ok_or(Error::Overflow)?;
-        env.storage().persistent().set(&DataKey::Balance(from.clone()), &new_balance_from);
+        NonceManagerClient::new(&env, &nonce_manager).consume_nonce(&from, &nonce, &purpose);
 
-        let balance_to = Self::balance(env.clone(), to.clone());
-        let new_balance_to = balance_to.checked_add(amount).ok_or(Error::Overflow)?;
-        env.storage().persistent().set(&DataKey::Balance(to.clone()), &new_balance_to);
+        Self::move_balance(&env, &from, &to, amount)?;
+
+        Transferred { from, to, amount }.publish(&env);
+        Ok(())
+    }
+
+    /// Sets the amount `spender` may transfer out of `from`'s balance,
+    /// overwriting any previous allowance. Requires `from` authorization.
+    ///
+    /// Prefer `increase_allowance`/`decrease_allowance` when changing an
+    /// existing non-zero allowance, to avoid the spender front-running the
+    /// overwrite and spending both the old and the new amount.
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128) -> Result<(), Error> {
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        from.require_auth();
 
-        env.events().publish((symbol_short!("transfer"), from, to), amount);
+        Self::write_allowance(&env, &from, &spender, amount);
+
+        Approved {
+            from,
+            spender,
+            amount,
+        }
+        .publish(&env);
         Ok(())
     }
 
+    /// Atomically raises `spender`'s allowance over `from` by `delta`.
+    /// Requires `from` authorization.
+    pub fn increase_allowance(
+        env: Env,
+        from: Address,
+        spender: Address,
+        delta: i128,
+    ) -> Result<i128, Error> {
+        if delta <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        from.require_auth();
+
+        let current = Self::allowance(env.clone(), from.clone(), spender.clone());
+        let new_allowance = current.checked_add(delta).ok_or(Error::Overflow)?;
+        Self::write_allowance(&env, &from, &spender, new_allowance);
+
+        Approved {
+            from,
+            spender,
+            amount: new_allowance,
+        }
+        .publish(&env);
+        Ok(new_allowance)
+    }
+
+    /// Atomically lowers `spender`'s allowance over `from` by `delta`,
+    /// flooring at zero. Requires `from` authorization.
+    pub fn decrease_allowance(
+        env: Env,
+        from: Address,
+        spender: Address,
+        delta: i128,
+    ) -> Result<i128, Error> {
+        if delta <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        from.require_auth();
+
+        let current = Self::allowance(env.clone(), from.clone(), spender.clone());
+        let new_allowance = current.saturating_sub(delta).max(0);
+        Self::write_allowance(&env, &from, &spender, new_allowance);
+
+        Approved {
+            from,
+            spender,
+            amount: new_allowance,
+        }
+        .publish(&env);
+        Ok(new_allowance)
+    }
+
+    /// Transfers tokens out of `from` on its behalf, spending `spender`'s
+    /// allowance. Requires spender authorization.
+    pub fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        spender.require_auth();
+        Self::require_not_paused(&env)?;
+
+        let allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        if allowance < amount {
+            return Err(Error::InsufficientAllowance);
+        }
+        let new_allowance = allowance.checked_sub(amount).ok_or(Error::Overflow)?;
+        Self::write_allowance(&env, &from, &spender, new_allowance);
+
+        Self::move_balance(&env, &from, &to, amount)?;
+
+        Transferred { from, to, amount }.publish(&env);
+        Ok(())
+    }
+
+    /// Pauses `transfer` and `transfer_from` for incident response. Mint and
+    /// burn by the admin are unaffected. Only admin can call.
+    pub fn pause(env: Env) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Paused, &true);
+        TransfersPaused { admin }.publish(&env);
+        Ok(())
+    }
+
+    /// Resumes transfers after a `pause`. Only admin can call.
+    pub fn unpause(env: Env) -> Result<(), Error> {
+        let admin = Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Paused, &false);
+        TransfersUnpaused { admin }.publish(&env);
+        Ok(())
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Marks `address` (e.g. a treasury or reserve) as excluded from the
+    /// circulating supply, or includes it again. Only admin can call.
+    pub fn set_excluded(env: Env, address: Address, excluded: bool) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+
+        if Self::is_excluded(env.clone(), address.clone()) != excluded {
+            let balance = Self::balance(env.clone(), address.clone());
+            let delta = if excluded { balance } else { -balance };
+            Self::adjust_excluded_supply(&env, delta);
+
+            let key = DataKey::Excluded(address.clone());
+            if excluded {
+                env.storage().persistent().set(&key, &true);
+            } else {
+                env.storage().persistent().remove(&key);
+            }
+        }
+
+        SupplyExclusionSet { address, excluded }.publish(&env);
+        Ok(())
+    }
+
+    pub fn is_excluded(env: Env, address: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Excluded(address))
+    }
+
+    /// Total supply minus the balances of excluded addresses. The excluded
+    /// total is updated on every balance change, so it stays exact as
+    /// excluded addresses receive or send tokens.
+    pub fn circulating_supply(env: Env) -> i128 {
+        let excluded: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ExcludedSupply)
+            .unwrap_or(0);
+        Self::total_supply(env) - excluded
+    }
+
+    /// Opens a proposal identified by the hash of its off-chain description.
+    /// Votes are weighted by balances held before the current ledger, so
+    /// tokens acquired after the proposal is created carry no weight.
+    /// Requires proposer authorization.
+    pub fn create_proposal(
+        env: Env,
+        proposer: Address,
+        description_hash: BytesN<32>,
+        voting_ends: u64,
+    ) -> Result<u64, Error> {
+        proposer.require_auth();
+        if voting_ends <= env.ledger().timestamp() {
+            return Err(Error::InvalidVotingPeriod);
+        }
+
+        let proposal_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextProposalId)
+            .unwrap_or(1);
+        env.storage().instance().set(
+            &DataKey::NextProposalId,
+            &proposal_id.checked_add(1).ok_or(Error::Overflow)?,
+        );
+
+        let proposal = Proposal {
+            proposer: proposer.clone(),
+            description_hash: description_hash.clone(),
+            snapshot_ledger: env.ledger().sequence(),
+            voting_ends,
+            votes_for: 0,
+            votes_against: 0,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(proposal_id), &proposal);
+
+        ProposalCreated {
+            proposal_id,
+            proposer,
+            description_hash,
+            voting_ends,
+        }
+        .publish(&env);
+        Ok(proposal_id)
+    }
+
+    /// Casts `voter`'s snapshot balance for or against a proposal. Each
+    /// address votes once per proposal; voting closes at `voting_ends`.
+    /// Requires voter authorization.
+    pub fn vote(env: Env, voter: Address, proposal_id: u64, support: bool) -> Result<(), Error> {
+        voter.require_auth();
+
+        let mut proposal = Self::proposal(env.clone(), proposal_id)?;
+        if env.ledger().timestamp() >= proposal.voting_ends {
+            return Err(Error::VotingClosed);
+        }
+        let voted_key = DataKey::Voted(proposal_id, voter.clone());
+        if env.storage().persistent().has(&voted_key) {
+            return Err(Error::AlreadyVoted);
+        }
+
+        let weight = Self::balance_at(env.clone(), voter.clone(), proposal.snapshot_ledger);
+        if weight <= 0 {
+            return Err(Error::NoVotingPower);
+        }
+        if support {
+            proposal.votes_for = proposal
+                .votes_for
+                .checked_add(weight)
+                .ok_or(Error::Overflow)?;
+        } else {
+            proposal.votes_against = proposal
+                .votes_against
+                .checked_add(weight)
+                .ok_or(Error::Overflow)?;
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(proposal_id), &proposal);
+        env.storage().persistent().set(&voted_key, &support);
+
+        VoteCast {
+            proposal_id,
+            voter,
+            support,
+            weight,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Returns `(votes_for, votes_against)` for a proposal.
+    pub fn tally(env: Env, proposal_id: u64) -> Result<(i128, i128), Error> {
+        let proposal = Self::proposal(env, proposal_id)?;
+        Ok((proposal.votes_for, proposal.votes_against))
+    }
+
+    pub fn proposal(env: Env, proposal_id: u64) -> Result<Proposal, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .ok_or(Error::ProposalNotFound)
+    }
+
+    pub fn has_voted(env: Env, proposal_id: u64, voter: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Voted(proposal_id, voter))
+    }
+
+    /// Returns `id`'s balance as it stood before ledger `ledger` began.
+    pub fn balance_at(env: Env, id: Address, ledger: u32) -> i128 {
+        let count: u32 = match env
+            .storage()
+            .persistent()
+            .get(&DataKey::CheckpointCount(id.clone()))
+        {
+            Some(count) => count,
+            // Never written since checkpointing began, so unchanged.
+            None => return Self::balance(env, id),
+        };
+
+        // Binary search for the first checkpoint at or after `ledger`; the
+        // one before it holds the balance we want.
+        let (mut low, mut high) = (0u32, count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if Self::read_checkpoint(&env, &id, mid).ledger < ledger {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        if low == 0 {
+            return 0;
+        }
+        Self::read_checkpoint(&env, &id, low - 1).balance
+    }
+
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Allowance(from, spender))
+            .unwrap_or(0i128)
+    }
+
     pub fn balance(env: Env, id: Address) -> i128 {
-        env.storage().persistent().get(&DataKey::Balance(id)).unwrap_or(0i128)
+        env.storage()
+            .persistent()
+            .get(&DataKey::Balance(id))
+            .unwrap_or(0i128)
+    }
+
+    /// Balances of `accounts`, in the same order. Lets reconcilers check
+    /// balances rebuilt from events in pages of at most `MAX_BALANCE_BATCH`.
+    pub fn balance_of_many(env: Env, accounts: Vec<Address>) -> Result<Vec<i128>, Error> {
+        if accounts.len() > MAX_BALANCE_BATCH {
+            return Err(Error::BatchTooLarge);
+        }
+        let mut balances = Vec::new(&env);
+        for account in accounts.iter() {
+            balances.push_back(Self::balance(env.clone(), account));
+        }
+        Ok(balances)
+    }
+
+    /// Ledger timestamp from which `account`'s balance has not dropped,
+    /// i.e. its last outgoing move or first credit from zero; 0 if its
+    /// balance has never moved.
+    pub fn balance_held_since(env: Env, account: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::HeldSince(account))
+            .unwrap_or(0)
+    }
+
+    /// Whether `account` holds at least `amount` and its balance has not
+    /// dropped for at least `since_seconds`. Meant for gating actions on a
+    /// balance held over time rather than one borrowed for a single call.
+    pub fn has_held_at_least(env: Env, account: Address, amount: i128, since_seconds: u64) -> bool {
+        if Self::balance(env.clone(), account.clone()) < amount {
+            return false;
+        }
+        let held_since = Self::balance_held_since(env.clone(), account);
+        env.ledger().timestamp().saturating_sub(held_since) >= since_seconds
+    }
+
+    /// Number of addresses holding a non-zero balance.
+    pub fn holder_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::HolderCount)
+            .unwrap_or(0)
     }
 
     pub fn total_supply(env: Env) -> i128 {
-        env.storage().instance().get(&DataKey::TotalSupply).unwrap_or(0i128)
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalSupply)
+            .unwrap_or(0i128)
     }
 
     pub fn name(env: Env) -> String {
@@ -148,13 +672,153 @@ impl GovernanceToken {
     pub fn decimals(env: Env) -> u32 {
         env.storage().instance().get(&DataKey::Decimals).unwrap()
     }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotAuthorized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), Error> {
+        if Self::is_paused(env.clone()) {
+            return Err(Error::TransferPaused);
+        }
+        Ok(())
+    }
+
+    fn move_balance(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(), Error> {
+        let balance_from = Self::balance(env.clone(), from.clone());
+        if balance_from < amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        // A self-transfer moves nothing, so it must not touch the holding
+        // period or checkpoints.
+        if from == to {
+            return Ok(());
+        }
+
+        let new_balance_from = balance_from.checked_sub(amount).ok_or(Error::Overflow)?;
+        Self::write_balance(env, from, balance_from, new_balance_from);
+
+        let balance_to = Self::balance(env.clone(), to.clone());
+        let new_balance_to = balance_to.checked_add(amount).ok_or(Error::Overflow)?;
+        Self::write_balance(env, to, balance_to, new_balance_to);
+        Ok(())
+    }
+
+    fn burn_balance(env: &Env, from: &Address, amount: i128) -> Result<(), Error> {
+        let balance = Self::balance(env.clone(), from.clone());
+        if balance < amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let new_balance = balance.checked_sub(amount).ok_or(Error::Overflow)?;
+        Self::write_balance(env, from, balance, new_balance);
+
+        let total_supply = Self::total_supply(env.clone());
+        let new_total_supply = total_supply.checked_sub(amount).ok_or(Error::Overflow)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalSupply, &new_total_supply);
+        Ok(())
+    }
+
+    /// Stores a new balance and checkpoints it for the current ledger. The
+    /// first checkpoint of an account that already held tokens records the
+    /// old balance at ledger 0, so earlier snapshots still see it.
+    fn write_balance(env: &Env, id: &Address, old_balance: i128, new_balance: i128) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Balance(id.clone()), &new_balance);
+
+        if Self::is_excluded(env.clone(), id.clone()) {
+            Self::adjust_excluded_supply(env, new_balance - old_balance);
+        }
+
+        // Only outgoing moves restart the holding period, so nobody can reset
+        // another account's period by sending it dust. The first credit from
+        // zero starts it.
+        if new_balance < old_balance || (old_balance == 0 && new_balance > 0) {
+            env.storage()
+                .persistent()
+                .set(&DataKey::HeldSince(id.clone()), &env.ledger().timestamp());
+        }
+
+        let holders = Self::holder_count(env.clone());
+        if old_balance == 0 && new_balance > 0 {
+            env.storage()
+                .instance()
+                .set(&DataKey::HolderCount, &(holders + 1));
+        } else if old_balance > 0 && new_balance == 0 {
+            env.storage()
+                .instance()
+                .set(&DataKey::HolderCount, &(holders - 1));
+        }
+
+        // Each checkpoint has its own entry, so a transfer writes one small
+        // entry no matter how long the account's history is.
+        let count_key = DataKey::CheckpointCount(id.clone());
+        let mut count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        if count == 0 && old_balance != 0 {
+            Self::write_checkpoint(env, id, 0, 0, old_balance);
+            count = 1;
+        }
+        let ledger = env.ledger().sequence();
+        let index = if count > 0 && Self::read_checkpoint(env, id, count - 1).ledger == ledger {
+            count - 1
+        } else {
+            count += 1;
+            count - 1
+        };
+        Self::write_checkpoint(env, id, index, ledger, new_balance);
+        env.storage().persistent().set(&count_key, &count);
+    }
+
+    fn read_checkpoint(env: &Env, id: &Address, index: u32) -> Checkpoint {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Checkpoint(id.clone(), index))
+            .unwrap()
+    }
+
+    fn write_checkpoint(env: &Env, id: &Address, index: u32, ledger: u32, balance: i128) {
+        env.storage().persistent().set(
+            &DataKey::Checkpoint(id.clone(), index),
+            &Checkpoint { ledger, balance },
+        );
+    }
+
+    fn adjust_excluded_supply(env: &Env, delta: i128) {
+        let excluded: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ExcludedSupply)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::ExcludedSupply, &(excluded + delta));
+    }
+
+    fn write_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Allowance(from.clone(), spender.clone()), &amount);
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::testutils::{Address as _, Events, MockAuth, MockAuthInvoke};
-    use soroban_sdk::{IntoVal};
+    use soroban_sdk::testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke};
+    use soroban_sdk::{vec, Event as _, IntoVal};
+    use stellarcade_session_nonce_manager::{
+        SessionNonceManagerContract, SessionNonceManagerContractClient,
+    };
 
     #[test]
     fn test_token_flow() {
@@ -169,10 +833,10 @@ mod test {
         let client = GovernanceTokenClient::new(&env, &contract_id);
 
         client.init(
-            &admin, 
-            &String::from_str(&env, "StellarCade Governance"), 
-            &String::from_str(&env, "SCG"), 
-            &18
+            &admin,
+            &String::from_str(&env, "StellarCade Governance"),
+            &String::from_str(&env, "SCG"),
+            &18,
         );
 
         client.mint(&user1, &1000);
@@ -199,25 +863,445 @@ mod test {
         let client = GovernanceTokenClient::new(&env, &contract_id);
 
         client.init(
-            &admin, 
-            &String::from_str(&env, "Test"), 
-            &String::from_str(&env, "T"), 
-            &0
+            &admin,
+            &String::from_str(&env, "Test"),
+            &String::from_str(&env, "T"),
+            &0,
         );
 
         // Use mock_auths to simulate authorization from malicious address
-        client.mock_auths(&[
-            MockAuth {
-                address: &malicious,
-                invoke: &MockAuthInvoke {
-                    contract: &contract_id,
-                    fn_name: "mint",
-                    args: (user.clone(), 1000i128).into_val(&env),
-                    sub_invokes: &[],
-                },
+        client.mock_auths(&[MockAuth {
+            address: &malicious,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "mint",
+                args: (user.clone(), 1000i128).into_val(&env),
+                sub_invokes: &[],
             },
-        ]);
+        }]);
 
         client.mint(&user, &1000);
     }
+
+    fn setup_token(env: &Env) -> (GovernanceTokenClient<'_>, Address) {
+        let admin = Address::generate(env);
+        let contract_id = env.register(GovernanceToken, ());
+        let client = GovernanceTokenClient::new(env, &contract_id);
+        client.init(
+            &admin,
+            &String::from_str(env, "StellarCade Governance"),
+            &String::from_str(env, "SCG"),
+            &18,
+        );
+        (client, admin)
+    }
+
+    #[test]
+    fn test_transfer_with_nonce_rejects_replay() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let from = Address::generate(&env);
+        let to = Address::generate(&env);
+        client.mint(&from, &1000);
+
+        let manager_id = env.register(SessionNonceManagerContract, ());
+        let manager = SessionNonceManagerContractClient::new(&env, &manager_id);
+        manager.init(&Address::generate(&env));
+        let purpose = String::from_str(&env, "transfer");
+        let nonce = manager.issue_nonce(&from, &purpose);
+
+        client.transfer_with_nonce(&from, &to, &100, &nonce, &purpose, &manager_id);
+        assert_eq!(client.balance(&from), 900);
+        assert_eq!(client.balance(&to), 100);
+
+        let replay =
+            client.try_transfer_with_nonce(&from, &to, &100, &nonce, &purpose, &manager_id);
+        assert!(replay.is_err());
+        assert_eq!(client.balance(&from), 900);
+        assert_eq!(client.balance(&to), 100);
+    }
+
+    #[test]
+    fn test_burn_self_reduces_balance_and_supply() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let user = Address::generate(&env);
+        client.mint(&user, &1000);
+
+        client.burn_self(&user, &300);
+        assert_eq!(client.balance(&user), 700);
+        assert_eq!(client.total_supply(), 700);
+    }
+
+    #[test]
+    fn test_burn_self_rejects_more_than_balance() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let user = Address::generate(&env);
+        client.mint(&user, &100);
+
+        assert_eq!(
+            client.try_burn_self(&user, &101),
+            Err(Ok(Error::InsufficientBalance))
+        );
+        assert_eq!(client.balance(&user), 100);
+        assert_eq!(client.total_supply(), 100);
+    }
+
+    #[test]
+    fn test_proposal_votes_weighted_by_snapshot_balance() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        client.mint(&alice, &700);
+        client.mint(&bob, &300);
+
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+        let hash = BytesN::from_array(&env, &[1; 32]);
+        let voting_ends = env.ledger().timestamp() + 1_000;
+        let proposal_id = client.create_proposal(&alice, &hash, &voting_ends);
+
+        // Tokens moved after creation do not change voting weight.
+        client.transfer(&alice, &bob, &200);
+
+        client.vote(&alice, &proposal_id, &true);
+        client.vote(&bob, &proposal_id, &false);
+        assert_eq!(client.tally(&proposal_id), (700, 300));
+        assert!(client.has_voted(&proposal_id, &bob));
+
+        assert_eq!(
+            client.try_vote(&alice, &proposal_id, &false),
+            Err(Ok(Error::AlreadyVoted))
+        );
+        assert_eq!(client.tally(&proposal_id), (700, 300));
+    }
+
+    #[test]
+    fn test_vote_rejected_without_power_or_after_close() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let holder = Address::generate(&env);
+        let latecomer = Address::generate(&env);
+        client.mint(&holder, &100);
+
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+        let hash = BytesN::from_array(&env, &[2; 32]);
+        let voting_ends = env.ledger().timestamp() + 1_000;
+        let proposal_id = client.create_proposal(&holder, &hash, &voting_ends);
+
+        client.mint(&latecomer, &500);
+        assert_eq!(
+            client.try_vote(&latecomer, &proposal_id, &true),
+            Err(Ok(Error::NoVotingPower))
+        );
+
+        env.ledger().with_mut(|li| li.timestamp = voting_ends);
+        assert_eq!(
+            client.try_vote(&holder, &proposal_id, &true),
+            Err(Ok(Error::VotingClosed))
+        );
+        assert_eq!(
+            client.try_tally(&(proposal_id + 1)),
+            Err(Ok(Error::ProposalNotFound))
+        );
+    }
+
+    #[test]
+    fn test_holder_count_tracks_non_zero_balances() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        assert_eq!(client.holder_count(), 0);
+
+        client.mint(&user1, &500);
+        client.mint(&user2, &300);
+        client.mint(&user1, &100);
+        assert_eq!(client.holder_count(), 2);
+
+        // A full self-transfer leaves the holder in place.
+        client.transfer(&user1, &user1, &600);
+        assert_eq!(client.holder_count(), 2);
+
+        // Moving everything out swaps one holder for another.
+        client.transfer(&user2, &user3, &300);
+        assert_eq!(client.holder_count(), 2);
+        client.transfer(&user3, &user1, &100);
+        assert_eq!(client.holder_count(), 2);
+
+        client.burn(&user3, &200);
+        assert_eq!(client.holder_count(), 1);
+        client.burn_self(&user1, &700);
+        assert_eq!(client.holder_count(), 0);
+    }
+
+    #[test]
+    fn test_circulating_supply_skips_excluded_balances() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let reserve = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        client.mint(&reserve, &1_000);
+        client.mint(&user, &200);
+        assert_eq!(client.circulating_supply(), 1_200);
+
+        // Excluding picks up the balance already held
+        client.set_excluded(&reserve, &true);
+        assert!(client.is_excluded(&reserve));
+        assert_eq!(client.circulating_supply(), 200);
+
+        // Later balance changes of the excluded address are tracked
+        client.mint(&reserve, &500);
+        assert_eq!(client.total_supply(), 1_700);
+        assert_eq!(client.circulating_supply(), 200);
+        client.transfer(&reserve, &user, &300);
+        assert_eq!(client.circulating_supply(), 500);
+        client.burn(&reserve, &100);
+        assert_eq!(client.circulating_supply(), 500);
+
+        // Including it again counts its balance
+        client.set_excluded(&reserve, &false);
+        assert_eq!(client.circulating_supply(), client.total_supply());
+        assert_eq!(client.circulating_supply(), 1_600);
+    }
+
+    #[test]
+    fn test_has_held_at_least_tracks_holding_period() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let user = Address::generate(&env);
+        let other = Address::generate(&env);
+        let day = 86_400;
+
+        env.ledger().set_timestamp(1_000);
+        client.mint(&user, &500);
+        assert_eq!(client.balance_held_since(&user), 1_000);
+        assert!(!client.has_held_at_least(&user, &500, &day));
+
+        env.ledger().set_timestamp(1_000 + day);
+        assert!(client.has_held_at_least(&user, &500, &day));
+        assert!(!client.has_held_at_least(&user, &501, &day));
+        assert!(!client.has_held_at_least(&user, &500, &(day + 1)));
+
+        // Sending tokens out restarts the holding period.
+        client.transfer(&user, &other, &100);
+        assert_eq!(client.balance_held_since(&user), 1_000 + day);
+        assert!(!client.has_held_at_least(&user, &400, &day));
+        env.ledger().set_timestamp(1_000 + 2 * day);
+        assert!(client.has_held_at_least(&user, &400, &day));
+    }
+
+    #[test]
+    fn test_balance_at_searches_checkpoints_across_ledgers() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let user = Address::generate(&env);
+        let other = Address::generate(&env);
+
+        env.ledger().set_sequence_number(10);
+        client.mint(&user, &100);
+        for (i, ledger) in [20u32, 30, 40, 50].iter().enumerate() {
+            env.ledger().set_sequence_number(*ledger);
+            client.transfer(&user, &other, &10);
+            // A second change in the same ledger overwrites its checkpoint.
+            if i == 1 {
+                client.transfer(&user, &other, &5);
+            }
+        }
+
+        assert_eq!(client.balance_at(&user, &10), 0);
+        assert_eq!(client.balance_at(&user, &11), 100);
+        assert_eq!(client.balance_at(&user, &20), 100);
+        assert_eq!(client.balance_at(&user, &21), 90);
+        assert_eq!(client.balance_at(&user, &31), 75);
+        assert_eq!(client.balance_at(&user, &45), 65);
+        assert_eq!(client.balance_at(&user, &51), 55);
+        assert_eq!(client.balance_at(&other, &31), 25);
+    }
+
+    #[test]
+    fn test_inbound_dust_does_not_reset_holding_period() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let user = Address::generate(&env);
+        let attacker = Address::generate(&env);
+        let day = 86_400;
+
+        env.ledger().set_timestamp(1_000);
+        client.mint(&user, &500);
+        client.mint(&attacker, &10);
+
+        env.ledger().set_timestamp(1_000 + day);
+        client.transfer(&attacker, &user, &1);
+        client.transfer(&user, &user, &200);
+        assert_eq!(client.balance_held_since(&user), 1_000);
+        assert!(client.has_held_at_least(&user, &500, &day));
+    }
+
+    #[test]
+    fn test_balance_of_many_matches_individual_balances() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup_token(&env);
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let c = Address::generate(&env);
+        let empty = Address::generate(&env);
+
+        client.mint(&a, &1_000);
+        client.mint(&b, &250);
+        client.transfer(&a, &c, &400);
+        client.burn(&b, &50);
+
+        let accounts = vec![
+            &env,
+            a.clone(),
+            b.clone(),
+            c.clone(),
+            empty.clone(),
+            a.clone(),
+        ];
+        let balances = client.balance_of_many(&accounts);
+        assert_eq!(balances, vec![&env, 600, 200, 400, 0, 600]);
+        for (account, balance) in accounts.iter().zip(balances.iter()) {
+            assert_eq!(client.balance(&account), balance);
+        }
+
+        assert_eq!(client.balance_of_many(&Vec::new(&env)), Vec::new(&env));
+
+        let mut too_many = Vec::new(&env);
+        for _ in 0..=MAX_BALANCE_BATCH {
+            too_many.push_back(a.clone());
+        }
+        assert_eq!(
+            client.try_balance_of_many(&too_many),
+            Err(Ok(Error::BatchTooLarge))
+        );
+    }
+
+    #[test]
+    fn test_increase_then_decrease_allowance() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _) = setup_token(&env);
+        let owner = Address::generate(&env);
+        let spender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.mint(&owner, &1000);
+
+        client.approve(&owner, &spender, &100);
+        assert_eq!(client.increase_allowance(&owner, &spender, &250), 350);
+        assert_eq!(client.decrease_allowance(&owner, &spender, &50), 300);
+        assert_eq!(client.allowance(&owner, &spender), 300);
+
+        client.transfer_from(&spender, &owner, &recipient, &120);
+        assert_eq!(client.allowance(&owner, &spender), 180);
+        assert_eq!(client.balance(&recipient), 120);
+        assert_eq!(client.balance(&owner), 880);
+
+        let result = client.try_transfer_from(&spender, &owner, &recipient, &181);
+        assert_eq!(result, Err(Ok(Error::InsufficientAllowance)));
+    }
+
+    #[test]
+    fn test_decrease_allowance_floors_at_zero() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _) = setup_token(&env);
+        let owner = Address::generate(&env);
+        let spender = Address::generate(&env);
+
+        client.increase_allowance(&owner, &spender, &40);
+        assert_eq!(client.decrease_allowance(&owner, &spender, &100), 0);
+        assert_eq!(client.allowance(&owner, &spender), 0);
+    }
+
+    #[test]
+    fn test_pause_blocks_transfers_but_not_mint() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _) = setup_token(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        client.mint(&user1, &1000);
+        client.approve(&user1, &user2, &500);
+
+        client.pause();
+        assert!(client.is_paused());
+
+        assert_eq!(
+            client.try_transfer(&user1, &user2, &100),
+            Err(Ok(Error::TransferPaused))
+        );
+        assert_eq!(
+            client.try_transfer_from(&user2, &user1, &user2, &100),
+            Err(Ok(Error::TransferPaused))
+        );
+
+        // Admin supply management keeps working while paused
+        client.mint(&user1, &500);
+        client.burn(&user1, &200);
+        assert_eq!(client.balance(&user1), 1300);
+
+        client.unpause();
+        assert!(!client.is_paused());
+        client.transfer(&user1, &user2, &100);
+        assert_eq!(client.balance(&user2), 100);
+    }
+
+    #[test]
+    fn test_structured_events() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _) = setup_token(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+
+        client.mint(&user1, &1000);
+        let events = env.events().all().filter_by_contract(&client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &Minted {
+                to: user1.clone(),
+                amount: 1000,
+            }
+            .to_xdr(&env, &client.address)
+        );
+
+        client.transfer(&user1, &user2, &400);
+        let events = env.events().all().filter_by_contract(&client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &Transferred {
+                from: user1.clone(),
+                to: user2.clone(),
+                amount: 400,
+            }
+            .to_xdr(&env, &client.address)
+        );
+
+        client.burn(&user2, &100);
+        let events = env.events().all().filter_by_contract(&client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &Burned {
+                from: user2.clone(),
+                amount: 100,
+            }
+            .to_xdr(&env, &client.address)
+        );
+    }
 }
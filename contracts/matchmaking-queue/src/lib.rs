@@ -1,8 +1,8 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short,
-    Address, Env, Symbol, Vec,
+    contract, contractclient, contractimpl, contracttype, symbol_short, token, vec,
+    Address, Env, IntoVal, Symbol, Vec,
 };
 
 // ── Storage Keys ─────────────────────────────────────────────────
@@ -13,6 +13,12 @@ pub enum DataKey {
     QueueState(Symbol),     // queue_id → MatchQueueState
     NextMatchId,
     Match(u64),             // match_id → MatchRecord
+    PlayerQueue(Address),   // player → queue_id they are waiting in
+    QueueCapacity(Symbol),  // queue_id → max players waiting
+    MatchCallback(Symbol),  // queue_id → MatchCallback
+    PenaltyConfig,          // → PenaltyConfig
+    Abandon(u64, Address),  // (match_id, player) → reported
+    Stake(Address),         // player → PlayerStake held in escrow
 }
 
 // ── Domain Types ─────────────────────────────────────────────────
@@ -24,12 +30,61 @@ pub struct MatchQueueState {
     pub criteria_hash: Symbol,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MatchStatus {
+    Active,
+    Abandoned,
+    Completed,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct MatchRecord {
     pub match_id: u64,
     pub queue_id: Symbol,
     pub players: Vec<Address>,
+    pub status: MatchStatus,
+}
+
+/// Game contract notified when a queue forms a match.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchCallback {
+    pub game_contract: Address,
+    pub start_selector: Symbol,
+}
+
+/// Penalty-slashing deployment charged when a player abandons a match.
+/// Each player escrows `stake` of `token` on enqueue to cover the slash.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PenaltyConfig {
+    pub penalty_contract: Address,
+    pub violation_code: Symbol,
+    pub token: Address,
+    pub stake: i128,
+}
+
+/// Stake a player escrowed on enqueue, returned once their match settles.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlayerStake {
+    pub token: Address,
+    pub amount: i128,
+}
+
+// ── External Interfaces ───────────────────────────────────────────
+#[contractclient(name = "PenaltyClient")]
+pub trait PenaltyContract {
+    fn apply_penalty_from(
+        env: Env,
+        payer: Address,
+        account: Address,
+        code: Symbol,
+        context_hash: Symbol,
+        token_address: Address,
+    ) -> u64;
 }
 
 // ── Events ────────────────────────────────────────────────────────
@@ -54,6 +109,29 @@ pub struct MatchCreated {
     pub queue_id: Symbol,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchCancelled {
+    pub match_id: u64,
+    pub queue_id: Symbol,
+    pub requeued: Vec<Address>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchCompleted {
+    pub match_id: u64,
+    pub queue_id: Symbol,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AbandonReported {
+    pub match_id: u64,
+    pub player: Address,
+    pub penalty_id: Option<u64>,
+}
+
 // ── Contract ──────────────────────────────────────────────────────
 #[contract]
 pub struct MatchmakingQueue;
@@ -70,6 +148,7 @@ impl MatchmakingQueue {
     }
 
     /// Enqueue a player into a matchmaking queue. Player must auth.
+    /// A player can wait in only one queue at a time.
     pub fn enqueue_player(
         env: Env,
         queue_id: Symbol,
@@ -78,6 +157,13 @@ impl MatchmakingQueue {
     ) {
         player.require_auth();
 
+        let lock_key = DataKey::PlayerQueue(player.clone());
+        if let Some(current) = env.storage().persistent().get::<_, Symbol>(&lock_key) {
+            if current != queue_id {
+                panic!("AlreadyQueued: player is waiting in another queue");
+            }
+        }
+
         let mut state: MatchQueueState = env
             .storage()
             .persistent()
@@ -95,8 +181,34 @@ impl MatchmakingQueue {
             }
         }
 
+        if let Some(max_size) = Self::queue_capacity(env.clone(), queue_id.clone()) {
+            if state.players.len() >= max_size {
+                panic!("QueueFull: queue is at capacity");
+            }
+        }
+
+        if let Some(config) = Self::penalty_config(env.clone()) {
+            if config.stake > 0 {
+                let stake_key = DataKey::Stake(player.clone());
+                assert!(
+                    !env.storage().persistent().has(&stake_key),
+                    "StakeHeld: player's previous match has not settled"
+                );
+                token::Client::new(&env, &config.token).transfer(
+                    &player,
+                    &env.current_contract_address(),
+                    &config.stake,
+                );
+                env.storage().persistent().set(
+                    &stake_key,
+                    &PlayerStake { token: config.token, amount: config.stake },
+                );
+            }
+        }
+
         state.players.push_back(player.clone());
         env.storage().persistent().set(&DataKey::QueueState(queue_id.clone()), &state);
+        env.storage().persistent().set(&lock_key, &queue_id);
 
         env.events().publish(
             (symbol_short!("enqueued"),),
@@ -129,6 +241,8 @@ impl MatchmakingQueue {
 
         state.players = new_players;
         env.storage().persistent().set(&DataKey::QueueState(queue_id.clone()), &state);
+        release_player_lock(&env, &player, &queue_id);
+        refund_stake(&env, &player);
 
         env.events().publish(
             (symbol_short!("dequeued"),),
@@ -169,7 +283,9 @@ impl MatchmakingQueue {
                         break;
                     }
                 }
-                if !matched {
+                if matched {
+                    release_player_lock(&env, &p, &queue_id);
+                } else {
                     remaining.push_back(p);
                 }
             }
@@ -181,17 +297,228 @@ impl MatchmakingQueue {
             match_id,
             queue_id: queue_id.clone(),
             players,
+            status: MatchStatus::Active,
         };
         env.storage().persistent().set(&DataKey::Match(match_id), &record);
 
         env.events().publish(
             (symbol_short!("matched"),),
-            MatchCreated { match_id, queue_id },
+            MatchCreated { match_id, queue_id: queue_id.clone() },
         );
 
+        // Not best-effort: if the game contract rejects the call, the whole
+        // match creation is rolled back and players stay queued.
+        if let Some(callback) = Self::match_callback(env.clone(), queue_id) {
+            env.invoke_contract::<()>(
+                &callback.game_contract,
+                &callback.start_selector,
+                vec![&env, match_id.into_val(&env), record.players.into_val(&env)],
+            );
+        }
+
         match_id
     }
 
+    /// Dissolve a match that cannot start. Admin-only. The match is marked
+    /// Abandoned; if `requeue` is set, its players are put back at the end
+    /// of the match's queue. Players now waiting in any queue, who would
+    /// overflow the queue's capacity, or who were reported for abandoning
+    /// the match are skipped. Requeued players keep their stake; everyone
+    /// else gets theirs back.
+    pub fn cancel_match(env: Env, match_id: u64, requeue: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        admin.require_auth();
+
+        let mut record = Self::match_state(env.clone(), match_id);
+        assert!(record.status == MatchStatus::Active, "Match is not active");
+        record.status = MatchStatus::Abandoned;
+        env.storage().persistent().set(&DataKey::Match(match_id), &record);
+
+        let mut requeued = Vec::new(&env);
+        let mut state: Option<MatchQueueState> = if requeue {
+            env.storage()
+                .persistent()
+                .get(&DataKey::QueueState(record.queue_id.clone()))
+        } else {
+            None
+        };
+        let capacity = Self::queue_capacity(env.clone(), record.queue_id.clone());
+        for player in record.players.iter() {
+            let lock_key = DataKey::PlayerQueue(player.clone());
+            let rejoin = state.as_ref().is_some_and(|state| {
+                !env.storage().persistent().has(&lock_key)
+                    && !capacity.is_some_and(|max_size| state.players.len() >= max_size)
+                    && !Self::abandon_reported(env.clone(), match_id, player.clone())
+            });
+            if !rejoin {
+                refund_stake(&env, &player);
+                continue;
+            }
+
+            if let Some(state) = state.as_mut() {
+                state.players.push_back(player.clone());
+            }
+            env.storage().persistent().set(&lock_key, &record.queue_id);
+            requeued.push_back(player.clone());
+
+            env.events().publish(
+                (symbol_short!("enqueued"),),
+                PlayerEnqueued { queue_id: record.queue_id.clone(), player },
+            );
+        }
+        if let Some(state) = state {
+            env.storage()
+                .persistent()
+                .set(&DataKey::QueueState(record.queue_id.clone()), &state);
+        }
+
+        env.events().publish(
+            (symbol_short!("cancelled"),),
+            MatchCancelled { match_id, queue_id: record.queue_id, requeued },
+        );
+    }
+
+    /// Close a match that was played out. Admin-only. The match is marked
+    /// Completed and every player who still has a stake gets it back.
+    pub fn complete_match(env: Env, match_id: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        admin.require_auth();
+
+        let mut record = Self::match_state(env.clone(), match_id);
+        assert!(record.status == MatchStatus::Active, "Match is not active");
+        record.status = MatchStatus::Completed;
+        env.storage().persistent().set(&DataKey::Match(match_id), &record);
+
+        for player in record.players.iter() {
+            refund_stake(&env, &player);
+        }
+
+        env.events().publish(
+            (symbol_short!("completed"),),
+            MatchCompleted { match_id, queue_id: record.queue_id },
+        );
+    }
+
+    /// Record that `player` walked out of an active match. Admin-only. If a
+    /// penalty contract is configured, the slash is taken from the stake
+    /// the player escrowed on enqueue: `apply_penalty_from` is called with
+    /// this contract as payer, under the configured violation code and the
+    /// match's queue id as context, and the rest of the stake is returned.
+    /// The call is not best-effort: if it fails (e.g. the stake does not
+    /// cover the slash), the report is rolled back. A player is reported at
+    /// most once per match.
+    pub fn report_abandon(env: Env, match_id: u64, player: Address) -> Option<u64> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        admin.require_auth();
+
+        let record = Self::match_state(env.clone(), match_id);
+        assert!(record.status == MatchStatus::Active, "Match is not active");
+        assert!(record.players.contains(&player), "Player not in match");
+
+        let key = DataKey::Abandon(match_id, player.clone());
+        assert!(!env.storage().persistent().has(&key), "Abandon already reported");
+        env.storage().persistent().set(&key, &true);
+
+        let penalty_id = Self::penalty_config(env.clone())
+            .map(|config| slash_stake(&env, &config, &player, &record.queue_id));
+
+        env.events().publish(
+            (symbol_short!("abandon"),),
+            AbandonReported { match_id, player, penalty_id },
+        );
+
+        penalty_id
+    }
+
+    /// Charge abandoning players through `penalty_contract.apply_penalty_from`
+    /// under `violation_code`, and require a `stake` of `token` from every
+    /// player who enqueues from now on. Admin-only. This contract must be
+    /// the penalty contract's admin.
+    pub fn set_penalty_config(
+        env: Env,
+        penalty_contract: Address,
+        violation_code: Symbol,
+        token: Address,
+        stake: i128,
+    ) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        admin.require_auth();
+        assert!(stake >= 0, "Stake must be non-negative");
+
+        env.storage().instance().set(
+            &DataKey::PenaltyConfig,
+            &PenaltyConfig { penalty_contract, violation_code, token, stake },
+        );
+    }
+
+    /// Stop penalizing abandons; reports are still recorded. Admin-only.
+    pub fn clear_penalty_config(env: Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        admin.require_auth();
+
+        env.storage().instance().remove(&DataKey::PenaltyConfig);
+    }
+
+    /// Read the abandon penalty configuration, if any.
+    pub fn penalty_config(env: Env) -> Option<PenaltyConfig> {
+        env.storage().instance().get(&DataKey::PenaltyConfig)
+    }
+
+    /// Whether `player` has been reported for abandoning `match_id`.
+    pub fn abandon_reported(env: Env, match_id: u64, player: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Abandon(match_id, player))
+    }
+
+    /// Read the stake a player has in escrow, if any.
+    pub fn stake_of(env: Env, player: Address) -> Option<PlayerStake> {
+        env.storage().persistent().get(&DataKey::Stake(player))
+    }
+
+    /// Limit how many players may wait in a queue. Admin-only. Players
+    /// already waiting are kept if the new limit is lower.
+    pub fn set_queue_capacity(env: Env, queue_id: Symbol, max_size: u32) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        admin.require_auth();
+        assert!(max_size > 0, "Capacity must be positive");
+
+        env.storage().persistent().set(&DataKey::QueueCapacity(queue_id), &max_size);
+    }
+
+    /// Notify `game_contract.start_selector(match_id, players)` whenever
+    /// `queue_id` forms a match. Admin-only.
+    pub fn set_match_callback(
+        env: Env,
+        queue_id: Symbol,
+        game_contract: Address,
+        start_selector: Symbol,
+    ) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        admin.require_auth();
+
+        env.storage().persistent().set(
+            &DataKey::MatchCallback(queue_id),
+            &MatchCallback { game_contract, start_selector },
+        );
+    }
+
+    /// Stop notifying a game contract for `queue_id`. Admin-only.
+    pub fn clear_match_callback(env: Env, queue_id: Symbol) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        admin.require_auth();
+
+        env.storage().persistent().remove(&DataKey::MatchCallback(queue_id));
+    }
+
+    /// Read a queue's match callback, if one is configured.
+    pub fn match_callback(env: Env, queue_id: Symbol) -> Option<MatchCallback> {
+        env.storage().persistent().get(&DataKey::MatchCallback(queue_id))
+    }
+
+    /// Read a queue's capacity; `None` means unbounded.
+    pub fn queue_capacity(env: Env, queue_id: Symbol) -> Option<u32> {
+        env.storage().persistent().get(&DataKey::QueueCapacity(queue_id))
+    }
+
     /// Read the current state of a queue.
     pub fn queue_state(env: Env, queue_id: Symbol) -> MatchQueueState {
         env.storage()
@@ -209,11 +536,122 @@ impl MatchmakingQueue {
     }
 }
 
+/// Clear a player's global queue lock if it points at `queue_id`.
+fn release_player_lock(env: &Env, player: &Address, queue_id: &Symbol) {
+    let lock_key = DataKey::PlayerQueue(player.clone());
+    if env.storage().persistent().get::<_, Symbol>(&lock_key).as_ref() == Some(queue_id) {
+        env.storage().persistent().remove(&lock_key);
+    }
+}
+
+/// Return a player's escrowed stake, if any.
+fn refund_stake(env: &Env, player: &Address) {
+    let key = DataKey::Stake(player.clone());
+    if let Some(stake) = env.storage().persistent().get::<_, PlayerStake>(&key) {
+        env.storage().persistent().remove(&key);
+        token::Client::new(env, &stake.token).transfer(
+            &env.current_contract_address(),
+            player,
+            &stake.amount,
+        );
+    }
+}
+
+/// Penalize an abandoning player out of their escrowed stake and return
+/// whatever the slash did not take. The penalty contract only gets an
+/// allowance for the stake, so it can never take more than was escrowed.
+fn slash_stake(env: &Env, config: &PenaltyConfig, player: &Address, context: &Symbol) -> u64 {
+    let penalty = PenaltyClient::new(env, &config.penalty_contract);
+    let contract = env.current_contract_address();
+    let key = DataKey::Stake(player.clone());
+    let Some(stake) = env.storage().persistent().get::<_, PlayerStake>(&key) else {
+        return penalty.apply_penalty_from(
+            &contract,
+            player,
+            &config.violation_code,
+            context,
+            &config.token,
+        );
+    };
+    env.storage().persistent().remove(&key);
+
+    let token = token::Client::new(env, &stake.token);
+    let expiry = env.ledger().sequence();
+    token.approve(&contract, &config.penalty_contract, &stake.amount, &expiry);
+    let before = token.balance(&contract);
+    let penalty_id = penalty.apply_penalty_from(
+        &contract,
+        player,
+        &config.violation_code,
+        context,
+        &stake.token,
+    );
+    let remaining = stake.amount - (before - token.balance(&contract));
+    token.approve(&contract, &config.penalty_contract, &0, &expiry);
+    if remaining > 0 {
+        token.transfer(&contract, player, &remaining);
+    }
+
+    penalty_id
+}
+
 // ── Tests ─────────────────────────────────────────────────────────
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, vec, Env, Symbol};
+    use soroban_sdk::{
+        contract, contractimpl,
+        testutils::{Address as _, MockAuth, MockAuthInvoke},
+        vec, Env, Symbol,
+    };
+    use stellarcade_penalty_slashing::{PenaltyRule, PenaltySlashing, PenaltySlashingClient};
+
+    /// Stand-in game contract: records the last match it was asked to start.
+    #[contract]
+    pub struct MockGame;
+
+    #[contractimpl]
+    impl MockGame {
+        pub fn start_match(env: Env, match_id: u64, players: Vec<Address>) {
+            env.storage().instance().set(&symbol_short!("started"), &(match_id, players));
+        }
+
+        pub fn reject(_env: Env, _match_id: u64, _players: Vec<Address>) {
+            panic!("Game refused to start");
+        }
+
+        pub fn started(env: Env) -> Option<(u64, Vec<Address>)> {
+            env.storage().instance().get(&symbol_short!("started"))
+        }
+    }
+
+    /// Stand-in penalty contract: records every penalty it is asked to apply.
+    #[contract]
+    pub struct MockPenalty;
+
+    #[contractimpl]
+    impl MockPenalty {
+        pub fn apply_penalty_from(
+            env: Env,
+            _payer: Address,
+            account: Address,
+            code: Symbol,
+            context_hash: Symbol,
+            _token_address: Address,
+        ) -> u64 {
+            let mut calls: Vec<(Address, Symbol, Symbol)> = Self::calls(env.clone());
+            calls.push_back((account, code, context_hash));
+            env.storage().instance().set(&symbol_short!("calls"), &calls);
+            calls.len() as u64
+        }
+
+        pub fn calls(env: Env) -> Vec<(Address, Symbol, Symbol)> {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("calls"))
+                .unwrap_or_else(|| Vec::new(&env))
+        }
+    }
 
     #[test]
     fn test_enqueue_and_create_match() {
@@ -294,4 +732,367 @@ mod test {
         client.init(&admin);
         client.init(&admin);
     }
+
+    #[test]
+    #[should_panic(expected = "AlreadyQueued")]
+    fn test_enqueue_into_second_queue_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let p1 = Address::generate(&env);
+        let crit = Symbol::new(&env, "1v1");
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        client.init(&admin);
+        client.enqueue_player(&Symbol::new(&env, "ranked"), &p1, &crit);
+        client.enqueue_player(&Symbol::new(&env, "casual"), &p1, &crit);
+    }
+
+    #[test]
+    fn test_join_second_queue_after_leaving_first() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        let ranked = Symbol::new(&env, "ranked");
+        let casual = Symbol::new(&env, "casual");
+        let crit = Symbol::new(&env, "1v1");
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        // Leaving via dequeue frees the player.
+        client.enqueue_player(&ranked, &p1, &crit);
+        client.dequeue_player(&p1, &ranked, &p1);
+        client.enqueue_player(&casual, &p1, &crit);
+        assert_eq!(client.queue_state(&casual).players.len(), 1);
+
+        // Being matched frees the player too.
+        client.enqueue_player(&casual, &p2, &crit);
+        client.create_match(&casual, &vec![&env, p1.clone(), p2.clone()]);
+        client.enqueue_player(&ranked, &p1, &crit);
+        client.enqueue_player(&ranked, &p2, &crit);
+        assert_eq!(client.queue_state(&ranked).players.len(), 2);
+    }
+
+    #[test]
+    fn test_queue_capacity_enforced_and_freed_by_match() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let queue_id = Symbol::new(&env, "ranked");
+        let crit = Symbol::new(&env, "1v1");
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        client.init(&admin);
+        assert_eq!(client.queue_capacity(&queue_id), None);
+
+        client.set_queue_capacity(&queue_id, &2);
+        assert_eq!(client.queue_capacity(&queue_id), Some(2));
+
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        let p3 = Address::generate(&env);
+        client.enqueue_player(&queue_id, &p1, &crit);
+        client.enqueue_player(&queue_id, &p2, &crit);
+
+        let full = client.try_enqueue_player(&queue_id, &p3, &crit);
+        assert!(full.is_err());
+
+        client.create_match(&queue_id, &vec![&env, p1.clone()]);
+        client.enqueue_player(&queue_id, &p3, &crit);
+        assert_eq!(client.queue_state(&queue_id).players, vec![&env, p2, p3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "QueueFull")]
+    fn test_enqueue_into_full_queue_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let queue_id = Symbol::new(&env, "ranked");
+        let crit = Symbol::new(&env, "1v1");
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        client.init(&admin);
+        client.set_queue_capacity(&queue_id, &1);
+
+        client.enqueue_player(&queue_id, &Address::generate(&env), &crit);
+        client.enqueue_player(&queue_id, &Address::generate(&env), &crit);
+    }
+
+    #[test]
+    fn test_match_callback_starts_game() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let queue_id = Symbol::new(&env, "ranked");
+        let crit = Symbol::new(&env, "1v1");
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        let game_id = env.register_contract(None, MockGame);
+        let game = MockGameClient::new(&env, &game_id);
+
+        client.init(&admin);
+        let selector = Symbol::new(&env, "start_match");
+        client.set_match_callback(&queue_id, &game_id, &selector);
+        assert_eq!(
+            client.match_callback(&queue_id),
+            Some(MatchCallback { game_contract: game_id.clone(), start_selector: selector })
+        );
+
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        client.enqueue_player(&queue_id, &p1, &crit);
+        client.enqueue_player(&queue_id, &p2, &crit);
+        assert_eq!(game.started(), None);
+
+        let players = vec![&env, p1, p2];
+        let match_id = client.create_match(&queue_id, &players);
+        assert_eq!(game.started(), Some((match_id, players)));
+    }
+
+    #[test]
+    fn test_cancel_match_with_requeue_returns_players() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let ranked = Symbol::new(&env, "ranked");
+        let casual = Symbol::new(&env, "casual");
+        let crit = Symbol::new(&env, "1v1");
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        let p3 = Address::generate(&env);
+        let waiting = Address::generate(&env);
+        for p in [&p1, &p2, &p3, &waiting] {
+            client.enqueue_player(&ranked, p, &crit);
+        }
+        let players = vec![&env, p1.clone(), p2.clone(), p3.clone()];
+        let match_id = client.create_match(&ranked, &players);
+
+        // p3 moved on to another queue after being matched.
+        client.enqueue_player(&casual, &p3, &crit);
+
+        client.cancel_match(&match_id, &true);
+        assert_eq!(client.match_state(&match_id).status, MatchStatus::Abandoned);
+        assert_eq!(
+            client.queue_state(&ranked).players,
+            vec![&env, waiting, p1.clone(), p2.clone()]
+        );
+        assert_eq!(client.queue_state(&casual).players, vec![&env, p3.clone()]);
+
+        // Requeued players hold the queue lock again.
+        let other = client.try_enqueue_player(&casual, &p1, &crit);
+        assert!(other.is_err());
+    }
+
+    #[test]
+    fn test_cancel_match_without_requeue_releases_players() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let ranked = Symbol::new(&env, "ranked");
+        let casual = Symbol::new(&env, "casual");
+        let crit = Symbol::new(&env, "1v1");
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        client.enqueue_player(&ranked, &p1, &crit);
+        client.enqueue_player(&ranked, &p2, &crit);
+        let match_id = client.create_match(&ranked, &vec![&env, p1.clone(), p2.clone()]);
+
+        client.cancel_match(&match_id, &false);
+        assert_eq!(client.match_state(&match_id).status, MatchStatus::Abandoned);
+        assert_eq!(client.queue_state(&ranked).players.len(), 0);
+
+        // Players are free to join any queue, and the match cannot be cancelled twice.
+        client.enqueue_player(&casual, &p1, &crit);
+        assert!(client.try_cancel_match(&match_id, &true).is_err());
+        assert_eq!(client.queue_state(&ranked).players.len(), 0);
+    }
+
+    #[test]
+    fn test_rejected_callback_rolls_back_match() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let queue_id = Symbol::new(&env, "ranked");
+        let crit = Symbol::new(&env, "1v1");
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        let game_id = env.register_contract(None, MockGame);
+
+        client.init(&admin);
+        client.set_match_callback(&queue_id, &game_id, &Symbol::new(&env, "reject"));
+
+        let p1 = Address::generate(&env);
+        client.enqueue_player(&queue_id, &p1, &crit);
+        let result = client.try_create_match(&queue_id, &vec![&env, p1.clone()]);
+        assert!(result.is_err());
+
+        // The player is still waiting and the match id was not consumed.
+        assert_eq!(client.queue_state(&queue_id).players, vec![&env, p1.clone()]);
+        client.clear_match_callback(&queue_id);
+        assert_eq!(client.create_match(&queue_id, &vec![&env, p1]), 0);
+    }
+
+    #[test]
+    fn test_report_abandon_applies_penalty() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let token = Address::generate(&env);
+        let queue_id = Symbol::new(&env, "ranked");
+        let crit = Symbol::new(&env, "1v1");
+        let code = Symbol::new(&env, "abandon");
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        let penalty_id = env.register_contract(None, MockPenalty);
+        let penalty = MockPenaltyClient::new(&env, &penalty_id);
+        client.init(&admin);
+
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        client.enqueue_player(&queue_id, &p1, &crit);
+        client.enqueue_player(&queue_id, &p2, &crit);
+        let match_id = client.create_match(&queue_id, &vec![&env, p1.clone(), p2.clone()]);
+
+        // Without a penalty contract the abandon is only recorded.
+        assert_eq!(client.report_abandon(&match_id, &p1), None);
+        assert!(client.abandon_reported(&match_id, &p1));
+
+        client.set_penalty_config(&penalty_id, &code, &token, &0);
+        assert_eq!(client.report_abandon(&match_id, &p2), Some(1));
+        assert_eq!(penalty.calls(), vec![&env, (p2.clone(), code, queue_id)]);
+        assert!(!client.abandon_reported(&match_id, &Address::generate(&env)));
+
+        // Each player is reported once, and only for matches they played in.
+        assert!(client.try_report_abandon(&match_id, &p2).is_err());
+        assert!(client.try_report_abandon(&match_id, &Address::generate(&env)).is_err());
+        assert_eq!(penalty.calls().len(), 1);
+    }
+
+    #[test]
+    fn test_report_abandon_rejects_settled_match() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let queue_id = Symbol::new(&env, "ranked");
+        let crit = Symbol::new(&env, "1v1");
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        client.enqueue_player(&queue_id, &p1, &crit);
+        client.enqueue_player(&queue_id, &p2, &crit);
+        let done = client.create_match(&queue_id, &vec![&env, p1.clone()]);
+        let cancelled = client.create_match(&queue_id, &vec![&env, p2.clone()]);
+
+        client.complete_match(&done);
+        client.cancel_match(&cancelled, &false);
+        assert_eq!(client.match_state(&done).status, MatchStatus::Completed);
+        assert!(client.try_report_abandon(&done, &p1).is_err());
+        assert!(client.try_report_abandon(&cancelled, &p2).is_err());
+        assert!(client.try_complete_match(&done).is_err());
+    }
+
+    #[test]
+    fn test_report_abandon_slashes_stake_through_penalty_slashing() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let queue_id = Symbol::new(&env, "ranked");
+        let crit = Symbol::new(&env, "1v1");
+        let code = Symbol::new(&env, "abandon");
+
+        let sac = env.register_stellar_asset_contract_v2(admin.clone());
+        let token_id = sac.address();
+        let tokens = token::Client::new(&env, &token_id);
+        let minter = token::StellarAssetClient::new(&env, &token_id);
+
+        let contract_id = env.register_contract(None, MatchmakingQueue);
+        let client = MatchmakingQueueClient::new(&env, &contract_id);
+        client.init(&admin);
+
+        // The matchmaking contract administers the penalty contract.
+        let penalty_id = env.register_contract(None, PenaltySlashing);
+        let penalty = PenaltySlashingClient::new(&env, &penalty_id);
+        penalty.init(&contract_id, &treasury);
+        penalty.define_violation(
+            &code,
+            &PenaltyRule {
+                code: code.clone(),
+                slash_amount: 40,
+                description_hash: Symbol::new(&env, "left"),
+                active: true,
+            },
+        );
+        client.set_penalty_config(&penalty_id, &code, &token_id, &100);
+
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        for p in [&p1, &p2] {
+            minter.mint(p, &1000);
+            client.enqueue_player(&queue_id, p, &crit);
+        }
+        assert_eq!(tokens.balance(&contract_id), 200);
+        let match_id = client.create_match(&queue_id, &vec![&env, p1.clone(), p2.clone()]);
+
+        // Only the admin signs: the slash comes out of p1's escrowed stake.
+        env.mock_auths(&[MockAuth {
+            address: &admin,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "report_abandon",
+                args: (match_id, p1.clone()).into_val(&env),
+                sub_invokes: &[],
+            },
+        }]);
+        let pid = client.report_abandon(&match_id, &p1).expect("penalty applied");
+
+        let record = penalty.penalty_state(&pid);
+        assert_eq!(record.account, p1);
+        assert_eq!(record.context_hash, queue_id);
+        assert_eq!(tokens.balance(&treasury), 40);
+        assert_eq!(tokens.balance(&p1), 960);
+        assert_eq!(client.stake_of(&p1), None);
+
+        // p2 played it out and gets the full stake back.
+        env.mock_all_auths();
+        client.complete_match(&match_id);
+        assert_eq!(tokens.balance(&p2), 1000);
+        assert_eq!(tokens.balance(&contract_id), 0);
+    }
 }
@@ -17,9 +17,10 @@
 //! ## Fairness Model
 //! The player's guess is committed to storage **before** the oracle reveals the
 //! server seed, so the oracle cannot select a seed after observing the guess.
-//! After resolution, anyone can independently verify:
-//!   `sha256(server_seed || game_id_be)[0..8] % range_size + min == secret`
-//! using the seed stored by the RNG contract.
+//! After resolution, anyone can independently verify that
+//!   `rng_result % range_size + min == secret`
+//! where `rng_result` is re-derived from the server seed and ledger entropy
+//! stored by the RNG contract (see its `FulfilledEntry`).
 //!
 //! ## Payout
 //! A winning player receives their full wager back plus a multiplier equal to
@@ -60,6 +61,9 @@ const BASIS_POINTS_DIVISOR: i128 = 10_000;
 /// even for the platform's maximum token supply.
 pub const MAX_RANGE_SIZE: u32 = 1_000_000;
 
+/// Default seconds after a guess before an unfulfilled game can be refunded.
+pub const DEFAULT_REFUND_TIMEOUT: u64 = 86_400;
+
 // ---------------------------------------------------------------------------
 // Error Types
 // ---------------------------------------------------------------------------
@@ -89,6 +93,11 @@ pub enum Error {
     WagerTooLow = 14,
     WagerTooHigh = 15,
     Overflow = 16,
+    /// `refund_stuck_guess` called on a game whose RNG request was fulfilled;
+    /// it must be resolved instead.
+    RngAlreadyFulfilled = 17,
+    /// `refund_stuck_guess` called before the refund timeout elapsed.
+    RefundTooEarly = 18,
 }
 
 // ---------------------------------------------------------------------------
@@ -101,6 +110,7 @@ pub enum Error {
 /// `Guessed`  — `submit_guess` called; waiting for RNG fulfillment and resolution.
 /// `Won`      — Resolved; guess matched the secret number.
 /// `Lost`     — Resolved; guess did not match.
+/// `Refunded` — RNG never fulfilled; wager returned by `refund_stuck_guess`.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum GameStatus {
@@ -108,6 +118,7 @@ pub enum GameStatus {
     Guessed = 1,
     Won = 2,
     Lost = 3,
+    Refunded = 4,
 }
 
 /// Full state for a single number-guess game.
@@ -127,6 +138,8 @@ pub struct Game {
     pub secret: u32,
     /// Token payout sent to the player on a win; 0 on a loss or before resolution.
     pub payout: i128,
+    /// Ledger timestamp of `submit_guess`; 0 until the player submits.
+    pub guessed_at: u64,
 }
 
 /// Storage key discriminants.
@@ -140,6 +153,8 @@ pub enum DataKey {
     MinWager,
     MaxWager,
     HouseEdgeBps,
+    /// Seconds after a guess before `refund_stuck_guess` is allowed (u64).
+    RefundTimeout,
     // --- persistent() keys: per-game data ---
     Game(u64),
 }
@@ -180,6 +195,20 @@ pub struct GameResolved {
     pub payout: i128,
 }
 
+#[contractevent]
+pub struct GuessRefunded {
+    #[topic]
+    pub game_id: u64,
+    #[topic]
+    pub player: Address,
+    pub wager: i128,
+}
+
+#[contractevent]
+pub struct RefundTimeoutSet {
+    pub timeout: u64,
+}
+
 // ---------------------------------------------------------------------------
 // Contract
 // ---------------------------------------------------------------------------
@@ -322,6 +351,7 @@ impl NumberGuess {
             status: GameStatus::Open,
             secret: 0,
             payout: 0,
+            guessed_at: 0,
         };
         env.storage().persistent().set(&game_key, &game);
         env.storage().persistent().extend_ttl(
@@ -380,6 +410,7 @@ impl NumberGuess {
 
         game.guess = guess;
         game.status = GameStatus::Guessed;
+        game.guessed_at = env.ledger().timestamp();
         env.storage().persistent().set(&game_key, &game);
         env.storage().persistent().extend_ttl(
             &game_key,
@@ -421,7 +452,9 @@ impl NumberGuess {
 
         match game.status {
             GameStatus::Open => return Err(Error::GuessNotSubmitted),
-            GameStatus::Won | GameStatus::Lost => return Err(Error::GameAlreadyResolved),
+            GameStatus::Won | GameStatus::Lost | GameStatus::Refunded => {
+                return Err(Error::GameAlreadyResolved)
+            }
             GameStatus::Guessed => {}
         }
 
@@ -499,6 +532,106 @@ impl NumberGuess {
         Ok(())
     }
 
+    // -----------------------------------------------------------------------
+    // refund_stuck_guess
+    // -----------------------------------------------------------------------
+
+    /// Refund the wager of a guessed game whose RNG request was never
+    /// fulfilled, once the refund timeout has passed since the guess.
+    ///
+    /// Authorization is required from `player`, who must own the game. The
+    /// game is voided (`Refunded`) before the transfer, so it can neither be
+    /// refunded twice nor resolved if the oracle fulfills late. A game whose
+    /// RNG result is available must be resolved instead.
+    pub fn refund_stuck_guess(env: Env, player: Address, game_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let game_key = DataKey::Game(game_id);
+        let mut game: Game = env
+            .storage()
+            .persistent()
+            .get(&game_key)
+            .ok_or(Error::GameNotFound)?;
+
+        if game.player != player {
+            return Err(Error::NotAuthorized);
+        }
+        match game.status {
+            GameStatus::Open => return Err(Error::GuessNotSubmitted),
+            GameStatus::Won | GameStatus::Lost | GameStatus::Refunded => {
+                return Err(Error::GameAlreadyResolved)
+            }
+            GameStatus::Guessed => {}
+        }
+
+        let timeout: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RefundTimeout)
+            .unwrap_or(DEFAULT_REFUND_TIMEOUT);
+        let refundable_at = game
+            .guessed_at
+            .checked_add(timeout)
+            .ok_or(Error::Overflow)?;
+        if env.ledger().timestamp() < refundable_at {
+            return Err(Error::RefundTooEarly);
+        }
+
+        let rng_addr: Address = env.storage().instance().get(&DataKey::RngContract).unwrap();
+        if let Ok(Ok(_)) = RandomGeneratorClient::new(&env, &rng_addr).try_get_result(&game_id) {
+            return Err(Error::RngAlreadyFulfilled);
+        }
+
+        // Void the game before the transfer (reentrancy guard).
+        game.status = GameStatus::Refunded;
+        env.storage().persistent().set(&game_key, &game);
+        env.storage().persistent().extend_ttl(
+            &game_key,
+            PERSISTENT_BUMP_LEDGERS,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::BalanceContract)
+            .unwrap();
+        TokenClient::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &game.player,
+            &game.wager,
+        );
+
+        GuessRefunded {
+            game_id,
+            player: game.player,
+            wager: game.wager,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Set the seconds after a guess before `refund_stuck_guess` is allowed.
+    /// Admin only. Defaults to `DEFAULT_REFUND_TIMEOUT` when never set.
+    pub fn set_refund_timeout(env: Env, timeout: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if timeout == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::RefundTimeout, &timeout);
+
+        RefundTimeoutSet { timeout }.publish(&env);
+
+        Ok(())
+    }
+
     // -----------------------------------------------------------------------
     // get_game
     // -----------------------------------------------------------------------
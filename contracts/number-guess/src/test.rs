@@ -2,11 +2,11 @@
 
 use super::*;
 use soroban_sdk::{
-    testutils::Address as _,
+    testutils::{Address as _, Ledger},
     token::{StellarAssetClient, TokenClient},
     Address, BytesN, Env,
 };
-use stellarcade_random_generator::{RandomGenerator, RandomGeneratorClient};
+use stellarcade_random_generator::{Entropy, RandomGenerator, RandomGeneratorClient};
 
 // ---------------------------------------------------------------------------
 // Helpers
@@ -25,12 +25,22 @@ fn make_seed(env: &Env, byte: u8) -> BytesN<32> {
 }
 
 /// Re-derive the RNG result the same way the Random Generator contract does,
-/// so tests can select seeds that produce a specific secret number.
-fn derive_rng_result(env: &Env, server_seed: &BytesN<32>, request_id: u64, max: u64) -> u64 {
+/// so tests can select seeds that produce a specific secret number. `entropy`
+/// is the ledger data the RNG recorded for the request (`pending_entropy`).
+fn derive_rng_result(
+    env: &Env,
+    server_seed: &BytesN<32>,
+    request_id: u64,
+    entropy: &Entropy,
+    max: u64,
+) -> u64 {
     use soroban_sdk::Bytes;
-    let mut preimage = [0u8; 40];
+    let mut preimage = [0u8; 84];
     preimage[..32].copy_from_slice(&server_seed.to_array());
-    preimage[32..].copy_from_slice(&request_id.to_be_bytes());
+    preimage[32..40].copy_from_slice(&request_id.to_be_bytes());
+    preimage[40..44].copy_from_slice(&entropy.ledger_sequence.to_be_bytes());
+    preimage[44..52].copy_from_slice(&entropy.timestamp.to_be_bytes());
+    preimage[52..].copy_from_slice(&entropy.prev_digest.to_array());
     let digest: BytesN<32> = env
         .crypto()
         .sha256(&Bytes::from_slice(env, &preimage))
@@ -43,11 +53,18 @@ fn derive_rng_result(env: &Env, server_seed: &BytesN<32>, request_id: u64, max:
 }
 
 /// Find a seed whose RNG result, mapped into [min, max], equals `target`.
-fn find_seed_for_target(env: &Env, game_id: u64, min: u32, max: u32, target: u32) -> BytesN<32> {
+fn find_seed_for_target(
+    env: &Env,
+    entropy: &Entropy,
+    game_id: u64,
+    min: u32,
+    max: u32,
+    target: u32,
+) -> BytesN<32> {
     let range_size = (max - min + 1) as u64;
     for i in 0u8..=255 {
         let seed = make_seed(env, i);
-        let rng_result = derive_rng_result(env, &seed, game_id, range_size);
+        let rng_result = derive_rng_result(env, &seed, game_id, entropy, range_size);
         let secret = min + rng_result as u32;
         if secret == target {
             return seed;
@@ -210,7 +227,8 @@ fn test_win_path() {
         .start_game(&player, &min, &max, &wager, &game_id);
     s.ng_client.submit_guess(&game_id, &target);
 
-    let winning_seed = find_seed_for_target(&env, game_id, min, max, target);
+    let entropy = s.rng_client.pending_entropy(&game_id);
+    let winning_seed = find_seed_for_target(&env, &entropy, game_id, min, max, target);
     s.rng_client
         .fulfill_random(&s.oracle, &game_id, &winning_seed);
     s.ng_client.resolve_game(&game_id);
@@ -252,10 +270,11 @@ fn test_loss_path() {
 
     // Find a seed whose outcome is NOT 3
     let range_size = (max - min + 1) as u64;
+    let entropy = s.rng_client.pending_entropy(&game_id);
     let mut losing_seed = make_seed(&env, 0);
     for i in 0u8..=255 {
         let seed = make_seed(&env, i);
-        let rng_result = derive_rng_result(&env, &seed, game_id, range_size);
+        let rng_result = derive_rng_result(&env, &seed, game_id, &entropy, range_size);
         let secret = min + rng_result as u32;
         if secret != guess {
             losing_seed = seed;
@@ -579,7 +598,8 @@ fn test_payout_formula_range_two() {
         .start_game(&player, &min, &max, &wager, &game_id);
     s.ng_client.submit_guess(&game_id, &target);
 
-    let winning_seed = find_seed_for_target(&env, game_id, min, max, target);
+    let entropy = s.rng_client.pending_entropy(&game_id);
+    let winning_seed = find_seed_for_target(&env, &entropy, game_id, min, max, target);
     s.rng_client
         .fulfill_random(&s.oracle, &game_id, &winning_seed);
     s.ng_client.resolve_game(&game_id);
@@ -695,8 +715,84 @@ fn test_secret_always_in_range() {
         );
 
         // Verify the derivation independently
-        let expected_rng = derive_rng_result(&env, &seed, game_id, range_size);
+        let entropy = s.rng_client.get_result(&game_id).entropy;
+        let expected_rng = derive_rng_result(&env, &seed, game_id, &entropy, range_size);
         let expected_secret = min + expected_rng as u32;
         assert_eq!(game.secret, expected_secret);
     }
 }
+
+// ---------------------------------------------------------------------------
+// 21. Refund when RNG fulfillment never arrives
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_refund_stuck_guess_after_timeout() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let player = Address::generate(&env);
+    s.token_sac.mint(&player, &500);
+    s.ng_client.set_refund_timeout(&3_600);
+
+    let game_id: u64 = 1;
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    s.ng_client
+        .start_game(&player, &1u32, &10u32, &100i128, &game_id);
+    s.ng_client.submit_guess(&game_id, &5u32);
+    assert_eq!(tc(&env, &s.token_addr).balance(&player), 400);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000 + 3_599);
+    assert_eq!(
+        s.ng_client.try_refund_stuck_guess(&player, &game_id),
+        Err(Ok(Error::RefundTooEarly))
+    );
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000 + 3_600);
+    s.ng_client.refund_stuck_guess(&player, &game_id);
+    assert_eq!(s.ng_client.get_game(&game_id).status, GameStatus::Refunded);
+    assert_eq!(tc(&env, &s.token_addr).balance(&player), 500);
+
+    // A late fulfillment cannot resolve or re-refund the voided game.
+    s.rng_client
+        .fulfill_random(&s.oracle, &game_id, &make_seed(&env, 1));
+    assert_eq!(
+        s.ng_client.try_resolve_game(&game_id),
+        Err(Ok(Error::GameAlreadyResolved))
+    );
+    assert_eq!(
+        s.ng_client.try_refund_stuck_guess(&player, &game_id),
+        Err(Ok(Error::GameAlreadyResolved))
+    );
+}
+
+#[test]
+fn test_refund_rejected_once_rng_fulfilled_or_resolved() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let player = Address::generate(&env);
+    s.token_sac.mint(&player, &500);
+
+    let game_id: u64 = 1;
+    s.ng_client
+        .start_game(&player, &1u32, &10u32, &100i128, &game_id);
+    s.ng_client.submit_guess(&game_id, &5u32);
+    s.rng_client
+        .fulfill_random(&s.oracle, &game_id, &make_seed(&env, 42));
+
+    env.ledger()
+        .with_mut(|l| l.timestamp += DEFAULT_REFUND_TIMEOUT);
+    assert_eq!(
+        s.ng_client.try_refund_stuck_guess(&player, &game_id),
+        Err(Ok(Error::RngAlreadyFulfilled))
+    );
+
+    s.ng_client.resolve_game(&game_id);
+    assert_eq!(
+        s.ng_client.try_refund_stuck_guess(&player, &game_id),
+        Err(Ok(Error::GameAlreadyResolved))
+    );
+}
@@ -2,7 +2,7 @@
 
 use soroban_sdk::{
     contract, contracterror, contractevent, contractimpl, contracttype,
-    Address, Bytes, BytesN, Env, Vec,
+    Address, Bytes, BytesN, Env, Map, Vec,
 };
 
 #[contract]
@@ -21,6 +21,12 @@ pub enum DataKey {
     OracleSources,
     Request(BytesN<32>),
     Latest(BytesN<32>),
+    Submissions(BytesN<32>),
+    LatestValue(BytesN<32>),
+    LastFulfilled(BytesN<32>),
+    SourceLatest(BytesN<32>, Address),
+    FeedSubscribers(BytesN<32>),
+    SubscriberAllowed(Address),
 }
 
 #[derive(Clone)]
@@ -29,6 +35,19 @@ pub struct OracleRequest {
     pub feed_id: BytesN<32>,
     pub fulfilled: bool,
     pub payload: Bytes,
+    /// Distinct oracle submissions needed before a numeric request finalizes.
+    /// Zero for byte-payload requests.
+    pub min_responses: u32,
+    /// Median of the submitted values once a numeric request is fulfilled.
+    pub value: Option<i128>,
+}
+
+/// The most recent payload one oracle delivered for a feed.
+#[derive(Clone)]
+#[contracttype]
+pub struct SourceReading {
+    pub payload: Bytes,
+    pub updated_at: u64,
 }
 
 //
@@ -54,6 +73,42 @@ pub struct RequestFulfilled {
     pub feed_id: BytesN<32>,
 }
 
+/// Emitted alongside `RequestFulfilled` so indexers see which oracle
+/// answered, how much data it sent and when, without reading the request.
+#[contractevent]
+pub struct DataUpdated {
+    #[topic]
+    pub feed_id: BytesN<32>,
+    pub oracle: Address,
+    pub payload_len: u32,
+    pub fulfilled_at: u64,
+}
+
+/// One event per subscriber each time `feed_id` gets new data, topic'd by
+/// both so a consumer can filter on its own address.
+#[contractevent]
+pub struct FeedUpdated {
+    #[topic]
+    pub feed_id: BytesN<32>,
+    #[topic]
+    pub subscriber: Address,
+}
+
+#[contractevent]
+pub struct ValueSubmitted {
+    pub request_id: BytesN<32>,
+    pub oracle: Address,
+    pub value: i128,
+}
+
+#[contractevent]
+pub struct ValueFinalized {
+    pub request_id: BytesN<32>,
+    pub feed_id: BytesN<32>,
+    pub value: i128,
+    pub responses: u32,
+}
+
 //
 // ─────────────────────────────────────────────
 // ERRORS
@@ -71,6 +126,12 @@ pub enum Error {
     InvalidInput = 6,
     OracleNotWhitelisted = 7,
     Overflow = 8,
+    DuplicateSubmission = 9,
+    WrongRequestKind = 10,
+    AlreadySubscribed = 11,
+    NotSubscribed = 12,
+    TooManySubscribers = 13,
+    SubscriberNotAllowed = 14,
 }
 
 //
@@ -81,6 +142,11 @@ pub enum Error {
 
 const TTL_RENEW_WINDOW: u32 = 1_000;
 
+/// Cap on subscribers per feed, bounding the events each update emits. Only
+/// admin-allowlisted addresses may take a slot, so the cap cannot be filled
+/// with throwaway addresses.
+pub const MAX_FEED_SUBSCRIBERS: u32 = 20;
+
 fn renew_persistent_ttl(env: &Env, key: &DataKey) -> Result<(), Error> {
     let max_ttl = env.storage().max_ttl();
 
@@ -179,6 +245,8 @@ pub fn request_data(
             feed_id: feed_id.clone(),
             fulfilled: false,
             payload: Bytes::new(&env),
+            min_responses: 0,
+            value: None,
         };
 
         env.storage().persistent().set(&key, &request);
@@ -233,6 +301,10 @@ pub fn request_data(
             return Err(Error::AlreadyFulfilled);
         }
 
+        if request.min_responses > 0 {
+            return Err(Error::WrongRequestKind);
+        }
+
         request.fulfilled = true;
         request.payload = payload.clone();
 
@@ -244,9 +316,96 @@ pub fn request_data(
         env.storage().persistent().set(&latest_key, &payload);
         renew_persistent_ttl(&env, &latest_key)?;
 
+        let now = env.ledger().timestamp();
+
+        let fulfilled_key = DataKey::LastFulfilled(request.feed_id.clone());
+        env.storage().persistent().set(&fulfilled_key, &now);
+        renew_persistent_ttl(&env, &fulfilled_key)?;
+
+        let source_key = DataKey::SourceLatest(request.feed_id.clone(), caller.clone());
+        env.storage().persistent().set(
+            &source_key,
+            &SourceReading {
+                payload: payload.clone(),
+                updated_at: now,
+            },
+        );
+        renew_persistent_ttl(&env, &source_key)?;
+
         let feed_id = request.feed_id.clone();
 
         RequestFulfilled {
+            request_id,
+            feed_id: feed_id.clone(),
+        }
+        .publish(&env);
+
+        DataUpdated {
+            feed_id: feed_id.clone(),
+            oracle: caller,
+            payload_len: payload.len(),
+            fulfilled_at: now,
+        }
+        .publish(&env);
+
+        notify_subscribers(&env, &feed_id);
+
+        Ok(())
+    }
+
+    // ───────── NUMERIC FEEDS ─────────
+
+    /// Open a request for an `i128` value that finalizes to the median of the
+    /// first `min_responses` distinct oracle submissions. `min_responses`
+    /// may not exceed the number of whitelisted oracles, or the request could
+    /// never finalize.
+    pub fn request_numeric_data(
+        env: Env,
+        caller: Address,
+        feed_id: BytesN<32>,
+        request_id: BytesN<32>,
+        min_responses: u32,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotAuthorized);
+        }
+
+        renew_instance_ttl(&env)?;
+
+        let zero = BytesN::from_array(&env, &[0; 32]);
+        if feed_id == zero || request_id == zero || min_responses == 0 {
+            return Err(Error::InvalidInput);
+        }
+
+        let sources: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::OracleSources)
+            .ok_or(Error::NotAuthorized)?;
+        if min_responses > sources.len() {
+            return Err(Error::InvalidInput);
+        }
+
+        let key = DataKey::Request(request_id.clone());
+
+        if env.storage().persistent().has(&key) {
+            return Err(Error::RequestExists);
+        }
+
+        let request = OracleRequest {
+            feed_id: feed_id.clone(),
+            fulfilled: false,
+            payload: Bytes::new(&env),
+            min_responses,
+            value: None,
+        };
+
+        env.storage().persistent().set(&key, &request);
+        renew_persistent_ttl(&env, &key)?;
+
+        RequestCreated {
             request_id,
             feed_id,
         }
@@ -255,8 +414,196 @@ pub fn request_data(
         Ok(())
     }
 
+    /// Submit a whitelisted oracle's value for a numeric request. Each oracle
+    /// may submit once; the submission that reaches `min_responses` finalizes
+    /// the request with the median of all collected values.
+    pub fn submit_value(
+        env: Env,
+        caller: Address,
+        request_id: BytesN<32>,
+        value: i128,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        renew_instance_ttl(&env)?;
+
+        let sources: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::OracleSources)
+            .ok_or(Error::NotAuthorized)?;
+
+        if !sources.contains(&caller) {
+            return Err(Error::OracleNotWhitelisted);
+        }
+
+        let req_key = DataKey::Request(request_id.clone());
+
+        let mut request: OracleRequest = env
+            .storage()
+            .persistent()
+            .get(&req_key)
+            .ok_or(Error::RequestNotFound)?;
+
+        if request.fulfilled {
+            return Err(Error::AlreadyFulfilled);
+        }
+
+        if request.min_responses == 0 {
+            return Err(Error::WrongRequestKind);
+        }
+
+        let sub_key = DataKey::Submissions(request_id.clone());
+
+        let mut submissions: Map<Address, i128> = env
+            .storage()
+            .persistent()
+            .get(&sub_key)
+            .unwrap_or(Map::new(&env));
+
+        if submissions.contains_key(caller.clone()) {
+            return Err(Error::DuplicateSubmission);
+        }
+
+        submissions.set(caller.clone(), value);
+        env.storage().persistent().set(&sub_key, &submissions);
+        renew_persistent_ttl(&env, &sub_key)?;
+
+        ValueSubmitted {
+            request_id: request_id.clone(),
+            oracle: caller,
+            value,
+        }
+        .publish(&env);
+
+        if submissions.len() < request.min_responses {
+            return Ok(());
+        }
+
+        let median = median(&submissions.values());
+
+        request.fulfilled = true;
+        request.value = Some(median);
+
+        env.storage().persistent().set(&req_key, &request);
+        renew_persistent_ttl(&env, &req_key)?;
+
+        let latest_key = DataKey::LatestValue(request.feed_id.clone());
+
+        env.storage().persistent().set(&latest_key, &median);
+        renew_persistent_ttl(&env, &latest_key)?;
+
+        ValueFinalized {
+            request_id,
+            feed_id: request.feed_id.clone(),
+            value: median,
+            responses: submissions.len(),
+        }
+        .publish(&env);
+
+        notify_subscribers(&env, &request.feed_id);
+
+        Ok(())
+    }
+
+    // ───────── SUBSCRIPTIONS ─────────
+
+    /// Allow or forbid `subscriber` to take subscription slots. Admin only.
+    /// Forbidding does not drop existing subscriptions; see `evict_subscriber`.
+    pub fn set_subscriber_allowed(
+        env: Env,
+        subscriber: Address,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        require_admin(&env)?;
+
+        let key = DataKey::SubscriberAllowed(subscriber);
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+            renew_persistent_ttl(&env, &key)?;
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        Ok(())
+    }
+
+    pub fn is_subscriber_allowed(env: Env, subscriber: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::SubscriberAllowed(subscriber))
+    }
+
+    /// Register `subscriber` for a `FeedUpdated` event whenever `feed_id` is
+    /// fulfilled or a numeric request on it finalizes. The subscriber must
+    /// be allowlisted by the admin.
+    pub fn subscribe_feed(
+        env: Env,
+        subscriber: Address,
+        feed_id: BytesN<32>,
+    ) -> Result<(), Error> {
+        subscriber.require_auth();
+
+        if !Self::is_subscriber_allowed(env.clone(), subscriber.clone()) {
+            return Err(Error::SubscriberNotAllowed);
+        }
+
+        let key = DataKey::FeedSubscribers(feed_id.clone());
+        let mut subscribers = Self::feed_subscribers(env.clone(), feed_id);
+
+        if subscribers.contains(&subscriber) {
+            return Err(Error::AlreadySubscribed);
+        }
+        if subscribers.len() >= MAX_FEED_SUBSCRIBERS {
+            return Err(Error::TooManySubscribers);
+        }
+
+        subscribers.push_back(subscriber);
+        env.storage().persistent().set(&key, &subscribers);
+        renew_persistent_ttl(&env, &key)?;
+
+        Ok(())
+    }
+
+    pub fn unsubscribe_feed(
+        env: Env,
+        subscriber: Address,
+        feed_id: BytesN<32>,
+    ) -> Result<(), Error> {
+        subscriber.require_auth();
+        remove_subscriber(&env, feed_id, &subscriber)
+    }
+
+    /// Drop `subscriber` from `feed_id`, freeing its slot. Admin only.
+    pub fn evict_subscriber(
+        env: Env,
+        subscriber: Address,
+        feed_id: BytesN<32>,
+    ) -> Result<(), Error> {
+        require_admin(&env)?;
+        remove_subscriber(&env, feed_id, &subscriber)
+    }
+
+    pub fn feed_subscribers(env: Env, feed_id: BytesN<32>) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::FeedSubscribers(feed_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
     // ───────── READ METHODS ─────────
 
+    pub fn latest_value(env: Env, feed_id: BytesN<32>) -> Option<i128> {
+        let key = DataKey::LatestValue(feed_id);
+        let result = env.storage().persistent().get(&key);
+
+        if result.is_some() {
+            renew_persistent_ttl(&env, &key).ok();
+        }
+
+        result
+    }
+
     pub fn latest(env: Env, feed_id: BytesN<32>) -> Option<Bytes> {
         let key = DataKey::Latest(feed_id);
         let result = env.storage().persistent().get(&key);
@@ -268,6 +615,45 @@ pub fn request_data(
         result
     }
 
+    /// Ledger timestamp of the last byte-payload fulfillment for `feed_id`.
+    pub fn last_fulfilled(env: Env, feed_id: BytesN<32>) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::LastFulfilled(feed_id))
+    }
+
+    /// Freshest payload any whitelisted oracle has delivered for `feed_id`,
+    /// provided it is at most `max_age` seconds old. Lets consumers fall back
+    /// to a secondary source when the usual one goes quiet. Only
+    /// byte-payload fulfillments are tracked per source.
+    pub fn latest_with_fallback(env: Env, feed_id: BytesN<32>, max_age: u64) -> Option<Bytes> {
+        let sources: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::OracleSources)
+            .unwrap_or(Vec::new(&env));
+        let now = env.ledger().timestamp();
+
+        let mut freshest: Option<SourceReading> = None;
+        for oracle in sources.iter() {
+            let reading: Option<SourceReading> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::SourceLatest(feed_id.clone(), oracle));
+            let Some(reading) = reading else { continue };
+            if now.saturating_sub(reading.updated_at) > max_age {
+                continue;
+            }
+            let newer = match &freshest {
+                Some(best) => reading.updated_at > best.updated_at,
+                None => true,
+            };
+            if newer {
+                freshest = Some(reading);
+            }
+        }
+
+        freshest.map(|reading| reading.payload)
+    }
+
     pub fn get_request(
         env: Env,
         request_id: BytesN<32>,
@@ -282,4 +668,389 @@ pub fn request_data(
 
         result
     }
-}
\ No newline at end of file
+}
+
+fn require_admin(env: &Env) -> Result<(), Error> {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(Error::NotAuthorized)?;
+    admin.require_auth();
+    Ok(())
+}
+
+fn remove_subscriber(env: &Env, feed_id: BytesN<32>, subscriber: &Address) -> Result<(), Error> {
+    let key = DataKey::FeedSubscribers(feed_id.clone());
+    let mut subscribers = OracleIntegration::feed_subscribers(env.clone(), feed_id);
+
+    let index = subscribers
+        .first_index_of(subscriber)
+        .ok_or(Error::NotSubscribed)?;
+    subscribers.remove(index);
+
+    if subscribers.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &subscribers);
+        renew_persistent_ttl(env, &key)?;
+    }
+
+    Ok(())
+}
+
+fn notify_subscribers(env: &Env, feed_id: &BytesN<32>) {
+    let subscribers: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::FeedSubscribers(feed_id.clone()))
+        .unwrap_or(Vec::new(env));
+
+    for subscriber in subscribers.iter() {
+        FeedUpdated {
+            feed_id: feed_id.clone(),
+            subscriber,
+        }
+        .publish(env);
+    }
+}
+
+//
+// ─────────────────────────────────────────────
+// AGGREGATION
+// ─────────────────────────────────────────────
+//
+
+/// Median of `values`. For an even count this is the mean of the two middle
+/// values, rounded toward negative infinity. Sorting is a plain insertion
+/// sort, which keeps the result independent of submission order and is cheap
+/// for the handful of oracles a request collects.
+fn median(values: &Vec<i128>) -> i128 {
+    let mut sorted = values.clone();
+    let n = sorted.len();
+
+    for i in 1..n {
+        let mut j = i;
+        while j > 0 && sorted.get_unchecked(j - 1) > sorted.get_unchecked(j) {
+            let prev = sorted.get_unchecked(j - 1);
+            sorted.set(j - 1, sorted.get_unchecked(j));
+            sorted.set(j, prev);
+            j -= 1;
+        }
+    }
+
+    let mid = n / 2;
+    if n % 2 == 1 {
+        return sorted.get_unchecked(mid);
+    }
+
+    // floor((a + b) / 2) without the intermediate sum overflowing.
+    let a = sorted.get_unchecked(mid - 1);
+    let b = sorted.get_unchecked(mid);
+    (a >> 1) + (b >> 1) + (a & b & 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger},
+        vec, Event as _,
+    };
+
+    fn setup(env: &Env) -> (OracleIntegrationClient<'_>, Vec<Address>) {
+        env.mock_all_auths();
+        let contract_id = env.register(OracleIntegration, ());
+        let client = OracleIntegrationClient::new(env, &contract_id);
+
+        let admin = Address::generate(env);
+        let oracles = vec![
+            env,
+            Address::generate(env),
+            Address::generate(env),
+            Address::generate(env),
+            Address::generate(env),
+        ];
+        client.init(&admin, &oracles);
+
+        (client, oracles)
+    }
+
+    fn id(env: &Env, byte: u8) -> BytesN<32> {
+        BytesN::from_array(env, &[byte; 32])
+    }
+
+    #[test]
+    fn test_median_of_three_is_stored() {
+        let env = Env::default();
+        let (client, oracles) = setup(&env);
+        let caller = Address::generate(&env);
+        let (feed, req) = (id(&env, 1), id(&env, 2));
+
+        client.request_numeric_data(&caller, &feed, &req, &3);
+
+        client.submit_value(&oracles.get(0).unwrap(), &req, &105);
+        client.submit_value(&oracles.get(1).unwrap(), &req, &100);
+        assert_eq!(client.get_request(&req).unwrap().value, None);
+        assert_eq!(client.latest_value(&feed), None);
+
+        client.submit_value(&oracles.get(2).unwrap(), &req, &102);
+
+        let request = client.get_request(&req).unwrap();
+        assert!(request.fulfilled);
+        assert_eq!(request.value, Some(102));
+        assert_eq!(client.latest_value(&feed), Some(102));
+    }
+
+    #[test]
+    fn test_outlier_does_not_skew_median() {
+        let env = Env::default();
+        let (client, oracles) = setup(&env);
+        let caller = Address::generate(&env);
+        let (feed, req) = (id(&env, 1), id(&env, 2));
+
+        client.request_numeric_data(&caller, &feed, &req, &3);
+        client.submit_value(&oracles.get(0).unwrap(), &req, &i128::MAX);
+        client.submit_value(&oracles.get(1).unwrap(), &req, &100);
+        client.submit_value(&oracles.get(2).unwrap(), &req, &101);
+
+        assert_eq!(client.latest_value(&feed), Some(101));
+    }
+
+    #[test]
+    fn test_even_count_averages_middle_values() {
+        let env = Env::default();
+        let (client, oracles) = setup(&env);
+        let caller = Address::generate(&env);
+        let (feed, req) = (id(&env, 1), id(&env, 2));
+
+        client.request_numeric_data(&caller, &feed, &req, &4);
+        client.submit_value(&oracles.get(0).unwrap(), &req, &10);
+        client.submit_value(&oracles.get(1).unwrap(), &req, &40);
+        client.submit_value(&oracles.get(2).unwrap(), &req, &20);
+        client.submit_value(&oracles.get(3).unwrap(), &req, &31);
+
+        assert_eq!(client.latest_value(&feed), Some(25));
+    }
+
+    #[test]
+    fn test_min_responses_cannot_exceed_oracle_count() {
+        let env = Env::default();
+        let (client, _oracles) = setup(&env);
+        let caller = Address::generate(&env);
+
+        assert_eq!(
+            client.try_request_numeric_data(&caller, &id(&env, 1), &id(&env, 2), &5),
+            Err(Ok(Error::InvalidInput))
+        );
+        client.request_numeric_data(&caller, &id(&env, 1), &id(&env, 2), &4);
+    }
+
+    #[test]
+    fn test_median_is_overflow_safe() {
+        let env = Env::default();
+        assert_eq!(median(&vec![&env, i128::MAX, i128::MAX]), i128::MAX);
+        assert_eq!(median(&vec![&env, i128::MIN, i128::MIN]), i128::MIN);
+        assert_eq!(median(&vec![&env, i128::MIN, i128::MAX]), -1);
+        assert_eq!(median(&vec![&env, -3, -4]), -4);
+    }
+
+    #[test]
+    fn test_duplicate_and_late_submissions_rejected() {
+        let env = Env::default();
+        let (client, oracles) = setup(&env);
+        let caller = Address::generate(&env);
+        let req = id(&env, 2);
+
+        client.request_numeric_data(&caller, &id(&env, 1), &req, &2);
+        client.submit_value(&oracles.get(0).unwrap(), &req, &1);
+        assert_eq!(
+            client.try_submit_value(&oracles.get(0).unwrap(), &req, &2),
+            Err(Ok(Error::DuplicateSubmission))
+        );
+
+        client.submit_value(&oracles.get(1).unwrap(), &req, &3);
+        assert_eq!(
+            client.try_submit_value(&oracles.get(2).unwrap(), &req, &4),
+            Err(Ok(Error::AlreadyFulfilled))
+        );
+    }
+
+    #[test]
+    fn test_fallback_returns_fresher_secondary() {
+        let env = Env::default();
+        let (client, oracles) = setup(&env);
+        let caller = Address::generate(&env);
+        let feed = id(&env, 1);
+        let stale = Bytes::from_array(&env, &[1, 1]);
+        let fresh = Bytes::from_array(&env, &[2, 2]);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.request_data(&caller, &feed, &id(&env, 2));
+        client.fulfill_data(&oracles.get(0).unwrap(), &id(&env, 2), &stale, &Bytes::new(&env));
+
+        env.ledger().with_mut(|li| li.timestamp = 1_500);
+        client.request_data(&caller, &feed, &id(&env, 3));
+        client.fulfill_data(&oracles.get(1).unwrap(), &id(&env, 3), &fresh, &Bytes::new(&env));
+        assert_eq!(client.last_fulfilled(&feed), Some(1_500));
+
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+        assert_eq!(client.latest_with_fallback(&feed, &600), Some(fresh));
+    }
+
+    #[test]
+    fn test_fallback_is_none_when_all_sources_stale() {
+        let env = Env::default();
+        let (client, oracles) = setup(&env);
+        let caller = Address::generate(&env);
+        let feed = id(&env, 1);
+        let payload = Bytes::from_array(&env, &[7]);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.request_data(&caller, &feed, &id(&env, 2));
+        client.fulfill_data(&oracles.get(0).unwrap(), &id(&env, 2), &payload, &Bytes::new(&env));
+        client.request_data(&caller, &feed, &id(&env, 3));
+        client.fulfill_data(&oracles.get(1).unwrap(), &id(&env, 3), &payload, &Bytes::new(&env));
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000 + 301);
+        assert_eq!(client.latest_with_fallback(&feed, &300), None);
+        assert_eq!(client.latest_with_fallback(&feed, &301), Some(payload));
+    }
+
+    #[test]
+    fn test_subscriber_notified_on_fulfill() {
+        let env = Env::default();
+        let (client, oracles) = setup(&env);
+        let caller = Address::generate(&env);
+        let subscriber = Address::generate(&env);
+        let (feed, req) = (id(&env, 1), id(&env, 2));
+
+        assert_eq!(
+            client.try_subscribe_feed(&subscriber, &feed),
+            Err(Ok(Error::SubscriberNotAllowed))
+        );
+        client.set_subscriber_allowed(&subscriber, &true);
+        client.subscribe_feed(&subscriber, &feed);
+        assert_eq!(
+            client.try_subscribe_feed(&subscriber, &feed),
+            Err(Ok(Error::AlreadySubscribed))
+        );
+        assert_eq!(client.feed_subscribers(&feed), vec![&env, subscriber.clone()]);
+
+        client.request_data(&caller, &feed, &req);
+        client.fulfill_data(
+            &oracles.get(0).unwrap(),
+            &req,
+            &Bytes::from_array(&env, &[9]),
+            &Bytes::new(&env),
+        );
+
+        let events = env.events().all().filter_by_contract(&client.address);
+        let events = events.events();
+        assert_eq!(
+            events[events.len() - 1],
+            FeedUpdated {
+                feed_id: feed.clone(),
+                subscriber: subscriber.clone(),
+            }
+            .to_xdr(&env, &client.address)
+        );
+
+        client.unsubscribe_feed(&subscriber, &feed);
+        assert_eq!(client.feed_subscribers(&feed).len(), 0);
+        assert_eq!(
+            client.try_unsubscribe_feed(&subscriber, &feed),
+            Err(Ok(Error::NotSubscribed))
+        );
+    }
+
+    #[test]
+    fn test_only_allowlisted_subscribers_take_slots() {
+        let env = Env::default();
+        let (client, _oracles) = setup(&env);
+        let feed = id(&env, 1);
+
+        // Throwaway addresses cannot fill the feed's slots.
+        for _ in 0..MAX_FEED_SUBSCRIBERS {
+            assert_eq!(
+                client.try_subscribe_feed(&Address::generate(&env), &feed),
+                Err(Ok(Error::SubscriberNotAllowed))
+            );
+        }
+        assert_eq!(client.feed_subscribers(&feed).len(), 0);
+
+        let consumer = Address::generate(&env);
+        client.set_subscriber_allowed(&consumer, &true);
+        client.subscribe_feed(&consumer, &feed);
+
+        // Revoking the allowance stops new subscriptions; eviction frees the slot.
+        client.set_subscriber_allowed(&consumer, &false);
+        assert!(!client.is_subscriber_allowed(&consumer));
+        assert_eq!(client.feed_subscribers(&feed).len(), 1);
+        client.evict_subscriber(&consumer, &feed);
+        assert_eq!(client.feed_subscribers(&feed).len(), 0);
+        assert_eq!(
+            client.try_subscribe_feed(&consumer, &feed),
+            Err(Ok(Error::SubscriberNotAllowed))
+        );
+    }
+
+    #[test]
+    fn test_fulfill_emits_data_updated() {
+        let env = Env::default();
+        let (client, oracles) = setup(&env);
+        let caller = Address::generate(&env);
+        let oracle = oracles.get(1).unwrap();
+        let (feed, req) = (id(&env, 1), id(&env, 2));
+
+        env.ledger().set_timestamp(5_000);
+        client.request_data(&caller, &feed, &req);
+        client.fulfill_data(
+            &oracle,
+            &req,
+            &Bytes::from_array(&env, &[1, 2, 3]),
+            &Bytes::new(&env),
+        );
+
+        let events = env.events().all().filter_by_contract(&client.address);
+        let events = events.events();
+        assert_eq!(
+            events[events.len() - 2],
+            RequestFulfilled {
+                request_id: req.clone(),
+                feed_id: feed.clone(),
+            }
+            .to_xdr(&env, &client.address)
+        );
+        assert_eq!(
+            events[events.len() - 1],
+            DataUpdated {
+                feed_id: feed.clone(),
+                oracle,
+                payload_len: 3,
+                fulfilled_at: 5_000,
+            }
+            .to_xdr(&env, &client.address)
+        );
+        assert_eq!(client.last_fulfilled(&feed), Some(5_000));
+    }
+
+    #[test]
+    fn test_feed_subscribers_are_bounded() {
+        let env = Env::default();
+        let (client, _) = setup(&env);
+        let feed = id(&env, 1);
+
+        for _ in 0..MAX_FEED_SUBSCRIBERS {
+            let subscriber = Address::generate(&env);
+            client.set_subscriber_allowed(&subscriber, &true);
+            client.subscribe_feed(&subscriber, &feed);
+        }
+        let extra = Address::generate(&env);
+        client.set_subscriber_allowed(&extra, &true);
+        assert_eq!(
+            client.try_subscribe_feed(&extra, &feed),
+            Err(Ok(Error::TooManySubscribers))
+        );
+    }
+}
@@ -4,6 +4,7 @@ use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
     token, Address, Env, Symbol,
 };
+use stellarcade_shared::check_transition;
 
 // ── Storage Keys ─────────────────────────────────────────────────
 #[contracttype]
@@ -25,12 +26,19 @@ pub enum PenaltyStatus {
     Resolved,
 }
 
+const PENALTY_TRANSITIONS: &[(PenaltyStatus, PenaltyStatus)] = &[
+    (PenaltyStatus::Applied, PenaltyStatus::Appealed),
+    (PenaltyStatus::Appealed, PenaltyStatus::Resolved),
+];
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PenaltyRule {
     pub code: Symbol,
     pub slash_amount: i128,
     pub description_hash: Symbol,
+    /// Inactive codes keep their rule and history but cannot be applied.
+    pub active: bool,
 }
 
 #[contracttype]
@@ -42,6 +50,10 @@ pub struct PenaltyRecord {
     pub slash_amount: i128,
     pub context_hash: Symbol,
     pub status: PenaltyStatus,
+    /// Token the slash was taken in.
+    pub token: Address,
+    /// Portion of `slash_amount` returned to the account on appeal.
+    pub refunded_amount: i128,
 }
 
 // ── Events ────────────────────────────────────────────────────────
@@ -52,6 +64,13 @@ pub struct ViolationDefined {
     pub slash_amount: i128,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ViolationStatusChanged {
+    pub code: Symbol,
+    pub active: bool,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PenaltyApplied {
@@ -68,6 +87,14 @@ pub struct PenaltyAppealed {
     pub account: Address,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PenaltyResolved {
+    pub penalty_id: u64,
+    pub account: Address,
+    pub refunded_amount: i128,
+}
+
 // ── Contract ──────────────────────────────────────────────────────
 #[contract]
 pub struct PenaltySlashing;
@@ -100,6 +127,25 @@ impl PenaltySlashing {
         );
     }
 
+    /// Retire or reinstate a violation code without deleting it. Admin-only.
+    /// Penalties already applied under the code are left untouched.
+    pub fn set_violation_active(env: Env, code: Symbol, active: bool) {
+        Self::require_admin(&env);
+        let key = DataKey::Violation(code.clone());
+        let mut rule: PenaltyRule = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Violation code not defined");
+        rule.active = active;
+        env.storage().persistent().set(&key, &rule);
+
+        env.events().publish(
+            (symbol_short!("vstatus"),),
+            ViolationStatusChanged { code, active },
+        );
+    }
+
     /// Apply a penalty to an account. Admin-only.
     /// Slashes tokens from `account` and transfers them to the treasury.
     pub fn apply_penalty(
@@ -110,12 +156,108 @@ impl PenaltySlashing {
         token_address: Address,
     ) -> u64 {
         Self::require_admin(&env);
+        Self::record_penalty(&env, None, account, code, context_hash, token_address)
+    }
+
+    /// Apply a penalty to an account, taking the slash from `payer` instead.
+    /// Admin-only. `payer` must have approved this contract to spend the
+    /// slash amount, e.g. a contract holding the account's stake in escrow.
+    pub fn apply_penalty_from(
+        env: Env,
+        payer: Address,
+        account: Address,
+        code: Symbol,
+        context_hash: Symbol,
+        token_address: Address,
+    ) -> u64 {
+        Self::require_admin(&env);
+        Self::record_penalty(&env, Some(payer), account, code, context_hash, token_address)
+    }
+
+    /// File an appeal for a penalty. Only the penalized account may appeal.
+    pub fn appeal_penalty(env: Env, penalty_id: u64) {
+        let mut record: PenaltyRecord = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Penalty(penalty_id))
+            .expect("Penalty not found");
+
+        record.account.require_auth();
+
+        if check_transition(&record.status, &PenaltyStatus::Appealed, PENALTY_TRANSITIONS).is_err() {
+            panic!("Can only appeal an applied penalty");
+        }
+
+        record.status = PenaltyStatus::Appealed;
+        env.storage().persistent().set(&DataKey::Penalty(penalty_id), &record);
+
+        env.events().publish(
+            (symbol_short!("appealed"),),
+            PenaltyAppealed { penalty_id, account: record.account },
+        );
+    }
+
+    /// Resolve an appealed penalty with a negotiated partial refund. Admin-only.
+    /// `refund_amount` is returned from the treasury to the account (the
+    /// treasury must authorize the transfer); the rest stays slashed.
+    pub fn resolve_appeal_partial(env: Env, penalty_id: u64, refund_amount: i128) {
+        Self::require_admin(&env);
+
+        let mut record: PenaltyRecord = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Penalty(penalty_id))
+            .expect("Penalty not found");
+
+        if check_transition(&record.status, &PenaltyStatus::Resolved, PENALTY_TRANSITIONS).is_err() {
+            panic!("Can only resolve an appealed penalty");
+        }
+        assert!(
+            refund_amount >= 0 && refund_amount <= record.slash_amount,
+            "Refund must be between zero and the slash amount"
+        );
+
+        record.status = PenaltyStatus::Resolved;
+        record.refunded_amount = refund_amount;
+        env.storage().persistent().set(&DataKey::Penalty(penalty_id), &record);
+
+        if refund_amount > 0 {
+            let treasury: Address = env.storage().instance().get(&DataKey::Treasury).expect("Not initialized");
+            let token_client = token::Client::new(&env, &record.token);
+            token_client.transfer(&treasury, &record.account, &refund_amount);
+        }
+
+        env.events().publish(
+            (symbol_short!("resolved"),),
+            PenaltyResolved { penalty_id, account: record.account, refunded_amount: refund_amount },
+        );
+    }
+
+    /// Read current state of a penalty record.
+    pub fn penalty_state(env: Env, penalty_id: u64) -> PenaltyRecord {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Penalty(penalty_id))
+            .expect("Penalty not found")
+    }
 
+    // ── Internal helpers ──────────────────────────────────────────
+    /// Record a penalty and move its slash to the treasury, from `payer`
+    /// via allowance if given, else directly from `account`.
+    fn record_penalty(
+        env: &Env,
+        payer: Option<Address>,
+        account: Address,
+        code: Symbol,
+        context_hash: Symbol,
+        token_address: Address,
+    ) -> u64 {
         let rule: PenaltyRule = env
             .storage()
             .persistent()
             .get(&DataKey::Violation(code.clone()))
             .expect("Violation code not defined");
+        assert!(rule.active, "Violation code is inactive");
 
         let penalty_id: u64 = env
             .storage()
@@ -126,11 +268,19 @@ impl PenaltySlashing {
             .instance()
             .set(&DataKey::NextPenaltyId, &penalty_id.checked_add(1).expect("Overflow"));
 
-        // Transfer slash amount from account to treasury
+        // Transfer slash amount from account (or its payer) to treasury
         if rule.slash_amount > 0 {
             let treasury: Address = env.storage().instance().get(&DataKey::Treasury).expect("Not initialized");
-            let token_client = token::Client::new(&env, &token_address);
-            token_client.transfer(&account, &treasury, &rule.slash_amount);
+            let token_client = token::Client::new(env, &token_address);
+            match payer {
+                Some(payer) => token_client.transfer_from(
+                    &env.current_contract_address(),
+                    &payer,
+                    &treasury,
+                    &rule.slash_amount,
+                ),
+                None => token_client.transfer(&account, &treasury, &rule.slash_amount),
+            }
         }
 
         let record = PenaltyRecord {
@@ -140,6 +290,8 @@ impl PenaltySlashing {
             slash_amount: rule.slash_amount,
             context_hash,
             status: PenaltyStatus::Applied,
+            token: token_address,
+            refunded_amount: 0,
         };
         env.storage().persistent().set(&DataKey::Penalty(penalty_id), &record);
 
@@ -151,39 +303,6 @@ impl PenaltySlashing {
         penalty_id
     }
 
-    /// File an appeal for a penalty. Only the penalized account may appeal.
-    pub fn appeal_penalty(env: Env, penalty_id: u64) {
-        let mut record: PenaltyRecord = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Penalty(penalty_id))
-            .expect("Penalty not found");
-
-        record.account.require_auth();
-
-        assert!(
-            record.status == PenaltyStatus::Applied,
-            "Can only appeal an applied penalty"
-        );
-
-        record.status = PenaltyStatus::Appealed;
-        env.storage().persistent().set(&DataKey::Penalty(penalty_id), &record);
-
-        env.events().publish(
-            (symbol_short!("appealed"),),
-            PenaltyAppealed { penalty_id, account: record.account },
-        );
-    }
-
-    /// Read current state of a penalty record.
-    pub fn penalty_state(env: Env, penalty_id: u64) -> PenaltyRecord {
-        env.storage()
-            .persistent()
-            .get(&DataKey::Penalty(penalty_id))
-            .expect("Penalty not found")
-    }
-
-    // ── Internal helpers ──────────────────────────────────────────
     fn require_admin(env: &Env) {
         let admin: Address = env
             .storage()
@@ -231,6 +350,7 @@ mod test {
             code: Symbol::new(&env, "CHEAT"),
             slash_amount: 100,
             description_hash: Symbol::new(&env, "DESC1"),
+            active: true,
         };
         client.define_violation(&Symbol::new(&env, "CHEAT"), &rule);
 
@@ -248,6 +368,44 @@ mod test {
         assert_eq!(state.status, PenaltyStatus::Applied);
     }
 
+    #[test]
+    fn test_apply_penalty_from_takes_slash_from_payer() {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let admin = Address::generate(&env);
+        let offender = Address::generate(&env);
+        let escrow = Address::generate(&env);
+        let treasury = Address::generate(&env);
+
+        let (token_id, sa, tc) = setup_token(&env, &admin);
+        sa.mint(&escrow, &300);
+
+        let contract_id = env.register_contract(None, PenaltySlashing);
+        let client = PenaltySlashingClient::new(&env, &contract_id);
+        client.init(&admin, &treasury);
+
+        let code = Symbol::new(&env, "AFK");
+        let rule = PenaltyRule {
+            code: code.clone(),
+            slash_amount: 120,
+            description_hash: Symbol::new(&env, "DSAFK"),
+            active: true,
+        };
+        client.define_violation(&code, &rule);
+
+        tc.approve(&escrow, &contract_id, &120, &env.ledger().sequence());
+        let pid = client.apply_penalty_from(&escrow, &offender, &code, &Symbol::new(&env, "CTX"), &token_id);
+
+        assert_eq!(tc.balance(&escrow), 180);
+        assert_eq!(tc.balance(&treasury), 120);
+        assert_eq!(client.penalty_state(&pid).account, offender);
+
+        // The allowance is spent, so a second slash from the payer fails.
+        let again = client.try_apply_penalty_from(&escrow, &offender, &code, &Symbol::new(&env, "CTX"), &token_id);
+        assert!(again.is_err());
+    }
+
     #[test]
     fn test_appeal_penalty() {
         let env = Env::default();
@@ -268,6 +426,7 @@ mod test {
             code: Symbol::new(&env, "AFK"),
             slash_amount: 50,
             description_hash: Symbol::new(&env, "DSAFK"),
+            active: true,
         };
         client.define_violation(&Symbol::new(&env, "AFK"), &rule);
 
@@ -298,4 +457,144 @@ mod test {
         client.init(&admin, &treasury);
         client.apply_penalty(&offender, &Symbol::new(&env, "BOGUS"), &Symbol::new(&env, "C"), &token);
     }
+
+    #[test]
+    fn test_resolve_appeal_partial_refunds_half() {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let admin = Address::generate(&env);
+        let offender = Address::generate(&env);
+        let treasury = Address::generate(&env);
+
+        let (token_id, sa, tc) = setup_token(&env, &admin);
+        sa.mint(&offender, &1000);
+
+        let contract_id = env.register_contract(None, PenaltySlashing);
+        let client = PenaltySlashingClient::new(&env, &contract_id);
+        client.init(&admin, &treasury);
+
+        let rule = PenaltyRule {
+            code: Symbol::new(&env, "CHEAT"),
+            slash_amount: 200,
+            description_hash: Symbol::new(&env, "DESC1"),
+            active: true,
+        };
+        client.define_violation(&Symbol::new(&env, "CHEAT"), &rule);
+        let pid = client.apply_penalty(
+            &offender,
+            &Symbol::new(&env, "CHEAT"),
+            &Symbol::new(&env, "CTX1"),
+            &token_id,
+        );
+        client.appeal_penalty(&pid);
+
+        client.resolve_appeal_partial(&pid, &100);
+
+        assert_eq!(tc.balance(&offender), 900);
+        assert_eq!(tc.balance(&treasury), 100);
+        let state = client.penalty_state(&pid);
+        assert_eq!(state.status, PenaltyStatus::Resolved);
+        assert_eq!(state.refunded_amount, 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "Refund must be between zero and the slash amount")]
+    fn test_resolve_appeal_partial_rejects_excess_refund() {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let admin = Address::generate(&env);
+        let offender = Address::generate(&env);
+        let treasury = Address::generate(&env);
+
+        let (token_id, sa, _) = setup_token(&env, &admin);
+        sa.mint(&offender, &500);
+
+        let contract_id = env.register_contract(None, PenaltySlashing);
+        let client = PenaltySlashingClient::new(&env, &contract_id);
+        client.init(&admin, &treasury);
+
+        let rule = PenaltyRule {
+            code: Symbol::new(&env, "AFK"),
+            slash_amount: 50,
+            description_hash: Symbol::new(&env, "DSAFK"),
+            active: true,
+        };
+        client.define_violation(&Symbol::new(&env, "AFK"), &rule);
+        let pid = client.apply_penalty(
+            &offender,
+            &Symbol::new(&env, "AFK"),
+            &Symbol::new(&env, "CTX2"),
+            &token_id,
+        );
+        client.appeal_penalty(&pid);
+
+        client.resolve_appeal_partial(&pid, &51);
+    }
+
+    #[test]
+    fn test_deactivated_violation_can_be_reactivated() {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let admin = Address::generate(&env);
+        let offender = Address::generate(&env);
+        let treasury = Address::generate(&env);
+
+        let (token_id, sa, tc) = setup_token(&env, &admin);
+        sa.mint(&offender, &1000);
+
+        let contract_id = env.register_contract(None, PenaltySlashing);
+        let client = PenaltySlashingClient::new(&env, &contract_id);
+        client.init(&admin, &treasury);
+
+        let code = Symbol::new(&env, "CHEAT");
+        let rule = PenaltyRule {
+            code: code.clone(),
+            slash_amount: 100,
+            description_hash: Symbol::new(&env, "DESC1"),
+            active: true,
+        };
+        client.define_violation(&code, &rule);
+        let first = client.apply_penalty(&offender, &code, &Symbol::new(&env, "CTX1"), &token_id);
+
+        client.set_violation_active(&code, &false);
+        let result = client.try_apply_penalty(&offender, &code, &Symbol::new(&env, "CTX2"), &token_id);
+        assert!(result.is_err());
+        assert_eq!(tc.balance(&offender), 900);
+
+        // History recorded under the code is kept
+        assert_eq!(client.penalty_state(&first).code, code);
+
+        client.set_violation_active(&code, &true);
+        client.apply_penalty(&offender, &code, &Symbol::new(&env, "CTX3"), &token_id);
+        assert_eq!(tc.balance(&offender), 800);
+        assert_eq!(tc.balance(&treasury), 200);
+    }
+
+    #[test]
+    #[should_panic(expected = "Violation code is inactive")]
+    fn test_apply_inactive_violation_fails() {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+        let admin = Address::generate(&env);
+        let offender = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let token = Address::generate(&env);
+
+        let contract_id = env.register_contract(None, PenaltySlashing);
+        let client = PenaltySlashingClient::new(&env, &contract_id);
+        client.init(&admin, &treasury);
+
+        let code = Symbol::new(&env, "AFK");
+        let rule = PenaltyRule {
+            code: code.clone(),
+            slash_amount: 50,
+            description_hash: Symbol::new(&env, "DSAFK"),
+            active: false,
+        };
+        client.define_violation(&code, &rule);
+        client.apply_penalty(&offender, &code, &Symbol::new(&env, "C"), &token);
+    }
 }
@@ -5,24 +5,30 @@
 //!
 //! 1. An authorized game contract calls `request_random`, registering a
 //!    pending request with a caller address and an upper bound (`max`).
+//!    The ledger sequence, timestamp and the digest of the previous
+//!    fulfillment are recorded with the request as on-chain entropy.
 //! 2. The designated oracle calls `fulfill_random` with a `server_seed`.
 //!    The result is computed deterministically as:
 //!
-//!      `sha256(server_seed || request_id_be_bytes)[0..8] % max`
+//!      `sha256(server_seed || request_id_be || ledger_sequence_be
+//!              || timestamp_be || prev_digest)[0..8] % max`
 //!
-//!    and stored on-chain alongside the server seed so anyone can verify.
+//!    and stored on-chain alongside the server seed and entropy so anyone
+//!    can verify.
 //!
 //! ## Fairness Model
 //! The oracle must publish `sha256(server_seed)` **before** a game round
 //! begins (off-chain commitment). Once a request is submitted, the server
 //! seed is fixed — the oracle cannot choose a seed after seeing the
 //! request without breaking the pre-published commitment. After fulfillment,
-//! any party can re-derive and verify the result using the stored seed:
-//!
-//!   `sha256(stored_server_seed || request_id_be)[0..8] % max == stored_result`
+//! any party can re-derive and verify the result using the stored seed and
+//! entropy. Because the entropy is fixed by the ledger the request lands in,
+//! neither the oracle (committed to its seed in advance) nor the caller
+//! (who cannot see the seed) controls the outcome alone.
 //!
 //! ## Storage Strategy
-//! - `instance()`: Admin, Oracle. Fixed contract-level config.
+//! - `instance()`: Admin, Oracle, LastDigest. Contract-level config and the
+//!   digest chained into the next request's entropy.
 //! - `persistent()`: AuthorizedCaller entries, PendingRequest entries,
 //!   FulfilledRequest entries — each a separate ledger entry with TTL
 //!   bumped on every write so active requests never expire mid-game.
@@ -77,6 +83,8 @@ pub enum DataKey {
     // --- instance() ---
     Admin,
     Oracle,
+    /// SHA-256 digest computed by the most recent fulfillment.
+    LastDigest,
     // --- persistent() ---
     /// Presence flag for whitelisted game contract addresses.
     AuthorizedCaller(Address),
@@ -86,12 +94,27 @@ pub enum DataKey {
     FulfilledRequest(u64),
 }
 
+/// On-chain entropy captured when a request is registered and mixed into
+/// its result alongside the oracle's seed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entropy {
+    /// Ledger sequence in which `request_random` was called.
+    pub ledger_sequence: u32,
+    /// Ledger timestamp at which `request_random` was called.
+    pub timestamp: u64,
+    /// Digest of the fulfillment preceding the request; all zeroes before
+    /// the first fulfillment.
+    pub prev_digest: BytesN<32>,
+}
+
 /// A pending randomness request registered by an authorized game contract.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PendingEntry {
     pub caller: Address,
     pub max: u64,
+    pub entropy: Entropy,
 }
 
 /// A fulfilled request with its deterministic result and the oracle seed.
@@ -105,7 +128,9 @@ pub struct FulfilledEntry {
     pub max: u64,
     /// Oracle-provided seed; stored to allow on-chain result verification.
     pub server_seed: BytesN<32>,
-    /// `sha256(server_seed || request_id_be)[0..8] % max`; always in `[0, max)`.
+    /// Ledger data recorded at request time; stored for verification.
+    pub entropy: Entropy,
+    /// Derived from `entropy_digest`; always in `[0, max)`.
     pub result: u64,
 }
 
@@ -201,6 +226,9 @@ impl RandomGenerator {
     /// `max` must be >= 2. The fulfilled result will be in `[0, max - 1]`.
     /// `request_id` must be globally unique — rejected if a pending or
     /// fulfilled entry for the same ID already exists.
+    ///
+    /// The current ledger sequence, timestamp and the digest of the last
+    /// fulfillment are recorded with the request and mixed into its result.
     pub fn request_random(
         env: Env,
         caller: Address,
@@ -234,7 +262,16 @@ impl RandomGenerator {
             return Err(Error::DuplicateRequestId);
         }
 
-        let entry = PendingEntry { caller: caller.clone(), max };
+        let entropy = Entropy {
+            ledger_sequence: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+            prev_digest: env
+                .storage()
+                .instance()
+                .get(&DataKey::LastDigest)
+                .unwrap_or_else(|| BytesN::from_array(&env, &[0u8; 32])),
+        };
+        let entry = PendingEntry { caller: caller.clone(), max, entropy };
         let key = DataKey::PendingRequest(request_id);
         env.storage().persistent().set(&key, &entry);
         env.storage()
@@ -252,10 +289,11 @@ impl RandomGenerator {
 
     /// Fulfill a pending randomness request. Oracle only.
     ///
-    /// The result is derived as:
-    ///   `sha256(server_seed || request_id_be_bytes)[0..8] % max`
+    /// The result is derived from `server_seed`, `request_id` and the entropy
+    /// recorded at request time (see `entropy_digest`).
     ///
-    /// Both `server_seed` and `result` are persisted for on-chain verification.
+    /// `server_seed`, the entropy and `result` are persisted for on-chain
+    /// verification. The digest becomes the `prev_digest` of later requests.
     /// Fairness holds when the oracle published `sha256(server_seed)` before
     /// the corresponding `request_random` call was submitted.
     pub fn fulfill_random(
@@ -283,7 +321,9 @@ impl RandomGenerator {
             .get(&pending_key)
             .ok_or(Error::RequestNotFound)?;
 
-        let result = derive_result(&env, &server_seed, request_id, pending.max);
+        let digest = entropy_digest(&env, &server_seed, request_id, &pending.entropy);
+        let result = digest_to_result(&digest, pending.max);
+        env.storage().instance().set(&DataKey::LastDigest, &digest);
 
         // Remove the pending entry; write the fulfilled entry.
         env.storage().persistent().remove(&pending_key);
@@ -292,6 +332,7 @@ impl RandomGenerator {
             caller: pending.caller,
             max: pending.max,
             server_seed: server_seed.clone(),
+            entropy: pending.entropy,
             result,
         };
         let fulfilled_key = DataKey::FulfilledRequest(request_id);
@@ -305,6 +346,24 @@ impl RandomGenerator {
         Ok(())
     }
 
+    // -----------------------------------------------------------------------
+    // pending_entropy
+    // -----------------------------------------------------------------------
+
+    /// Return the entropy recorded for a pending request, so the oracle and
+    /// verifiers can compute its result ahead of fulfillment.
+    ///
+    /// Returns `RequestNotFound` if no pending request exists for `request_id`.
+    pub fn pending_entropy(env: Env, request_id: u64) -> Result<Entropy, Error> {
+        require_initialized(&env)?;
+
+        env.storage()
+            .persistent()
+            .get::<_, PendingEntry>(&DataKey::PendingRequest(request_id))
+            .map(|entry| entry.entropy)
+            .ok_or(Error::RequestNotFound)
+    }
+
     // -----------------------------------------------------------------------
     // get_result
     // -----------------------------------------------------------------------
@@ -359,22 +418,39 @@ fn require_oracle(env: &Env, caller: &Address) -> Result<(), Error> {
     Ok(())
 }
 
-/// Derive a bounded random result from `server_seed` and `request_id`.
+/// Hash `server_seed`, `request_id` and the request's on-chain entropy.
 ///
-/// Constructs a 40-byte preimage:  server_seed (32 bytes) || request_id (8 bytes BE)
-/// Takes SHA-256, interprets the first 8 bytes as a big-endian u64, and reduces
-/// modulo `max`. Produces a value in `[0, max - 1]`.
+/// Constructs an 84-byte preimage:
+///   server_seed (32) || request_id (8 BE) || ledger_sequence (4 BE)
+///   || timestamp (8 BE) || prev_digest (32)
+/// and returns its SHA-256 digest.
 ///
 /// Combining `server_seed` with `request_id` in the preimage ensures that
 /// different requests fulfilled with the same seed produce different outputs,
 /// preventing the oracle from reusing a single seed commitment across rounds.
-fn derive_result(env: &Env, server_seed: &BytesN<32>, request_id: u64, max: u64) -> u64 {
+/// The ledger fields and `prev_digest` are fixed when the request lands, after
+/// the oracle has committed to its seed, so the oracle cannot pick a seed for
+/// a known outcome and the caller cannot predict one without the seed.
+fn entropy_digest(
+    env: &Env,
+    server_seed: &BytesN<32>,
+    request_id: u64,
+    entropy: &Entropy,
+) -> BytesN<32> {
     // Build preimage on the stack to avoid heap allocation.
-    let mut preimage = [0u8; 40];
+    let mut preimage = [0u8; 84];
     preimage[..32].copy_from_slice(&server_seed.to_array());
-    preimage[32..].copy_from_slice(&request_id.to_be_bytes());
+    preimage[32..40].copy_from_slice(&request_id.to_be_bytes());
+    preimage[40..44].copy_from_slice(&entropy.ledger_sequence.to_be_bytes());
+    preimage[44..52].copy_from_slice(&entropy.timestamp.to_be_bytes());
+    preimage[52..].copy_from_slice(&entropy.prev_digest.to_array());
 
-    let digest: BytesN<32> = env.crypto().sha256(&Bytes::from_slice(env, &preimage)).into();
+    env.crypto().sha256(&Bytes::from_slice(env, &preimage)).into()
+}
+
+/// Interpret the first 8 bytes of `digest` as a big-endian u64 and reduce
+/// modulo `max`. Produces a value in `[0, max - 1]`.
+fn digest_to_result(digest: &BytesN<32>, max: u64) -> u64 {
     let arr = digest.to_array();
     let raw = u64::from_be_bytes([arr[0], arr[1], arr[2], arr[3], arr[4], arr[5], arr[6], arr[7]]);
     raw % max
@@ -387,7 +463,10 @@ fn derive_result(env: &Env, server_seed: &BytesN<32>, request_id: u64, max: u64)
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Bytes, BytesN, Env};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Bytes, BytesN, Env,
+    };
 
     // ------------------------------------------------------------------
     // Test helpers
@@ -409,12 +488,22 @@ mod test {
         (client, admin, oracle, game)
     }
 
-    /// Re-derive the expected result using the same logic as `derive_result`,
-    /// so the test is an independent cross-check of the on-chain computation.
-    fn expected_result(env: &Env, server_seed: &BytesN<32>, request_id: u64, max: u64) -> u64 {
-        let mut preimage = [0u8; 40];
+    /// Re-derive the expected result using the same logic as `entropy_digest`
+    /// and `digest_to_result`, so the test is an independent cross-check of
+    /// the on-chain computation.
+    fn expected_result(
+        env: &Env,
+        server_seed: &BytesN<32>,
+        request_id: u64,
+        entropy: &Entropy,
+        max: u64,
+    ) -> u64 {
+        let mut preimage = [0u8; 84];
         preimage[..32].copy_from_slice(&server_seed.to_array());
-        preimage[32..].copy_from_slice(&request_id.to_be_bytes());
+        preimage[32..40].copy_from_slice(&request_id.to_be_bytes());
+        preimage[40..44].copy_from_slice(&entropy.ledger_sequence.to_be_bytes());
+        preimage[44..52].copy_from_slice(&entropy.timestamp.to_be_bytes());
+        preimage[52..].copy_from_slice(&entropy.prev_digest.to_array());
         let digest: BytesN<32> =
             env.crypto().sha256(&Bytes::from_slice(env, &preimage)).into();
         let arr = digest.to_array();
@@ -465,7 +554,7 @@ mod test {
         client.fulfill_random(&oracle, &request_id, &server_seed);
 
         let entry = client.get_result(&request_id);
-        let expected = expected_result(&env, &server_seed, request_id, max);
+        let expected = expected_result(&env, &server_seed, request_id, &entry.entropy, max);
 
         assert_eq!(entry.result, expected);
         assert_eq!(entry.max, max);
@@ -710,7 +799,72 @@ mod test {
 
         assert!(entry_a.result < max_a);
         assert!(entry_b.result < max_b);
-        assert_eq!(entry_a.result, expected_result(&env, &seed_a, 10, max_a));
-        assert_eq!(entry_b.result, expected_result(&env, &seed_b, 20, max_b));
+        assert_eq!(
+            entry_a.result,
+            expected_result(&env, &seed_a, 10, &entry_a.entropy, max_a)
+        );
+        assert_eq!(
+            entry_b.result,
+            expected_result(&env, &seed_b, 20, &entry_b.entropy, max_b)
+        );
+    }
+
+    // ------------------------------------------------------------------
+    // 16. Request records ledger entropy and chains the previous digest
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_request_records_ledger_entropy() {
+        let env = Env::default();
+        let (client, _, oracle, game) = setup(&env);
+        env.mock_all_auths();
+
+        env.ledger().with_mut(|li| {
+            li.sequence_number = 100;
+            li.timestamp = 5_000;
+        });
+        client.request_random(&game, &1u64, &6u64);
+        let first = client.pending_entropy(&1u64);
+        assert_eq!(first.ledger_sequence, 100);
+        assert_eq!(first.timestamp, 5_000);
+        assert_eq!(first.prev_digest, BytesN::from_array(&env, &[0u8; 32]));
+
+        client.fulfill_random(&oracle, &1u64, &seed(&env, 4));
+        assert_eq!(client.get_result(&1u64).entropy, first);
+        assert!(client.try_pending_entropy(&1u64).is_err());
+
+        client.request_random(&game, &2u64, &6u64);
+        let second = client.pending_entropy(&2u64);
+        assert_ne!(second.prev_digest, first.prev_digest);
+    }
+
+    // ------------------------------------------------------------------
+    // 17. Same seed and request_id on different ledgers gives different results
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_same_seed_different_ledgers_differ() {
+        let env = Env::default();
+        let (client_a, _, oracle_a, game_a) = setup(&env);
+        let (client_b, _, oracle_b, game_b) = setup(&env);
+        env.mock_all_auths();
+
+        let max = 1_000_000u64;
+        let s = seed(&env, 0x5A);
+
+        env.ledger().with_mut(|li| li.sequence_number = 100);
+        client_a.request_random(&game_a, &1u64, &max);
+        env.ledger().with_mut(|li| li.sequence_number = 101);
+        client_b.request_random(&game_b, &1u64, &max);
+
+        client_a.fulfill_random(&oracle_a, &1u64, &s);
+        client_b.fulfill_random(&oracle_b, &1u64, &s);
+
+        let entry_a = client_a.get_result(&1u64);
+        let entry_b = client_b.get_result(&1u64);
+        assert_eq!(entry_a.server_seed, entry_b.server_seed);
+        assert_ne!(entry_a.result, entry_b.result);
+        assert_eq!(entry_a.result, expected_result(&env, &s, 1, &entry_a.entropy, max));
+        assert_eq!(entry_b.result, expected_result(&env, &s, 1, &entry_b.entropy, max));
     }
 }
@@ -14,6 +14,7 @@ pub enum DataKey {
     SplitConfig(Symbol),    // stream_id → SplitConfig
     StreamBalance(Symbol),  // stream_id → i128 (total deposited, not yet distributed)
     RecipientBalance(Symbol, Address), // (stream_id, recipient) → i128
+    DepositorTotal(Symbol, Address),   // (stream_id, depositor) → i128 (cumulative deposits)
 }
 
 // ── Domain Types ─────────────────────────────────────────────────
@@ -53,6 +54,14 @@ pub struct RevenueDistributed {
     pub total: i128,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecipientRotated {
+    pub stream_id: Symbol,
+    pub old_recipient: Address,
+    pub new_recipient: Address,
+}
+
 // ── Contract ──────────────────────────────────────────────────────
 #[contract]
 pub struct RevenueSplit;
@@ -118,6 +127,13 @@ impl RevenueSplit {
             .persistent()
             .set(&DataKey::StreamBalance(stream_id.clone()), &(current.checked_add(amount).expect("Overflow")));
 
+        // Reporting only; distribution never reads this.
+        let total_key = DataKey::DepositorTotal(stream_id.clone(), depositor);
+        let deposited: i128 = env.storage().persistent().get(&total_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&total_key, &(deposited.checked_add(amount).expect("Overflow")));
+
         env.events().publish(
             (symbol_short!("deposit"),),
             RevenueDeposited { stream_id, amount },
@@ -176,6 +192,48 @@ impl RevenueSplit {
         );
     }
 
+    /// Replace `old` with `new` in a stream's split, keeping its weight.
+    /// Admin-only. Shares are paid out on `distribute`, so each address
+    /// keeps the `RecipientBalance` it was actually paid; only future
+    /// distributions go to `new`.
+    pub fn rotate_recipient(env: Env, stream_id: Symbol, old: Address, new: Address) {
+        Self::require_admin(&env);
+
+        let config_key = DataKey::SplitConfig(stream_id.clone());
+        let mut config: SplitConfig = env
+            .storage()
+            .persistent()
+            .get(&config_key)
+            .expect("Split config not found");
+
+        let mut position: Option<u32> = None;
+        for (i, r) in config.recipients.iter().enumerate() {
+            assert!(r.recipient != new, "New recipient already in stream");
+            if r.recipient == old {
+                position = Some(i as u32);
+            }
+        }
+        let index = position.expect("Recipient not found in stream");
+
+        let mut entry = config.recipients.get(index).unwrap();
+        entry.recipient = new.clone();
+        config.recipients.set(index, entry);
+        env.storage().persistent().set(&config_key, &config);
+
+        env.events().publish(
+            (symbol_short!("rotated"),),
+            RecipientRotated { stream_id, old_recipient: old, new_recipient: new },
+        );
+    }
+
+    /// Read a stream's split configuration.
+    pub fn split_config(env: Env, stream_id: Symbol) -> SplitConfig {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SplitConfig(stream_id))
+            .expect("Split config not found")
+    }
+
     /// Query cumulative amount distributed to a recipient for a stream.
     pub fn recipient_balance(env: Env, stream_id: Symbol, recipient: Address) -> i128 {
         env.storage()
@@ -184,6 +242,14 @@ impl RevenueSplit {
             .unwrap_or(0)
     }
 
+    /// Query cumulative amount a depositor has paid into a stream.
+    pub fn depositor_total(env: Env, stream_id: Symbol, depositor: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::DepositorTotal(stream_id, depositor))
+            .unwrap_or(0)
+    }
+
     // ── Internal ─────────────────────────────────────────────────
     fn require_admin(env: &Env) {
         let admin: Address = env
@@ -248,6 +314,47 @@ mod test {
         assert_eq!(client.recipient_balance(&stream, &r2), 400);
     }
 
+    #[test]
+    fn test_depositor_totals_tracked_per_stream() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let r1 = Address::generate(&env);
+        let d1 = Address::generate(&env);
+        let d2 = Address::generate(&env);
+
+        let (token_id, sa, tc) = setup_token(&env, &admin);
+        sa.mint(&d1, &1000);
+        sa.mint(&d2, &1000);
+
+        let contract_id = env.register_contract(None, RevenueSplit);
+        let client = RevenueSplitClient::new(&env, &contract_id);
+        client.init(&admin, &token_id);
+
+        let stream = Symbol::new(&env, "gaming");
+        let other = Symbol::new(&env, "events");
+        let recipients = vec![&env, RecipientWeight { recipient: r1.clone(), weight_bps: 10000 }];
+        client.set_split_config(&stream, &recipients);
+        client.set_split_config(&other, &recipients);
+
+        client.deposit_revenue(&d1, &stream, &300);
+        client.deposit_revenue(&d2, &stream, &150);
+        client.deposit_revenue(&d1, &stream, &50);
+        client.deposit_revenue(&d2, &other, &80);
+
+        assert_eq!(client.depositor_total(&stream, &d1), 350);
+        assert_eq!(client.depositor_total(&stream, &d2), 150);
+        assert_eq!(client.depositor_total(&other, &d1), 0);
+        assert_eq!(client.depositor_total(&other, &d2), 80);
+
+        // Distribution pays out the pooled balance and leaves totals as-is.
+        client.distribute(&stream);
+        assert_eq!(tc.balance(&r1), 500);
+        assert_eq!(client.depositor_total(&stream, &d1), 350);
+        assert_eq!(client.depositor_total(&stream, &d2), 150);
+    }
+
     #[test]
     #[should_panic(expected = "Weights must sum to 10000 BPS")]
     fn test_invalid_weight_sum_fails() {
@@ -302,4 +409,80 @@ mod test {
         client.init(&admin, &token);
         client.init(&admin, &token);
     }
+
+    #[test]
+    fn test_rotate_recipient_keeps_paid_history() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let r1 = Address::generate(&env);
+        let r2 = Address::generate(&env);
+        let r1_new = Address::generate(&env);
+        let depositor = Address::generate(&env);
+
+        let (token_id, sa, tc) = setup_token(&env, &admin);
+        sa.mint(&depositor, &2000);
+
+        let contract_id = env.register_contract(None, RevenueSplit);
+        let client = RevenueSplitClient::new(&env, &contract_id);
+        client.init(&admin, &token_id);
+
+        let stream = Symbol::new(&env, "gaming");
+        let recipients = vec![
+            &env,
+            RecipientWeight { recipient: r1.clone(), weight_bps: 6000 },
+            RecipientWeight { recipient: r2.clone(), weight_bps: 4000 },
+        ];
+        client.set_split_config(&stream, &recipients);
+
+        client.deposit_revenue(&depositor, &stream, &1000);
+        client.distribute(&stream);
+        assert_eq!(client.recipient_balance(&stream, &r1), 600);
+
+        client.rotate_recipient(&stream, &r1, &r1_new);
+
+        // What r1 was paid stays recorded against r1.
+        assert_eq!(client.recipient_balance(&stream, &r1), 600);
+        assert_eq!(client.recipient_balance(&stream, &r1_new), 0);
+        let config = client.split_config(&stream);
+        assert_eq!(
+            config.recipients.get(0).unwrap(),
+            RecipientWeight { recipient: r1_new.clone(), weight_bps: 6000 }
+        );
+
+        // Later distributions pay the new address only.
+        client.deposit_revenue(&depositor, &stream, &1000);
+        client.distribute(&stream);
+        assert_eq!(tc.balance(&r1), 600);
+        assert_eq!(tc.balance(&r1_new), 600);
+        assert_eq!(client.recipient_balance(&stream, &r1), 600);
+        assert_eq!(client.recipient_balance(&stream, &r1_new), 600);
+        assert_eq!(client.recipient_balance(&stream, &r2), 800);
+    }
+
+    #[test]
+    #[should_panic(expected = "New recipient already in stream")]
+    fn test_rotate_to_existing_recipient_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let r1 = Address::generate(&env);
+        let r2 = Address::generate(&env);
+        let token = Address::generate(&env);
+
+        let contract_id = env.register_contract(None, RevenueSplit);
+        let client = RevenueSplitClient::new(&env, &contract_id);
+        client.init(&admin, &token);
+
+        let stream = Symbol::new(&env, "gaming");
+        let recipients = vec![
+            &env,
+            RecipientWeight { recipient: r1.clone(), weight_bps: 5000 },
+            RecipientWeight { recipient: r2.clone(), weight_bps: 5000 },
+        ];
+        client.set_split_config(&stream, &recipients);
+        client.rotate_recipient(&stream, &r1, &r2);
+    }
 }
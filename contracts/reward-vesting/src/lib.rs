@@ -6,8 +6,8 @@
 //! schedule. Rewards may be revoked by the admin before full vesting.
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, token, Address, Env, Map, String, Symbol,
-    Vec,
+    contract, contractevent, contractimpl, contracttype, symbol_short, token, Address, Env, Map,
+    Symbol, Vec,
 };
 
 // ─── Types ────────────────────────────────────────────────────────────────────
@@ -21,10 +21,32 @@ pub struct VestingSchedule {
     pub start_timestamp: u64,
     pub cliff_seconds: u64,
     pub duration_seconds: u64,
+    /// Share of `amount`, in basis points, unlocked at once at the cliff.
+    /// 0 keeps plain linear vesting from the start.
+    pub cliff_unlock_bps: u32,
+    /// Hard end date: everything counts as vested from this timestamp on,
+    /// even if `start_timestamp + duration_seconds` is later.
+    pub vest_end_override: Option<u64>,
     pub claimed: i128,
     pub revoked: bool,
 }
 
+/// Aggregate view of all of a user's schedules, for front-ends.
+///
+/// Revoked schedules contribute only what was claimed before revocation.
+/// `next_unlock_ts` is the earliest time after now at which more tokens vest:
+/// the nearest pending cliff, or the next second while any schedule is in its
+/// linear phase. `None` once everything has vested.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingSummary {
+    pub total_granted: i128,
+    pub total_vested: i128,
+    pub total_claimed: i128,
+    pub claimable_now: i128,
+    pub next_unlock_ts: Option<u64>,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
@@ -33,14 +55,94 @@ pub enum DataKey {
     NextScheduleId,
     ScheduleMap,
     UserSchedules(Address),
+    /// Tokens still owed across unrevoked schedules (granted minus claimed).
+    TotalLocked,
+    /// Smallest total a `claim_vested` call may transfer; 0 disables.
+    MinClaim,
+    /// Address allowed to trigger claims on a user's behalf.
+    Guardian(Address),
 }
 
 // ─── Events ───────────────────────────────────────────────────────────────────
 
 const EVT_INIT: Symbol = symbol_short!("init");
-const EVT_SCHEDULED: Symbol = symbol_short!("scheduled");
-const EVT_CLAIMED: Symbol = symbol_short!("claimed");
-const EVT_REVOKED: Symbol = symbol_short!("revoked");
+
+/// A new vesting schedule was created. `claimed_total` is always 0 and
+/// `remaining` equals `amount`.
+#[contractevent]
+pub struct Scheduled {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub schedule_id: u64,
+    pub amount: i128,
+    pub claimed_total: i128,
+    pub remaining: i128,
+}
+
+/// Tokens were claimed from a single schedule. `amount` is the portion
+/// released by this claim, `claimed_total` the schedule's cumulative claimed
+/// amount and `remaining` what is still locked in the schedule.
+#[contractevent]
+pub struct Claimed {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub schedule_id: u64,
+    pub amount: i128,
+    pub claimed_total: i128,
+    pub remaining: i128,
+}
+
+/// Aggregate of a single `claim_vested` call across all of the user's
+/// schedules. Emitted after the per-schedule `Claimed` events.
+#[contractevent]
+pub struct ClaimSummary {
+    #[topic]
+    pub user: Address,
+    pub total_amount: i128,
+    pub schedules_claimed: u32,
+}
+
+/// A schedule was revoked. `amount` is the unvested portion returned to the
+/// admin; nothing remains claimable afterwards.
+#[contractevent]
+pub struct Revoked {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub schedule_id: u64,
+    pub amount: i128,
+    pub claimed_total: i128,
+    pub remaining: i128,
+}
+
+/// An existing schedule's grant was increased. `amount` is the tokens
+/// added and `remaining` the schedule's new locked total.
+#[contractevent]
+pub struct ToppedUp {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub schedule_id: u64,
+    pub amount: i128,
+    pub claimed_total: i128,
+    pub remaining: i128,
+}
+
+/// A user set or cleared (`guardian: None`) their claim guardian.
+#[contractevent]
+pub struct GuardianSet {
+    #[topic]
+    pub user: Address,
+    pub guardian: Option<Address>,
+}
+
+/// The admin changed the minimum claim amount.
+#[contractevent]
+pub struct MinClaimSet {
+    pub min_claim: i128,
+}
 
 // ─── Contract ─────────────────────────────────────────────────────────────────
 
@@ -77,72 +179,109 @@ impl RewardVestingContract {
         cliff_seconds: u64,
         duration_seconds: u64,
     ) -> u64 {
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .expect("Not initialized");
-        admin.require_auth();
-
-        if amount <= 0 {
-            panic!("Invalid amount: must be positive");
-        }
-        if duration_seconds == 0 {
-            panic!("Invalid duration: must be positive");
-        }
-
-        // Transfer tokens from admin into the contract.
-        let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
-        let token = token::Client::new(&env, &token_addr);
-        token.transfer(&admin, &env.current_contract_address(), &amount);
-
-        let schedule_id: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::NextScheduleId)
-            .unwrap_or(0);
-        env.storage()
-            .instance()
-            .set(&DataKey::NextScheduleId, &(schedule_id + 1));
-
-        let schedule = VestingSchedule {
-            schedule_id,
-            user: user.clone(),
+        Self::create_cliff_unlock_schedule(
+            env,
+            user,
             amount,
             start_timestamp,
             cliff_seconds,
             duration_seconds,
-            claimed: 0,
-            revoked: false,
-        };
-
-        let mut map: Map<u64, VestingSchedule> = env
-            .storage()
-            .instance()
-            .get(&DataKey::ScheduleMap)
-            .unwrap_or(Map::new(&env));
-        map.set(schedule_id, schedule);
-        env.storage().instance().set(&DataKey::ScheduleMap, &map);
+            0,
+        )
+    }
 
-        // Track by user.
-        let user_key = DataKey::UserSchedules(user.clone());
-        let mut ids: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&user_key)
-            .unwrap_or(Vec::new(&env));
-        ids.push_back(schedule_id);
-        env.storage().persistent().set(&user_key, &ids);
+    /// Create a vesting schedule that unlocks a lump sum at the cliff.
+    ///
+    /// At the cliff `cliff_unlock_bps / 10000` of `amount` becomes claimable
+    /// at once; the remainder vests linearly from the cliff to
+    /// `start_timestamp + duration_seconds`. With `cliff_unlock_bps == 0`
+    /// this is the same as `create_vesting_schedule`.
+    pub fn create_cliff_unlock_schedule(
+        env: Env,
+        user: Address,
+        amount: i128,
+        start_timestamp: u64,
+        cliff_seconds: u64,
+        duration_seconds: u64,
+        cliff_unlock_bps: u32,
+    ) -> u64 {
+        Self::create_schedule(
+            env,
+            user,
+            amount,
+            start_timestamp,
+            cliff_seconds,
+            duration_seconds,
+            cliff_unlock_bps,
+            None,
+        )
+    }
 
-        env.events()
-            .publish((EVT_SCHEDULED,), (user, schedule_id, amount));
-        schedule_id
+    /// Create a vesting schedule that must be fully vested by a fixed date.
+    ///
+    /// Vests like `create_cliff_unlock_schedule`, except that the whole
+    /// `amount` counts as vested once `now >= vest_end_override`, even if
+    /// `start_timestamp + duration_seconds` is later. The override must be
+    /// after the cliff.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_schedule_with_end_date(
+        env: Env,
+        user: Address,
+        amount: i128,
+        start_timestamp: u64,
+        cliff_seconds: u64,
+        duration_seconds: u64,
+        cliff_unlock_bps: u32,
+        vest_end_override: u64,
+    ) -> u64 {
+        Self::create_schedule(
+            env,
+            user,
+            amount,
+            start_timestamp,
+            cliff_seconds,
+            duration_seconds,
+            cliff_unlock_bps,
+            Some(vest_end_override),
+        )
     }
 
     /// Claim all currently vested tokens for `user`. Returns amount transferred.
     pub fn claim_vested(env: Env, user: Address) -> i128 {
         user.require_auth();
+        Self::release_vested(env, user)
+    }
+
+    /// Claim on behalf of `user`. `caller` must be the user or their
+    /// guardian; the tokens always go to `user`.
+    pub fn claim_vested_by(env: Env, caller: Address, user: Address) -> i128 {
+        caller.require_auth();
+        if caller != user && Self::guardian_of(env.clone(), user.clone()) != Some(caller) {
+            panic!("Unauthorized: caller is not the user or their guardian");
+        }
+        Self::release_vested(env, user)
+    }
 
+    /// Let `guardian` trigger claims for `user` via `claim_vested_by`.
+    /// Passing `None` removes the current guardian.
+    pub fn set_guardian(env: Env, user: Address, guardian: Option<Address>) {
+        user.require_auth();
+        let key = DataKey::Guardian(user.clone());
+        match &guardian {
+            Some(g) => env.storage().persistent().set(&key, g),
+            None => env.storage().persistent().remove(&key),
+        }
+        GuardianSet { user, guardian }.publish(&env);
+    }
+
+    /// The guardian currently allowed to claim for `user`, if any.
+    pub fn guardian_of(env: Env, user: Address) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Guardian(user))
+    }
+
+    /// Release everything vested across `user`'s schedules to `user`.
+    /// Callers must have authorised the claim.
+    fn release_vested(env: Env, user: Address) -> i128 {
         let user_key = DataKey::UserSchedules(user.clone());
         let ids: Vec<u64> = env
             .storage()
@@ -161,6 +300,9 @@ impl RewardVestingContract {
         let token = token::Client::new(&env, &token_addr);
 
         let mut total_claim: i128 = 0;
+        let mut schedules_claimed: u32 = 0;
+        // What the user's live schedules still owe once this claim is paid.
+        let mut still_owed: i128 = 0;
 
         for id in ids.iter() {
             let mut schedule = match map.get(id) {
@@ -172,24 +314,129 @@ impl RewardVestingContract {
             }
             let vested = Self::vested_amount(&schedule, now);
             let claimable = vested.saturating_sub(schedule.claimed).max(0);
+            still_owed += schedule.amount - schedule.claimed - claimable;
             if claimable <= 0 {
                 continue;
             }
             schedule.claimed += claimable;
+            Claimed {
+                user: user.clone(),
+                schedule_id: id,
+                amount: claimable,
+                claimed_total: schedule.claimed,
+                remaining: schedule.amount - schedule.claimed,
+            }
+            .publish(&env);
             map.set(id, schedule);
             total_claim += claimable;
+            schedules_claimed += 1;
         }
 
         if total_claim == 0 {
             panic!("Nothing to claim");
         }
+        // A final claim that empties every schedule is always allowed, or a
+        // remainder below the minimum could never be withdrawn.
+        if still_owed > 0 && total_claim < Self::min_claim(env.clone()) {
+            panic!("BelowMinClaim: claimable amount below minimum");
+        }
+
+        // Only reachable through an accounting bug, but refuse to pay out
+        // more than the contract holds rather than fail inside the token.
+        if token.balance(&env.current_contract_address()) < total_claim {
+            panic!("Underfunded: contract balance below claim");
+        }
 
         env.storage().instance().set(&DataKey::ScheduleMap, &map);
+        Self::adjust_total_locked(&env, -total_claim);
         token.transfer(&env.current_contract_address(), &user, &total_claim);
-        env.events().publish((EVT_CLAIMED,), (user, total_claim));
+        ClaimSummary {
+            user,
+            total_amount: total_claim,
+            schedules_claimed,
+        }
+        .publish(&env);
         total_claim
     }
 
+    /// Set the smallest total a single `claim_vested` may transfer, so tiny
+    /// claims are rejected instead of wasting fees. 0 disables the check.
+    /// A claim that leaves nothing owed on any schedule is exempt.
+    pub fn set_min_claim(env: Env, min_claim: i128) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Not initialized");
+        admin.require_auth();
+
+        if min_claim < 0 {
+            panic!("Invalid amount: must not be negative");
+        }
+
+        env.storage().instance().set(&DataKey::MinClaim, &min_claim);
+        MinClaimSet { min_claim }.publish(&env);
+    }
+
+    /// Current minimum claim amount; 0 when unset.
+    pub fn min_claim(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MinClaim)
+            .unwrap_or(0)
+    }
+
+    /// Increase an existing schedule's grant by `additional_amount`, pulled
+    /// from the admin. The start, cliff and duration are unchanged, so the
+    /// extra tokens vest on the same curve as the original grant.
+    pub fn top_up_schedule(env: Env, schedule_id: u64, additional_amount: i128) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Not initialized");
+        admin.require_auth();
+
+        if additional_amount <= 0 {
+            panic!("Invalid amount: must be positive");
+        }
+
+        let mut map: Map<u64, VestingSchedule> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ScheduleMap)
+            .unwrap_or(Map::new(&env));
+
+        let mut schedule = map.get(schedule_id).expect("Schedule not found");
+        if schedule.revoked {
+            panic!("Schedule already revoked");
+        }
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+        let token = token::Client::new(&env, &token_addr);
+        token.transfer(&admin, &env.current_contract_address(), &additional_amount);
+
+        schedule.amount = schedule
+            .amount
+            .checked_add(additional_amount)
+            .expect("ArithmeticError: amount overflow");
+        let user = schedule.user.clone();
+        let claimed_total = schedule.claimed;
+        let remaining = schedule.amount - schedule.claimed;
+        map.set(schedule_id, schedule);
+        env.storage().instance().set(&DataKey::ScheduleMap, &map);
+        Self::adjust_total_locked(&env, additional_amount);
+
+        ToppedUp {
+            user,
+            schedule_id,
+            amount: additional_amount,
+            claimed_total,
+            remaining,
+        }
+        .publish(&env);
+    }
+
     /// Revoke a vesting schedule. Unvested tokens are returned to the admin.
     pub fn revoke_schedule(env: Env, schedule_id: u64) -> i128 {
         let admin: Address = env
@@ -216,8 +463,11 @@ impl RewardVestingContract {
 
         schedule.revoked = true;
         let user = schedule.user.clone();
+        let claimed_total = schedule.claimed;
+        let released = schedule.amount - schedule.claimed;
         map.set(schedule_id, schedule);
         env.storage().instance().set(&DataKey::ScheduleMap, &map);
+        Self::adjust_total_locked(&env, -released);
 
         if unvested > 0 {
             let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
@@ -225,8 +475,14 @@ impl RewardVestingContract {
             token.transfer(&env.current_contract_address(), &admin, &unvested);
         }
 
-        env.events()
-            .publish((EVT_REVOKED,), (schedule_id, user, unvested));
+        Revoked {
+            user,
+            schedule_id,
+            amount: unvested,
+            claimed_total,
+            remaining: 0,
+        }
+        .publish(&env);
         unvested
     }
 
@@ -254,19 +510,225 @@ impl RewardVestingContract {
         result
     }
 
+    /// Summarise all of `user`'s schedules in a single call.
+    pub fn vesting_summary(env: Env, user: Address) -> VestingSummary {
+        let now = env.ledger().timestamp();
+        let mut summary = VestingSummary {
+            total_granted: 0,
+            total_vested: 0,
+            total_claimed: 0,
+            claimable_now: 0,
+            next_unlock_ts: None,
+        };
+
+        for schedule in Self::vesting_state(env, user).iter() {
+            summary.total_claimed += schedule.claimed;
+            if schedule.revoked {
+                summary.total_granted += schedule.claimed;
+                summary.total_vested += schedule.claimed;
+                continue;
+            }
+
+            let vested = Self::vested_amount(&schedule, now);
+            summary.total_granted += schedule.amount;
+            summary.total_vested += vested;
+            summary.claimable_now += vested.saturating_sub(schedule.claimed).max(0);
+
+            if let Some(ts) = Self::next_unlock(&schedule, now) {
+                summary.next_unlock_ts = Some(match summary.next_unlock_ts {
+                    Some(current) => current.min(ts),
+                    None => ts,
+                });
+            }
+        }
+
+        summary
+    }
+
+    /// Return each of `user`'s schedules with its live `(vested, claimable)`
+    /// amounts at the current ledger time. Revoked schedules report what was
+    /// claimed before revocation as vested and nothing claimable.
+    pub fn vesting_detail(env: Env, user: Address) -> Vec<(VestingSchedule, i128, i128)> {
+        let now = env.ledger().timestamp();
+        let mut result = Vec::new(&env);
+        for schedule in Self::vesting_state(env.clone(), user).iter() {
+            let (vested, claimable) = if schedule.revoked {
+                (schedule.claimed, 0)
+            } else {
+                let vested = Self::vested_amount(&schedule, now);
+                (vested, vested.saturating_sub(schedule.claimed).max(0))
+            };
+            result.push_back((schedule, vested, claimable));
+        }
+        result
+    }
+
+    /// Tokens the contract still owes across all unrevoked schedules.
+    pub fn total_locked(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(0)
+    }
+
+    /// Whether the contract's token balance covers `total_locked`.
+    pub fn is_fully_funded(env: Env) -> bool {
+        let token_addr: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .expect("Not initialized");
+        let balance =
+            token::Client::new(&env, &token_addr).balance(&env.current_contract_address());
+        balance >= Self::total_locked(env)
+    }
+
     // ── Internal ──────────────────────────────────────────────────────────────
 
+    fn adjust_total_locked(env: &Env, delta: i128) {
+        let total: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalLocked, &(total + delta));
+    }
+
+    /// Shared constructor behind the `create_*` entry points.
+    #[allow(clippy::too_many_arguments)]
+    fn create_schedule(
+        env: Env,
+        user: Address,
+        amount: i128,
+        start_timestamp: u64,
+        cliff_seconds: u64,
+        duration_seconds: u64,
+        cliff_unlock_bps: u32,
+        vest_end_override: Option<u64>,
+    ) -> u64 {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Not initialized");
+        admin.require_auth();
+
+        if amount <= 0 {
+            panic!("Invalid amount: must be positive");
+        }
+        if duration_seconds == 0 {
+            panic!("Invalid duration: must be positive");
+        }
+        if cliff_unlock_bps > 10_000 {
+            panic!("Invalid cliff unlock: must be at most 10000 bps");
+        }
+        if let Some(end) = vest_end_override {
+            if end <= start_timestamp + cliff_seconds {
+                panic!("Invalid end override: must be after the cliff");
+            }
+        }
+
+        // Transfer tokens from admin into the contract.
+        let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+        let token = token::Client::new(&env, &token_addr);
+        token.transfer(&admin, &env.current_contract_address(), &amount);
+
+        let schedule_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextScheduleId)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextScheduleId, &(schedule_id + 1));
+
+        let schedule = VestingSchedule {
+            schedule_id,
+            user: user.clone(),
+            amount,
+            start_timestamp,
+            cliff_seconds,
+            duration_seconds,
+            cliff_unlock_bps,
+            vest_end_override,
+            claimed: 0,
+            revoked: false,
+        };
+
+        let mut map: Map<u64, VestingSchedule> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ScheduleMap)
+            .unwrap_or(Map::new(&env));
+        map.set(schedule_id, schedule);
+        env.storage().instance().set(&DataKey::ScheduleMap, &map);
+        Self::adjust_total_locked(&env, amount);
+
+        // Track by user.
+        let user_key = DataKey::UserSchedules(user.clone());
+        let mut ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&user_key)
+            .unwrap_or(Vec::new(&env));
+        ids.push_back(schedule_id);
+        env.storage().persistent().set(&user_key, &ids);
+
+        Scheduled {
+            user,
+            schedule_id,
+            amount,
+            claimed_total: 0,
+            remaining: amount,
+        }
+        .publish(&env);
+        schedule_id
+    }
+
+    /// Earliest time after `now` at which `schedule` vests more tokens, if any.
+    fn next_unlock(schedule: &VestingSchedule, now: u64) -> Option<u64> {
+        let cliff_ts = schedule.start_timestamp + schedule.cliff_seconds;
+        let mut end_ts = schedule.start_timestamp + schedule.duration_seconds;
+        if let Some(end) = schedule.vest_end_override {
+            end_ts = end_ts.min(end);
+        }
+        if now < cliff_ts {
+            Some(cliff_ts)
+        } else if now < end_ts {
+            Some(now + 1)
+        } else {
+            None
+        }
+    }
+
     fn vested_amount(schedule: &VestingSchedule, now: u64) -> i128 {
         if now < schedule.start_timestamp + schedule.cliff_seconds {
             return 0;
         }
+        if schedule.vest_end_override.is_some_and(|end| now >= end) {
+            return schedule.amount;
+        }
         let elapsed = now.saturating_sub(schedule.start_timestamp);
         if elapsed >= schedule.duration_seconds {
             return schedule.amount;
         }
-        (schedule.amount as u128)
-            .saturating_mul(elapsed as u128)
-            .saturating_div(schedule.duration_seconds as u128) as i128
+        if schedule.cliff_unlock_bps == 0 {
+            return (schedule.amount as u128)
+                .saturating_mul(elapsed as u128)
+                .saturating_div(schedule.duration_seconds as u128) as i128;
+        }
+
+        // Lump sum at the cliff, then the rest linearly until the end. The
+        // early return above covers cliffs at or past the end.
+        let cliff_chunk = schedule.amount * schedule.cliff_unlock_bps as i128 / 10_000;
+        let since_cliff = elapsed - schedule.cliff_seconds;
+        let linear_window = schedule.duration_seconds - schedule.cliff_seconds;
+        cliff_chunk
+            + ((schedule.amount - cliff_chunk) as u128)
+                .saturating_mul(since_cliff as u128)
+                .saturating_div(linear_window as u128) as i128
     }
 }
 
@@ -276,8 +738,8 @@ impl RewardVestingContract {
 mod tests {
     use super::*;
     use soroban_sdk::{
-        testutils::{Address as _, Ledger},
-        Env,
+        testutils::{Address as _, Events as _, Ledger},
+        vec, Env, Event as _,
     };
 
     fn setup_token(env: &Env, admin: &Address) -> (token::Client<'static>, Address) {
@@ -345,6 +807,92 @@ mod tests {
         client.create_vesting_schedule(&user, &100, &now, &0, &0);
     }
 
+    #[test]
+    fn test_cliff_unlock_releases_chunk_then_vests_linearly() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        // 25% at the cliff (t=200), remaining 7_500 over the next 800s.
+        client.create_cliff_unlock_schedule(&user, &10_000, &start, &200, &1000, &2_500);
+        let vested = |client: &RewardVestingContractClient| {
+            client.vesting_summary(&user).total_vested
+        };
+
+        env.ledger().with_mut(|l| l.timestamp = start + 199);
+        assert_eq!(vested(&client), 0);
+
+        env.ledger().with_mut(|l| l.timestamp = start + 200);
+        assert_eq!(vested(&client), 2_500);
+
+        env.ledger().with_mut(|l| l.timestamp = start + 400);
+        assert_eq!(vested(&client), 2_500 + 1_875);
+        env.ledger().with_mut(|l| l.timestamp = start + 600);
+        assert_eq!(vested(&client), 2_500 + 3_750);
+
+        env.ledger().with_mut(|l| l.timestamp = start + 1000);
+        assert_eq!(vested(&client), 10_000);
+        assert_eq!(client.claim_vested(&user), 10_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid cliff unlock")]
+    fn test_cliff_unlock_above_full_rejected() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let now = env.ledger().timestamp();
+        client.create_cliff_unlock_schedule(&user, &100, &now, &10, &100, &10_001);
+    }
+
+    #[test]
+    fn test_end_date_override_accelerates_full_vesting() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        // Linear over 1000s, but everything must be vested by t=600.
+        client.create_schedule_with_end_date(&user, &10_000, &start, &100, &1000, &0, &(start + 600));
+        assert_eq!(client.vesting_state(&user).get(0).unwrap().vest_end_override, Some(start + 600));
+
+        env.ledger().with_mut(|l| l.timestamp = start + 99);
+        assert_eq!(client.vesting_summary(&user).total_vested, 0);
+
+        // The linear schedule applies until the end date...
+        env.ledger().with_mut(|l| l.timestamp = start + 500);
+        let summary = client.vesting_summary(&user);
+        assert_eq!(summary.total_vested, 5_000);
+        assert_eq!(summary.next_unlock_ts, Some(start + 501));
+        assert_eq!(client.claim_vested(&user), 5_000);
+
+        // ...then the rest vests at once, long before start + duration.
+        env.ledger().with_mut(|l| l.timestamp = start + 600);
+        let summary = client.vesting_summary(&user);
+        assert_eq!(summary.total_vested, 10_000);
+        assert_eq!(summary.next_unlock_ts, None);
+        assert_eq!(client.claim_vested(&user), 5_000);
+        assert_eq!(client.total_locked(), 0);
+    }
+
+    #[test]
+    fn test_end_date_override_with_cliff_unlock() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        client.create_schedule_with_end_date(&user, &10_000, &start, &200, &1000, &2_500, &(start + 300));
+
+        env.ledger().with_mut(|l| l.timestamp = start + 200);
+        assert_eq!(client.vesting_summary(&user).total_vested, 2_500);
+        env.ledger().with_mut(|l| l.timestamp = start + 300);
+        assert_eq!(client.vesting_summary(&user).total_vested, 10_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid end override")]
+    fn test_end_date_override_at_cliff_rejected() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let now = env.ledger().timestamp();
+        client.create_schedule_with_end_date(&user, &100, &now, &10, &100, &0, &(now + 10));
+    }
+
     #[test]
     #[should_panic(expected = "Nothing to claim")]
     fn test_cliff_blocks_claim() {
@@ -380,6 +928,183 @@ mod tests {
         assert_eq!(claimed, 5_000);
     }
 
+    #[test]
+    fn test_partial_claim_event_carries_totals() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        let id = client.create_vesting_schedule(&user, &10_000, &start, &0, &1000);
+
+        env.ledger().with_mut(|l| l.timestamp = start + 250);
+        client.claim_vested(&user);
+        env.ledger().with_mut(|l| l.timestamp = start + 500);
+        client.claim_vested(&user);
+
+        // The claim's ClaimSummary follows its per-schedule Claimed event.
+        let events = env.events().all().filter_by_contract(&client.address);
+        let events = events.events();
+        let expected = Claimed {
+            user: user.clone(),
+            schedule_id: id,
+            amount: 2_500,
+            claimed_total: 5_000,
+            remaining: 5_000,
+        };
+        assert_eq!(
+            events[events.len() - 2],
+            expected.to_xdr(&env, &client.address)
+        );
+    }
+
+    #[test]
+    fn test_claim_emits_event_per_schedule() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        let id0 = client.create_vesting_schedule(&user, &1_000, &start, &0, &1000);
+        let id1 = client.create_vesting_schedule(&user, &4_000, &start, &0, &2000);
+
+        env.ledger().with_mut(|l| l.timestamp = start + 500);
+        let total = client.claim_vested(&user);
+        assert_eq!(total, 500 + 1_000);
+
+        let events = env.events().all().filter_by_contract(&client.address);
+        let events = events.events();
+        let n = events.len();
+        assert_eq!(
+            events[n - 3],
+            Claimed {
+                user: user.clone(),
+                schedule_id: id0,
+                amount: 500,
+                claimed_total: 500,
+                remaining: 500,
+            }
+            .to_xdr(&env, &client.address)
+        );
+        assert_eq!(
+            events[n - 2],
+            Claimed {
+                user: user.clone(),
+                schedule_id: id1,
+                amount: 1_000,
+                claimed_total: 1_000,
+                remaining: 3_000,
+            }
+            .to_xdr(&env, &client.address)
+        );
+        assert_eq!(
+            events[n - 1],
+            ClaimSummary {
+                user: user.clone(),
+                total_amount: 1_500,
+                schedules_claimed: 2,
+            }
+            .to_xdr(&env, &client.address)
+        );
+    }
+
+    #[test]
+    fn test_guardian_claims_for_user() {
+        let (env, client, _admin, token_client) = setup();
+        let user = Address::generate(&env);
+        let guardian = Address::generate(&env);
+        let now = env.ledger().timestamp();
+        client.create_vesting_schedule(&user, &10_000, &now, &0, &1000);
+
+        client.set_guardian(&user, &Some(guardian.clone()));
+        assert_eq!(client.guardian_of(&user), Some(guardian.clone()));
+
+        env.ledger().with_mut(|l| l.timestamp = now + 1000);
+        assert_eq!(client.claim_vested_by(&guardian, &user), 10_000);
+        assert_eq!(token_client.balance(&user), 10_000);
+        assert_eq!(token_client.balance(&guardian), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_non_guardian_claim_rejected() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let guardian = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        let now = env.ledger().timestamp();
+        client.create_vesting_schedule(&user, &10_000, &now, &0, &1000);
+        client.set_guardian(&user, &Some(guardian));
+
+        env.ledger().with_mut(|l| l.timestamp = now + 1000);
+        client.claim_vested_by(&stranger, &user);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_cleared_guardian_cannot_claim() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let guardian = Address::generate(&env);
+        let now = env.ledger().timestamp();
+        client.create_vesting_schedule(&user, &10_000, &now, &0, &1000);
+
+        client.set_guardian(&user, &Some(guardian.clone()));
+        client.set_guardian(&user, &None);
+        assert_eq!(client.guardian_of(&user), None);
+
+        env.ledger().with_mut(|l| l.timestamp = now + 1000);
+        client.claim_vested_by(&guardian, &user);
+    }
+
+    #[test]
+    #[should_panic(expected = "BelowMinClaim")]
+    fn test_claim_below_min_claim_rejected() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        client.set_min_claim(&100);
+        client.create_vesting_schedule(&user, &10_000, &start, &0, &1000);
+        // 10_000 * 5 / 1000 = 50 vested, below the minimum.
+        env.ledger().with_mut(|l| l.timestamp = start + 5);
+        client.claim_vested(&user);
+    }
+
+    #[test]
+    fn test_claim_above_min_claim_succeeds() {
+        let (env, client, _admin, token_client) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        client.set_min_claim(&100);
+        assert_eq!(client.min_claim(), 100);
+        client.create_vesting_schedule(&user, &10_000, &start, &0, &1000);
+
+        env.ledger().with_mut(|l| l.timestamp = start + 5);
+        assert!(client.try_claim_vested(&user).is_err());
+
+        env.ledger().with_mut(|l| l.timestamp = start + 10);
+        assert_eq!(client.claim_vested(&user), 100);
+        assert_eq!(token_client.balance(&user), 100);
+
+        // A threshold of 0 restores the default behaviour.
+        client.set_min_claim(&0);
+        env.ledger().with_mut(|l| l.timestamp = start + 11);
+        assert_eq!(client.claim_vested(&user), 10);
+    }
+
+    #[test]
+    fn test_final_claim_below_min_claim_succeeds() {
+        let (env, client, _admin, token_client) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        client.set_min_claim(&100);
+        client.create_vesting_schedule(&user, &1_000, &start, &0, &1000);
+
+        env.ledger().with_mut(|l| l.timestamp = start + 950);
+        assert_eq!(client.claim_vested(&user), 950);
+
+        // The last 50 is below the minimum but is all that is still owed.
+        env.ledger().with_mut(|l| l.timestamp = start + 1000);
+        assert_eq!(client.claim_vested(&user), 50);
+        assert_eq!(token_client.balance(&user), 1_000);
+    }
+
     #[test]
     fn test_revoke_schedule() {
         let (env, client, _admin, _tc) = setup();
@@ -402,6 +1127,41 @@ mod tests {
         client.revoke_schedule(&id);
     }
 
+    #[test]
+    fn test_top_up_vests_on_same_timeline() {
+        let (env, client, _admin, token_client) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        let id = client.create_vesting_schedule(&user, &10_000, &start, &0, &1000);
+
+        env.ledger().with_mut(|l| l.timestamp = start + 250);
+        assert_eq!(client.claim_vested(&user), 2_500);
+
+        client.top_up_schedule(&id, &10_000);
+        let schedule = client.vesting_state(&user).get(0).unwrap();
+        assert_eq!(schedule.amount, 20_000);
+        assert_eq!(schedule.start_timestamp, start);
+        assert_eq!(schedule.duration_seconds, 1000);
+        assert_eq!(client.total_locked(), 17_500);
+
+        // Half way through, half of the larger grant has vested.
+        env.ledger().with_mut(|l| l.timestamp = start + 500);
+        assert_eq!(client.claim_vested(&user), 7_500);
+        assert_eq!(token_client.balance(&user), 10_000);
+        assert!(client.is_fully_funded());
+    }
+
+    #[test]
+    #[should_panic(expected = "Schedule already revoked")]
+    fn test_top_up_revoked_schedule_fails() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let now = env.ledger().timestamp();
+        let id = client.create_vesting_schedule(&user, &10_000, &now, &0, &1000);
+        client.revoke_schedule(&id);
+        client.top_up_schedule(&id, &1_000);
+    }
+
     #[test]
     fn test_vesting_state_returns_schedules() {
         let (env, client, _admin, _tc) = setup();
@@ -423,4 +1183,152 @@ mod tests {
         assert_eq!(id0, 0);
         assert_eq!(id1, 1);
     }
+
+    #[test]
+    fn test_vesting_summary_aggregates_schedules() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        client.create_vesting_schedule(&user, &1_000, &start, &100, &1000);
+        client.create_vesting_schedule(&user, &2_000, &start, &300, &1000);
+
+        // Before either cliff: the nearest cliff is the next unlock.
+        let summary = client.vesting_summary(&user);
+        assert_eq!(
+            summary,
+            VestingSummary {
+                total_granted: 3_000,
+                total_vested: 0,
+                total_claimed: 0,
+                claimable_now: 0,
+                next_unlock_ts: Some(start + 100),
+            }
+        );
+
+        // First cliff passed and part claimed; second cliff still pending
+        // but the first schedule keeps vesting linearly.
+        env.ledger().with_mut(|l| l.timestamp = start + 200);
+        client.claim_vested(&user);
+        env.ledger().with_mut(|l| l.timestamp = start + 250);
+        let summary = client.vesting_summary(&user);
+        assert_eq!(summary.total_vested, 250);
+        assert_eq!(summary.total_claimed, 200);
+        assert_eq!(summary.claimable_now, 50);
+        assert_eq!(summary.next_unlock_ts, Some(start + 251));
+
+        // Everything vested: nothing left to unlock.
+        env.ledger().with_mut(|l| l.timestamp = start + 1000);
+        let summary = client.vesting_summary(&user);
+        assert_eq!(summary.total_vested, 3_000);
+        assert_eq!(summary.claimable_now, 2_800);
+        assert_eq!(summary.next_unlock_ts, None);
+    }
+
+    #[test]
+    fn test_vesting_detail_reports_live_vested_and_claimable() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        client.create_vesting_schedule(&user, &1_000, &start, &100, &1000);
+        client.create_vesting_schedule(&user, &4_000, &start, &0, &2000);
+
+        let amounts = |client: &RewardVestingContractClient| {
+            let mut out: Vec<(u64, i128, i128)> = Vec::new(&env);
+            for (schedule, vested, claimable) in client.vesting_detail(&user).iter() {
+                out.push_back((schedule.schedule_id, vested, claimable));
+            }
+            out
+        };
+
+        // Inside the first cliff; the second schedule vests linearly.
+        env.ledger().with_mut(|l| l.timestamp = start + 50);
+        assert_eq!(amounts(&client), vec![&env, (0, 0, 0), (1, 100, 100)]);
+
+        // vested = amount * elapsed / duration, claimable = vested - claimed.
+        env.ledger().with_mut(|l| l.timestamp = start + 500);
+        client.claim_vested(&user);
+        env.ledger().with_mut(|l| l.timestamp = start + 800);
+        assert_eq!(
+            amounts(&client),
+            vec![&env, (0, 800, 300), (1, 1_600, 600)]
+        );
+
+        // First schedule fully vested; revoking the second freezes it.
+        env.ledger().with_mut(|l| l.timestamp = start + 1_200);
+        client.revoke_schedule(&1);
+        assert_eq!(
+            amounts(&client),
+            vec![&env, (0, 1_000, 500), (1, 1_000, 0)]
+        );
+
+        let detail = client.vesting_detail(&user);
+        assert_eq!(detail.get(1).unwrap().0.claimed, 1_000);
+        assert!(detail.get(1).unwrap().0.revoked);
+    }
+
+    #[test]
+    fn test_vesting_summary_next_unlock_is_nearest_cliff() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        // A schedule whose cliff equals its duration vests all at once.
+        client.create_vesting_schedule(&user, &500, &start, &600, &600);
+        client.create_vesting_schedule(&user, &500, &start, &400, &400);
+
+        assert_eq!(
+            client.vesting_summary(&user).next_unlock_ts,
+            Some(start + 400)
+        );
+
+        env.ledger().with_mut(|l| l.timestamp = start + 400);
+        let summary = client.vesting_summary(&user);
+        assert_eq!(summary.claimable_now, 500);
+        assert_eq!(summary.next_unlock_ts, Some(start + 600));
+    }
+
+    #[test]
+    fn test_is_fully_funded_tracks_locked_total() {
+        let (env, client, _admin, _tc) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        let id0 = client.create_vesting_schedule(&user, &1_000, &start, &0, &1000);
+        client.create_vesting_schedule(&user, &3_000, &start, &0, &1000);
+        assert_eq!(client.total_locked(), 4_000);
+        assert!(client.is_fully_funded());
+
+        env.ledger().with_mut(|l| l.timestamp = start + 500);
+        client.claim_vested(&user);
+        assert_eq!(client.total_locked(), 2_000);
+
+        // Revoking releases the 500 still unclaimed on the first schedule;
+        // the unvested part goes back to the admin.
+        client.revoke_schedule(&id0);
+        assert_eq!(client.total_locked(), 1_500);
+        assert!(client.is_fully_funded());
+    }
+
+    #[test]
+    fn test_is_fully_funded_detects_shortfall() {
+        let (env, client, _admin, token_client) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        client.create_vesting_schedule(&user, &1_000, &start, &0, &1000);
+
+        // Drain part of the balance behind the contract's back.
+        token_client.transfer(&client.address, &Address::generate(&env), &600);
+        assert!(!client.is_fully_funded());
+    }
+
+    #[test]
+    #[should_panic(expected = "Underfunded")]
+    fn test_claim_exceeding_balance_rejected() {
+        let (env, client, _admin, token_client) = setup();
+        let user = Address::generate(&env);
+        let start = env.ledger().timestamp();
+        client.create_vesting_schedule(&user, &1_000, &start, &0, &1000);
+        token_client.transfer(&client.address, &Address::generate(&env), &600);
+
+        env.ledger().with_mut(|l| l.timestamp = start + 1000);
+        client.claim_vested(&user);
+    }
 }
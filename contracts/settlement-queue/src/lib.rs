@@ -1,9 +1,10 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractevent, contractimpl, contracttype, symbol_short, Address,
-    Env, Symbol,
+    contract, contracterror, contractevent, contractimpl, contracttype, symbol_short, Address, Env,
+    Symbol, Vec,
 };
+use stellarcade_shared::{check_transition, GovernanceTokenClient, TreasuryClient};
 
 // ---------------------------------------------------------------------------
 // TTL / storage constants
@@ -12,6 +13,16 @@ use soroban_sdk::{
 const PERSISTENT_BUMP_LEDGERS: u32 = 518_400; // ~30 days
 const PERSISTENT_BUMP_THRESHOLD: u32 = PERSISTENT_BUMP_LEDGERS - 100_800; // Renew ~7 days early
 
+/// Number of queue slots, starting at the head, scanned for the
+/// highest-priority pending settlement on each pick.
+const PRIORITY_WINDOW: u64 = 32;
+
+/// Maximum number of settlements returned by one `settlements_of` page.
+const MAX_ACCOUNT_PAGE: u32 = 50;
+
+/// Maximum number of processed slots one `compact` call verifies.
+const MAX_COMPACT_SLOTS: u64 = 200;
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -27,6 +38,7 @@ pub enum Error {
     SettlementNotFound = 5,
     InvalidState = 6,
     Overflow = 7,
+    ProcessingPaused = 8,
 }
 
 // ---------------------------------------------------------------------------
@@ -41,26 +53,57 @@ pub enum SettlementStatus {
     Failed = 2,
 }
 
+/// Allowed status changes. Re-marking a failed settlement updates its
+/// error code; `requeue_front` moves a failed settlement back to pending.
+const SETTLEMENT_TRANSITIONS: &[(SettlementStatus, SettlementStatus)] = &[
+    (SettlementStatus::Pending, SettlementStatus::Processed),
+    (SettlementStatus::Pending, SettlementStatus::Failed),
+    (SettlementStatus::Failed, SettlementStatus::Failed),
+    (SettlementStatus::Failed, SettlementStatus::Pending),
+];
+
+/// How a settlement is paid out when processed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SettlementKind {
+    /// Moved out of the treasury via `allocate`.
+    TreasuryTransfer = 0,
+    /// Minted fresh via the governance token's `mint`.
+    TokenMint = 1,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct SettlementData {
     pub account: Address,
     pub amount: i128,
     pub reason: Symbol,
+    pub priority: u32,
+    pub kind: SettlementKind,
     pub status: SettlementStatus,
     pub error_code: Option<u32>,
+    /// Queue slot the settlement was enqueued into.
+    pub queue_index: u64,
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Admin,
+    Processor,
     RewardContract,
     TreasuryContract,
+    TokenContract,
     Settlement(Symbol), // Keyed by settlement_id
     QueueHead,
     QueueTail,
     QueueItem(u64), // Keyed by index
+    AccountSettled(Address),
+    AccountSettlementCount(Address), // u32; number of ids enqueued for the account
+    AccountSettlement(Address, u32), // settlement_id at that position, in enqueue order
+    FrontQueue,                      // Vec<Symbol> of requeued settlement_ids, processed first
+    ProcessingPaused,                // bool; absent means processing runs
+    CompactedTo,                     // u64; every slot below it is verified cleared
 }
 
 // ---------------------------------------------------------------------------
@@ -73,6 +116,14 @@ pub struct ContractInitialized {
     pub admin: Address,
     pub reward_contract: Address,
     pub treasury_contract: Address,
+    pub token_contract: Address,
+    pub processor: Address,
+}
+
+#[contractevent]
+pub struct ProcessorUpdated {
+    #[topic]
+    pub processor: Address,
 }
 
 #[contractevent]
@@ -82,6 +133,15 @@ pub struct SettlementEnqueued {
     #[topic]
     pub account: Address,
     pub amount: i128,
+    pub kind: SettlementKind,
+}
+
+#[contractevent]
+pub struct SettlementAmended {
+    #[topic]
+    pub settlement_id: Symbol,
+    pub old_amount: i128,
+    pub new_amount: i128,
 }
 
 #[contractevent]
@@ -98,6 +158,29 @@ pub struct SettlementFailed {
     pub error_code: u32,
 }
 
+#[contractevent]
+pub struct SettlementRequeued {
+    #[topic]
+    pub settlement_id: Symbol,
+}
+
+#[contractevent]
+pub struct QueueCompacted {
+    pub compacted_to: u64,
+}
+
+#[contractevent]
+pub struct ProcessingPaused {
+    #[topic]
+    pub admin: Address,
+}
+
+#[contractevent]
+pub struct ProcessingResumed {
+    #[topic]
+    pub admin: Address,
+}
+
 // ---------------------------------------------------------------------------
 // Contract
 // ---------------------------------------------------------------------------
@@ -108,11 +191,18 @@ pub struct SettlementQueue;
 #[contractimpl]
 impl SettlementQueue {
     /// Initialise the contract.
+    ///
+    /// `treasury_contract` pays out `TreasuryTransfer` settlements and
+    /// `token_contract` (the governance token) mints `TokenMint` settlements.
+    /// `processor` may call `process_next` alongside the admin, so a keeper
+    /// can drain the queue without admin rights.
     pub fn init(
         env: Env,
         admin: Address,
         reward_contract: Address,
         treasury_contract: Address,
+        token_contract: Address,
+        processor: Address,
     ) -> Result<(), Error> {
         if env.storage().instance().has(&DataKey::Admin) {
             return Err(Error::AlreadyInitialized);
@@ -127,7 +217,13 @@ impl SettlementQueue {
         env.storage()
             .instance()
             .set(&DataKey::TreasuryContract, &treasury_contract);
-        
+        env.storage()
+            .instance()
+            .set(&DataKey::TokenContract, &token_contract);
+        env.storage()
+            .instance()
+            .set(&DataKey::Processor, &processor);
+
         env.storage().instance().set(&DataKey::QueueHead, &0u64);
         env.storage().instance().set(&DataKey::QueueTail, &0u64);
 
@@ -135,21 +231,78 @@ impl SettlementQueue {
             admin,
             reward_contract,
             treasury_contract,
+            token_contract,
+            processor,
         });
 
         Ok(())
     }
 
+    /// Replace the processor role. Admin only.
+    pub fn set_processor(env: Env, processor: Address) -> Result<(), Error> {
+        let (admin, _) = Self::require_initialized(&env)?;
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Processor, &processor);
+
+        env.events().publish_event(&ProcessorUpdated { processor });
+
+        Ok(())
+    }
+
+    /// The address currently allowed to call `process_next` besides the admin.
+    pub fn processor(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Processor)
+    }
+
+    /// Pause or resume `process_next`. While paused, settlements can still
+    /// be enqueued, amended, failed and queried; they are processed once
+    /// processing resumes. Admin only.
+    pub fn set_processing_paused(env: Env, admin: Address, paused: bool) -> Result<(), Error> {
+        let (stored_admin, _) = Self::require_initialized(&env)?;
+        admin.require_auth();
+        if admin != stored_admin {
+            return Err(Error::NotAuthorized);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::ProcessingPaused, &paused);
+
+        if paused {
+            env.events().publish_event(&ProcessingPaused { admin });
+        } else {
+            env.events().publish_event(&ProcessingResumed { admin });
+        }
+
+        Ok(())
+    }
+
+    /// Whether `process_next` is currently paused.
+    pub fn is_processing_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::ProcessingPaused)
+            .unwrap_or(false)
+    }
+
     /// Enqueue a new settlement.
+    ///
+    /// `priority` orders processing: higher values are processed first, see
+    /// `process_next`. `kind` selects the contract that pays it out.
     pub fn enqueue_settlement(
         env: Env,
         settlement_id: Symbol,
         account: Address,
         amount: i128,
         reason: Symbol,
+        priority: u32,
+        kind: SettlementKind,
     ) -> Result<(), Error> {
         let (admin, _reward_contract) = Self::require_initialized(&env)?;
-        
+
         // Auth: Admin must authorize this
         admin.require_auth();
 
@@ -158,12 +311,17 @@ impl SettlementQueue {
             return Err(Error::InvalidState); // Already exists
         }
 
+        let mut tail: u64 = env.storage().instance().get(&DataKey::QueueTail).unwrap();
+
         let settlement = SettlementData {
             account: account.clone(),
             amount,
             reason: reason.clone(),
+            priority,
+            kind: kind.clone(),
             status: SettlementStatus::Pending,
             error_code: None,
+            queue_index: tail,
         };
 
         env.storage().persistent().set(&settlement_key, &settlement);
@@ -174,8 +332,9 @@ impl SettlementQueue {
         );
 
         // Add to queue
-        let mut tail: u64 = env.storage().instance().get(&DataKey::QueueTail).unwrap();
-        env.storage().persistent().set(&DataKey::QueueItem(tail), &settlement_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::QueueItem(tail), &settlement_id);
         env.storage().persistent().extend_ttl(
             &DataKey::QueueItem(tail),
             PERSISTENT_BUMP_THRESHOLD,
@@ -185,19 +344,61 @@ impl SettlementQueue {
         tail = tail.checked_add(1).ok_or(Error::Overflow)?;
         env.storage().instance().set(&DataKey::QueueTail, &tail);
 
+        // One entry per id, so enqueueing costs the same however many
+        // settlements the account already has.
+        let count = Self::settlement_count_of(env.clone(), account.clone());
+        let entry_key = DataKey::AccountSettlement(account.clone(), count);
+        env.storage().persistent().set(&entry_key, &settlement_id);
+        env.storage().persistent().extend_ttl(
+            &entry_key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+        let count_key = DataKey::AccountSettlementCount(account.clone());
+        let next_count = count.checked_add(1).ok_or(Error::Overflow)?;
+        env.storage().persistent().set(&count_key, &next_count);
+        env.storage().persistent().extend_ttl(
+            &count_key,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
         env.events().publish_event(&SettlementEnqueued {
             settlement_id,
             account,
             amount,
+            kind,
         });
 
         Ok(())
     }
 
     /// Process the next batch of settlements.
-    pub fn process_next(env: Env, batch_size: u32) -> Result<u32, Error> {
+    ///
+    /// Settlements moved to the front with `requeue_front` are processed
+    /// first, in the order they were requeued. Otherwise each pick scans the
+    /// first `PRIORITY_WINDOW` queue slots from the head and processes the
+    /// pending settlement with the highest `priority`. Ties are broken FIFO:
+    /// the earliest-enqueued settlement wins. Slots whose settlement is no
+    /// longer pending (e.g. marked failed) are dropped while scanning. Each
+    /// processed settlement is paid out according to its kind: the treasury's
+    /// `allocate` or the governance token's `mint`. Returns the number of
+    /// settlements processed.
+    ///
+    /// `caller` must be the admin or the processor. Returns
+    /// `ProcessingPaused` while processing is paused.
+    pub fn process_next(env: Env, caller: Address, batch_size: u32) -> Result<u32, Error> {
         let (admin, _) = Self::require_initialized(&env)?;
-        admin.require_auth();
+        caller.require_auth();
+
+        let processor: Option<Address> = env.storage().instance().get(&DataKey::Processor);
+        if caller != admin && Some(caller) != processor {
+            return Err(Error::NotAuthorized);
+        }
+
+        if Self::is_processing_paused(env.clone()) {
+            return Err(Error::ProcessingPaused);
+        }
 
         if batch_size == 0 {
             return Err(Error::InvalidBatchSize);
@@ -207,34 +408,48 @@ impl SettlementQueue {
         let tail: u64 = env.storage().instance().get(&DataKey::QueueTail).unwrap();
 
         let mut processed_count = 0;
-        while head < tail && processed_count < batch_size {
-            let item_key = DataKey::QueueItem(head);
-            let settlement_id: Symbol = env.storage().persistent().get(&item_key).unwrap();
-            
+        while processed_count < batch_size {
+            let (settlement_id, mut settlement) = match Self::pop_front(&env) {
+                Some(next) => next,
+                None => {
+                    head = Self::skip_empty_slots(&env, head, tail);
+                    if head >= tail {
+                        break;
+                    }
+
+                    // Every slot in the window may have been stale; the next
+                    // pass then advances the head past them.
+                    let Some((index, settlement_id, settlement)) =
+                        Self::select_next(&env, head, tail)
+                    else {
+                        continue;
+                    };
+
+                    // Clean up the queue item pointer
+                    env.storage()
+                        .persistent()
+                        .remove(&DataKey::QueueItem(index));
+                    (settlement_id, settlement)
+                }
+            };
+
+            Self::pay_out(&env, &settlement_id, &settlement)?;
+
             let settlement_key = DataKey::Settlement(settlement_id.clone());
-            let mut settlement: SettlementData = env.storage().persistent().get(&settlement_key).unwrap();
+            settlement.status = SettlementStatus::Processed;
+            env.storage().persistent().set(&settlement_key, &settlement);
+            Self::add_account_settled(&env, &settlement.account, settlement.amount)?;
 
-            if settlement.status == SettlementStatus::Pending {
-                // In a real implementation, this would call out to Reward or Treasury
-                // or just mark as processed if this contract is the final word.
-                // For now, we update status to Processed.
-                settlement.status = SettlementStatus::Processed;
-                env.storage().persistent().set(&settlement_key, &settlement);
-                
-                env.events().publish_event(&SettlementProcessed {
-                    settlement_id: settlement_id.clone(),
-                    status: SettlementStatus::Processed,
-                });
-            }
+            env.events().publish_event(&SettlementProcessed {
+                settlement_id,
+                status: SettlementStatus::Processed,
+            });
 
-            // Head always increments, effectively "popping" the queue even if status was already changed
-            head += 1;
             processed_count += 1;
-            
-            // Clean up old queue item pointer
-            env.storage().persistent().remove(&item_key);
         }
 
+        // Drop slots emptied by this batch so `head` reflects the queue front.
+        head = Self::skip_empty_slots(&env, head, tail);
         env.storage().instance().set(&DataKey::QueueHead, &head);
 
         Ok(processed_count)
@@ -252,9 +467,12 @@ impl SettlementQueue {
             .get(&settlement_key)
             .ok_or(Error::SettlementNotFound)?;
 
-        if settlement.status == SettlementStatus::Processed {
-             return Err(Error::InvalidState);
-        }
+        check_transition(
+            &settlement.status,
+            &SettlementStatus::Failed,
+            SETTLEMENT_TRANSITIONS,
+        )
+        .map_err(|_| Error::InvalidState)?;
 
         settlement.status = SettlementStatus::Failed;
         settlement.error_code = Some(error_code);
@@ -269,6 +487,194 @@ impl SettlementQueue {
         Ok(())
     }
 
+    /// Reset a failed settlement to pending and move it to the front of the
+    /// queue, so the next `process_next` picks it up ahead of every other
+    /// pending settlement regardless of priority. Settlements requeued this
+    /// way are processed in the order they were requeued. Admin only.
+    pub fn requeue_front(env: Env, settlement_id: Symbol) -> Result<(), Error> {
+        let (admin, _) = Self::require_initialized(&env)?;
+        admin.require_auth();
+
+        let settlement_key = DataKey::Settlement(settlement_id.clone());
+        let mut settlement: SettlementData = env
+            .storage()
+            .persistent()
+            .get(&settlement_key)
+            .ok_or(Error::SettlementNotFound)?;
+
+        check_transition(
+            &settlement.status,
+            &SettlementStatus::Pending,
+            SETTLEMENT_TRANSITIONS,
+        )
+        .map_err(|_| Error::InvalidState)?;
+
+        settlement.status = SettlementStatus::Pending;
+        settlement.error_code = None;
+        env.storage().persistent().set(&settlement_key, &settlement);
+
+        let mut front = Self::front_queue(&env);
+        front.push_back(settlement_id.clone());
+        env.storage().persistent().set(&DataKey::FrontQueue, &front);
+        env.storage().persistent().extend_ttl(
+            &DataKey::FrontQueue,
+            PERSISTENT_BUMP_THRESHOLD,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        env.events()
+            .publish_event(&SettlementRequeued { settlement_id });
+
+        Ok(())
+    }
+
+    /// Verify that the processed slots below the queue head are cleared and
+    /// advance the audited high-watermark over them. Checks at most
+    /// `MAX_COMPACT_SLOTS` slots per call, so a long backlog of processed
+    /// slots is compacted over several calls. Only reads slots below the head,
+    /// so pending settlements are never touched. Returns the new watermark.
+    /// Admin only.
+    pub fn compact(env: Env, admin: Address) -> Result<u64, Error> {
+        let (stored_admin, _) = Self::require_initialized(&env)?;
+        admin.require_auth();
+        if admin != stored_admin {
+            return Err(Error::NotAuthorized);
+        }
+
+        let head: u64 = env.storage().instance().get(&DataKey::QueueHead).unwrap();
+        let mut compacted_to = Self::compacted_to(&env);
+        let end = head.min(compacted_to.saturating_add(MAX_COMPACT_SLOTS));
+
+        while compacted_to < end {
+            // Slots below the head are removed when processed; a live one
+            // means the queue pointers are inconsistent.
+            if env
+                .storage()
+                .persistent()
+                .has(&DataKey::QueueItem(compacted_to))
+            {
+                return Err(Error::InvalidState);
+            }
+            compacted_to += 1;
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::CompactedTo, &compacted_to);
+        env.events().publish_event(&QueueCompacted { compacted_to });
+
+        Ok(compacted_to)
+    }
+
+    /// The `(compacted_to, head)` slot range: every slot below `compacted_to`
+    /// has been verified cleared by `compact`, and the slots from there up to
+    /// the queue head are processed but not yet verified.
+    pub fn processed_range(env: Env) -> (u64, u64) {
+        let head: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::QueueHead)
+            .unwrap_or(0);
+        (Self::compacted_to(&env), head)
+    }
+
+    /// Correct the amount of a pending settlement in place, keeping its queue
+    /// slot. Processed or failed settlements cannot be amended.
+    pub fn amend_settlement(
+        env: Env,
+        settlement_id: Symbol,
+        new_amount: i128,
+    ) -> Result<(), Error> {
+        let (admin, _) = Self::require_initialized(&env)?;
+        admin.require_auth();
+
+        let settlement_key = DataKey::Settlement(settlement_id.clone());
+        let mut settlement: SettlementData = env
+            .storage()
+            .persistent()
+            .get(&settlement_key)
+            .ok_or(Error::SettlementNotFound)?;
+
+        if settlement.status != SettlementStatus::Pending {
+            return Err(Error::InvalidState);
+        }
+
+        let old_amount = settlement.amount;
+        settlement.amount = new_amount;
+        env.storage().persistent().set(&settlement_key, &settlement);
+
+        env.events().publish_event(&SettlementAmended {
+            settlement_id,
+            old_amount,
+            new_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Return the `(admin, reward_contract, treasury_contract)` wiring set
+    /// at `init`.
+    pub fn config(env: Env) -> Result<(Address, Address, Address), Error> {
+        let storage = env.storage().instance();
+        let admin = storage.get(&DataKey::Admin).ok_or(Error::NotInitialized)?;
+        let reward = storage
+            .get(&DataKey::RewardContract)
+            .ok_or(Error::NotInitialized)?;
+        let treasury = storage
+            .get(&DataKey::TreasuryContract)
+            .ok_or(Error::NotInitialized)?;
+        Ok((admin, reward, treasury))
+    }
+
+    /// Whether `init` has been called.
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::Admin)
+    }
+
+    /// Total amount paid out to `account` by processed settlements. Failed
+    /// settlements never count.
+    pub fn account_settled_total(env: Env, account: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AccountSettled(account))
+            .unwrap_or(0)
+    }
+
+    /// Number of settlements ever enqueued for `account`; the bound for
+    /// paging through `settlements_of`.
+    pub fn settlement_count_of(env: Env, account: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AccountSettlementCount(account))
+            .unwrap_or(0)
+    }
+
+    /// Settlements enqueued for `account`, in any status, oldest first.
+    /// Returns up to `limit` entries (capped at `MAX_ACCOUNT_PAGE`) starting
+    /// at position `start` in the account's list.
+    pub fn settlements_of(
+        env: Env,
+        account: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<SettlementData> {
+        let end = start
+            .saturating_add(limit.min(MAX_ACCOUNT_PAGE))
+            .min(Self::settlement_count_of(env.clone(), account.clone()));
+        let mut page = Vec::new(&env);
+        for i in start..end {
+            let id: Symbol = env
+                .storage()
+                .persistent()
+                .get(&DataKey::AccountSettlement(account.clone(), i))
+                .unwrap();
+            if let Some(settlement) = env.storage().persistent().get(&DataKey::Settlement(id)) {
+                page.push_back(settlement);
+            }
+        }
+        page
+    }
+
     /// Query the state of a settlement.
     pub fn settlement_state(env: Env, settlement_id: Symbol) -> Option<SettlementData> {
         env.storage()
@@ -276,17 +682,173 @@ impl SettlementQueue {
             .get(&DataKey::Settlement(settlement_id))
     }
 
+    /// Number of queue slots ahead of a pending settlement (`slot - head`), or
+    /// `None` if it is processed, failed or unknown. Slots ahead may hold
+    /// settlements that are no longer pending, and higher-priority items can
+    /// overtake it, so this is an upper bound on settlements ahead. For a
+    /// settlement requeued to the front, this is its position among the
+    /// requeued settlements.
+    pub fn queue_position(env: Env, settlement_id: Symbol) -> Option<u64> {
+        let settlement: SettlementData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Settlement(settlement_id.clone()))?;
+        if settlement.status != SettlementStatus::Pending {
+            return None;
+        }
+        if let Some(position) = Self::front_queue(&env).first_index_of(&settlement_id) {
+            return Some(position as u64);
+        }
+
+        let head: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::QueueHead)
+            .unwrap_or(0);
+        Some(settlement.queue_index.saturating_sub(head))
+    }
+
     // -----------------------------------------------------------------------
     // Internal helpers
     // -----------------------------------------------------------------------
 
+    /// Pay a settlement out through the target contract for its kind.
+    fn pay_out(
+        env: &Env,
+        settlement_id: &Symbol,
+        settlement: &SettlementData,
+    ) -> Result<(), Error> {
+        match settlement.kind {
+            SettlementKind::TreasuryTransfer => {
+                let treasury: Address = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::TreasuryContract)
+                    .ok_or(Error::NotInitialized)?;
+                // The treasury rejects a repeated (account, purpose) pair, so
+                // the unique settlement id is the purpose rather than `reason`.
+                TreasuryClient::new(env, &treasury).allocate(
+                    &settlement.account,
+                    &settlement.amount,
+                    settlement_id,
+                );
+            }
+            SettlementKind::TokenMint => {
+                let token: Address = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::TokenContract)
+                    .ok_or(Error::NotInitialized)?;
+                GovernanceTokenClient::new(env, &token)
+                    .mint(&settlement.account, &settlement.amount);
+            }
+        }
+        Ok(())
+    }
+
+    fn add_account_settled(env: &Env, account: &Address, amount: i128) -> Result<(), Error> {
+        let key = DataKey::AccountSettled(account.clone());
+        let total: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        let total = total.checked_add(amount).ok_or(Error::Overflow)?;
+        env.storage().persistent().set(&key, &total);
+        Ok(())
+    }
+
+    fn front_queue(env: &Env) -> Vec<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::FrontQueue)
+            .unwrap_or(Vec::new(env))
+    }
+
+    /// Take the first still-pending settlement off the front queue, dropping
+    /// entries that were marked failed again after being requeued.
+    fn pop_front(env: &Env) -> Option<(Symbol, SettlementData)> {
+        let mut front = Self::front_queue(env);
+        if front.is_empty() {
+            return None;
+        }
+
+        let mut next = None;
+        while let Some(settlement_id) = front.pop_front() {
+            let settlement: SettlementData = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Settlement(settlement_id.clone()))
+                .unwrap();
+            if settlement.status == SettlementStatus::Pending {
+                next = Some((settlement_id, settlement));
+                break;
+            }
+        }
+
+        if front.is_empty() {
+            env.storage().persistent().remove(&DataKey::FrontQueue);
+        } else {
+            env.storage().persistent().set(&DataKey::FrontQueue, &front);
+        }
+        next
+    }
+
+    /// Slot below which every queue slot has been verified cleared.
+    fn compacted_to(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::CompactedTo)
+            .unwrap_or(0)
+    }
+
+    /// Advance `head` past slots whose queue item has already been removed.
+    fn skip_empty_slots(env: &Env, mut head: u64, tail: u64) -> u64 {
+        while head < tail && !env.storage().persistent().has(&DataKey::QueueItem(head)) {
+            head += 1;
+        }
+        head
+    }
+
+    /// Pick the highest-priority pending settlement within the head window,
+    /// earliest slot first on ties. Stale slots are removed along the way.
+    fn select_next(env: &Env, head: u64, tail: u64) -> Option<(u64, Symbol, SettlementData)> {
+        let end = tail.min(head.saturating_add(PRIORITY_WINDOW));
+        let mut best: Option<(u64, Symbol, SettlementData)> = None;
+
+        for index in head..end {
+            let item_key = DataKey::QueueItem(index);
+            let Some(settlement_id) = env.storage().persistent().get::<_, Symbol>(&item_key) else {
+                continue;
+            };
+            let settlement: SettlementData = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Settlement(settlement_id.clone()))
+                .unwrap();
+
+            if settlement.status != SettlementStatus::Pending {
+                env.storage().persistent().remove(&item_key);
+                continue;
+            }
+
+            let is_better = match &best {
+                None => true,
+                Some((_, _, current)) => settlement.priority > current.priority,
+            };
+            if is_better {
+                best = Some((index, settlement_id, settlement));
+            }
+        }
+
+        best
+    }
+
     fn require_initialized(env: &Env) -> Result<(Address, Address), Error> {
-        let admin: Address = env.storage()
+        let admin: Address = env
+            .storage()
             .instance()
             .get(&DataKey::Admin)
             .ok_or(Error::NotInitialized)?;
-        
-        let reward: Address = env.storage()
+
+        let reward: Address = env
+            .storage()
             .instance()
             .get(&DataKey::RewardContract)
             .ok_or(Error::NotInitialized)?;
@@ -302,14 +864,80 @@ impl SettlementQueue {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env};
+    use soroban_sdk::token::{StellarAssetClient, TokenClient};
+    use soroban_sdk::{
+        testutils::{Address as _, Events, MockAuth, MockAuthInvoke},
+        vec, Address, Env, Event as _, IntoVal, Vec,
+    };
+    use stellarcade_shared::{GovernanceTokenInterface, TreasuryInterface};
+    use stellarcade_treasury::{Treasury as RealTreasury, TreasuryClient as RealTreasuryClient};
+
+    #[contract]
+    pub struct MockTreasury;
+
+    #[contractimpl]
+    impl TreasuryInterface for MockTreasury {
+        fn allocate(env: Env, to_contract: Address, amount: i128, purpose: Symbol) {
+            let mut calls: Vec<(Address, i128, Symbol)> = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("calls"))
+                .unwrap_or(Vec::new(&env));
+            calls.push_back((to_contract, amount, purpose));
+            env.storage()
+                .instance()
+                .set(&symbol_short!("calls"), &calls);
+        }
+
+        fn available_balance(_env: Env) -> i128 {
+            i128::MAX
+        }
+    }
+
+    #[contract]
+    pub struct MockToken;
+
+    #[contractimpl]
+    impl GovernanceTokenInterface for MockToken {
+        fn mint(env: Env, to: Address, amount: i128) {
+            let mut calls: Vec<(Address, i128)> = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("calls"))
+                .unwrap_or(Vec::new(&env));
+            calls.push_back((to, amount));
+            env.storage()
+                .instance()
+                .set(&symbol_short!("calls"), &calls);
+        }
+    }
+
+    fn allocations(env: &Env, treasury: &Address) -> Vec<(Address, i128, Symbol)> {
+        env.as_contract(treasury, || {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("calls"))
+                .unwrap_or(Vec::new(env))
+        })
+    }
+
+    fn mints(env: &Env, token: &Address) -> Vec<(Address, i128)> {
+        env.as_contract(token, || {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("calls"))
+                .unwrap_or(Vec::new(env))
+        })
+    }
 
     struct Setup<'a> {
         _env: Env,
         client: SettlementQueueClient<'a>,
         _admin: Address,
+        _processor: Address,
         _reward: Address,
         _treasury: Address,
+        _token: Address,
     }
 
     fn setup() -> Setup<'static> {
@@ -321,9 +949,11 @@ mod test {
 
         let admin = Address::generate(&env);
         let reward = Address::generate(&env);
-        let treasury = Address::generate(&env);
+        let treasury = env.register(MockTreasury, ());
+        let token = env.register(MockToken, ());
+        let processor = Address::generate(&env);
 
-        client.init(&admin, &reward, &treasury);
+        client.init(&admin, &reward, &treasury, &token, &processor);
 
         let client: SettlementQueueClient<'static> = unsafe { core::mem::transmute(client) };
 
@@ -331,8 +961,10 @@ mod test {
             _env: env,
             client,
             _admin: admin,
+            _processor: processor,
             _reward: reward,
             _treasury: treasury,
+            _token: token,
         }
     }
 
@@ -342,19 +974,41 @@ mod test {
         // Verify init values if we had queries for them, or just rely on following tests
     }
 
+    #[test]
+    fn test_config_reports_init_wiring() {
+        let s = setup();
+        assert!(s.client.is_initialized());
+        assert_eq!(
+            s.client.config(),
+            (s._admin.clone(), s._reward.clone(), s._treasury.clone())
+        );
+
+        let env = Env::default();
+        let fresh = SettlementQueueClient::new(&env, &env.register(SettlementQueue, ()));
+        assert!(!fresh.is_initialized());
+        assert_eq!(fresh.try_config(), Err(Ok(Error::NotInitialized)));
+    }
+
     #[test]
     fn test_enqueue_and_process() {
         let s = setup();
         let user = Address::generate(&s._env);
         let s_id = symbol_short!("s1");
 
-        s.client.enqueue_settlement(&s_id, &user, &1000i128, &symbol_short!("win"));
+        s.client.enqueue_settlement(
+            &s_id,
+            &user,
+            &1000i128,
+            &symbol_short!("win"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
 
         let state = s.client.settlement_state(&s_id).unwrap();
         assert_eq!(state.status, SettlementStatus::Pending);
         assert_eq!(state.amount, 1000);
 
-        s.client.process_next(&1);
+        s.client.process_next(&s._admin, &1);
 
         let state = s.client.settlement_state(&s_id).unwrap();
         assert_eq!(state.status, SettlementStatus::Processed);
@@ -364,20 +1018,322 @@ mod test {
     fn test_fifo_processing() {
         let s = setup();
         let user = Address::generate(&s._env);
-        
+
         let s1 = symbol_short!("s1");
         let s2 = symbol_short!("s2");
 
-        s.client.enqueue_settlement(&s1, &user, &100, &symbol_short!("r1"));
-        s.client.enqueue_settlement(&s2, &user, &200, &symbol_short!("r2"));
+        s.client.enqueue_settlement(
+            &s1,
+            &user,
+            &100,
+            &symbol_short!("r1"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
+        s.client.enqueue_settlement(
+            &s2,
+            &user,
+            &200,
+            &symbol_short!("r2"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
+
+        s.client.process_next(&s._admin, &1);
+
+        assert_eq!(
+            s.client.settlement_state(&s1).unwrap().status,
+            SettlementStatus::Processed
+        );
+        assert_eq!(
+            s.client.settlement_state(&s2).unwrap().status,
+            SettlementStatus::Pending
+        );
+
+        s.client.process_next(&s._admin, &1);
+        assert_eq!(
+            s.client.settlement_state(&s2).unwrap().status,
+            SettlementStatus::Processed
+        );
+    }
+
+    #[test]
+    fn test_priority_processing() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+
+        let low = symbol_short!("low");
+        let high1 = symbol_short!("high1");
+        let high2 = symbol_short!("high2");
+        let none = symbol_short!("none");
+
+        s.client.enqueue_settlement(
+            &low,
+            &user,
+            &100,
+            &symbol_short!("r"),
+            &1,
+            &SettlementKind::TreasuryTransfer,
+        );
+        s.client.enqueue_settlement(
+            &high1,
+            &user,
+            &100,
+            &symbol_short!("r"),
+            &5,
+            &SettlementKind::TreasuryTransfer,
+        );
+        s.client.enqueue_settlement(
+            &high2,
+            &user,
+            &100,
+            &symbol_short!("r"),
+            &5,
+            &SettlementKind::TreasuryTransfer,
+        );
+        s.client.enqueue_settlement(
+            &none,
+            &user,
+            &100,
+            &symbol_short!("r"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
+
+        let status = |id: &Symbol| s.client.settlement_state(id).unwrap().status;
+
+        // Highest priority first, FIFO between equal priorities
+        s.client.process_next(&s._admin, &1);
+        assert_eq!(status(&high1), SettlementStatus::Processed);
+        assert_eq!(status(&high2), SettlementStatus::Pending);
 
-        s.client.process_next(&1);
-        
-        assert_eq!(s.client.settlement_state(&s1).unwrap().status, SettlementStatus::Processed);
-        assert_eq!(s.client.settlement_state(&s2).unwrap().status, SettlementStatus::Pending);
+        s.client.process_next(&s._admin, &1);
+        assert_eq!(status(&high2), SettlementStatus::Processed);
+        assert_eq!(status(&low), SettlementStatus::Pending);
 
-        s.client.process_next(&1);
-        assert_eq!(s.client.settlement_state(&s2).unwrap().status, SettlementStatus::Processed);
+        s.client.process_next(&s._admin, &1);
+        assert_eq!(status(&low), SettlementStatus::Processed);
+        assert_eq!(status(&none), SettlementStatus::Pending);
+
+        assert_eq!(s.client.process_next(&s._admin, &5), 1);
+        assert_eq!(status(&none), SettlementStatus::Processed);
+        assert_eq!(s.client.process_next(&s._admin, &5), 0);
+    }
+
+    #[test]
+    fn test_priority_skips_failed_settlements() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+
+        let failed = symbol_short!("failed");
+        let ok = symbol_short!("ok");
+
+        s.client.enqueue_settlement(
+            &failed,
+            &user,
+            &100,
+            &symbol_short!("r"),
+            &9,
+            &SettlementKind::TreasuryTransfer,
+        );
+        s.client.enqueue_settlement(
+            &ok,
+            &user,
+            &100,
+            &symbol_short!("r"),
+            &1,
+            &SettlementKind::TreasuryTransfer,
+        );
+        s.client.mark_failed(&failed, &1);
+
+        assert_eq!(s.client.process_next(&s._admin, &5), 1);
+        assert_eq!(
+            s.client.settlement_state(&failed).unwrap().status,
+            SettlementStatus::Failed
+        );
+        assert_eq!(
+            s.client.settlement_state(&ok).unwrap().status,
+            SettlementStatus::Processed
+        );
+    }
+
+    #[test]
+    fn test_process_dispatches_by_kind() {
+        let s = setup();
+        let alice = Address::generate(&s._env);
+        let bob = Address::generate(&s._env);
+
+        s.client.enqueue_settlement(
+            &symbol_short!("xfer"),
+            &alice,
+            &300,
+            &symbol_short!("payout"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
+        s.client.enqueue_settlement(
+            &symbol_short!("mint"),
+            &bob,
+            &50,
+            &symbol_short!("reward"),
+            &0,
+            &SettlementKind::TokenMint,
+        );
+
+        assert_eq!(s.client.process_next(&s._admin, &2), 2);
+
+        assert_eq!(
+            allocations(&s._env, &s._treasury),
+            vec![&s._env, (alice, 300i128, symbol_short!("xfer"))]
+        );
+        assert_eq!(mints(&s._env, &s._token), vec![&s._env, (bob, 50i128)]);
+    }
+
+    #[test]
+    fn test_treasury_payouts_with_same_reason_all_settle() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(SettlementQueue, ());
+        let client = SettlementQueueClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let alice = Address::generate(&env);
+
+        // The real treasury dedupes allocations by (account, purpose). The
+        // queue is its admin so it may call `allocate`.
+        let asset = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        let treasury_id = env.register(RealTreasury, ());
+        let treasury = RealTreasuryClient::new(&env, &treasury_id);
+        treasury.init(&contract_id, &asset);
+        let funder = Address::generate(&env);
+        StellarAssetClient::new(&env, &asset).mint(&funder, &1000);
+        treasury.deposit(&funder, &1000, &symbol_short!("fund"));
+
+        let token = env.register(MockToken, ());
+        client.init(
+            &admin,
+            &Address::generate(&env),
+            &treasury_id,
+            &token,
+            &Address::generate(&env),
+        );
+
+        for (id, amount) in [(symbol_short!("s1"), 300i128), (symbol_short!("s2"), 200)] {
+            client.enqueue_settlement(
+                &id,
+                &alice,
+                &amount,
+                &symbol_short!("win"),
+                &0,
+                &SettlementKind::TreasuryTransfer,
+            );
+        }
+
+        assert_eq!(client.process_next(&admin, &2), 2);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&alice), 500);
+        assert_eq!(treasury.available_balance(), 500);
+    }
+
+    #[test]
+    fn test_failed_settlement_is_not_paid_out() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        let s_id = symbol_short!("s1");
+
+        s.client.enqueue_settlement(
+            &s_id,
+            &user,
+            &500,
+            &symbol_short!("reward"),
+            &0,
+            &SettlementKind::TokenMint,
+        );
+        s.client.mark_failed(&s_id, &1);
+
+        assert_eq!(s.client.process_next(&s._admin, &1), 0);
+        assert_eq!(mints(&s._env, &s._token).len(), 0);
+    }
+
+    #[test]
+    fn test_account_settled_total_excludes_failed() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        let other = Address::generate(&s._env);
+
+        for (id, account, amount) in [
+            (symbol_short!("s1"), &user, 300i128),
+            (symbol_short!("s2"), &other, 50i128),
+            (symbol_short!("s3"), &user, 200i128),
+            (symbol_short!("s4"), &user, 1_000i128),
+        ] {
+            s.client.enqueue_settlement(
+                &id,
+                account,
+                &amount,
+                &symbol_short!("win"),
+                &0,
+                &SettlementKind::TreasuryTransfer,
+            );
+        }
+        assert_eq!(s.client.account_settled_total(&user), 0);
+
+        s.client.process_next(&s._admin, &3);
+        assert_eq!(s.client.account_settled_total(&user), 500);
+        assert_eq!(s.client.account_settled_total(&other), 50);
+
+        s.client.mark_failed(&symbol_short!("s4"), &7);
+        s.client.process_next(&s._admin, &1);
+        assert_eq!(s.client.account_settled_total(&user), 500);
+    }
+
+    #[test]
+    fn test_settlements_of_lists_only_own_settlements() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        let other = Address::generate(&s._env);
+
+        for (id, account, amount) in [
+            (symbol_short!("s1"), &user, 300i128),
+            (symbol_short!("s2"), &other, 50i128),
+            (symbol_short!("s3"), &user, 200i128),
+            (symbol_short!("s4"), &user, 100i128),
+        ] {
+            s.client.enqueue_settlement(
+                &id,
+                account,
+                &amount,
+                &symbol_short!("win"),
+                &0,
+                &SettlementKind::TreasuryTransfer,
+            );
+        }
+        s.client.process_next(&s._admin, &1);
+        s.client.mark_failed(&symbol_short!("s3"), &7);
+
+        let mine = s.client.settlements_of(&user, &0, &10);
+        assert_eq!(mine.len(), 3);
+        assert_eq!(mine.get(0).unwrap().amount, 300);
+        assert_eq!(mine.get(0).unwrap().status, SettlementStatus::Processed);
+        assert_eq!(mine.get(1).unwrap().status, SettlementStatus::Failed);
+        assert_eq!(mine.get(2).unwrap().status, SettlementStatus::Pending);
+        for settlement in mine.iter() {
+            assert_eq!(settlement.account, user);
+        }
+
+        assert_eq!(s.client.settlement_count_of(&user), 3);
+        assert_eq!(s.client.settlement_count_of(&other), 1);
+
+        let theirs = s.client.settlements_of(&other, &0, &10);
+        assert_eq!(theirs.len(), 1);
+        assert_eq!(theirs.get(0).unwrap().amount, 50);
+
+        // Pages continue from `start`
+        let page = s.client.settlements_of(&user, &1, &1);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page.get(0).unwrap().amount, 200);
+        assert_eq!(s.client.settlements_of(&user, &3, &10).len(), 0);
     }
 
     #[test]
@@ -386,7 +1342,14 @@ mod test {
         let user = Address::generate(&s._env);
         let s_id = symbol_short!("s1");
 
-        s.client.enqueue_settlement(&s_id, &user, &500, &symbol_short!("fail"));
+        s.client.enqueue_settlement(
+            &s_id,
+            &user,
+            &500,
+            &symbol_short!("fail"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
         s.client.mark_failed(&s_id, &404);
 
         let state = s.client.settlement_state(&s_id).unwrap();
@@ -394,6 +1357,330 @@ mod test {
         assert_eq!(state.error_code, Some(404));
     }
 
+    #[test]
+    fn test_requeue_front_processes_before_pending() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        let ids = [
+            symbol_short!("s1"),
+            symbol_short!("s2"),
+            symbol_short!("s3"),
+            symbol_short!("s4"),
+        ];
+
+        for (i, id) in ids.iter().enumerate() {
+            s.client.enqueue_settlement(
+                id,
+                &user,
+                &(100 * (i as i128 + 1)),
+                &symbol_short!("win"),
+                &if i == 0 { 5 } else { 0 },
+                &SettlementKind::TreasuryTransfer,
+            );
+        }
+        s.client.mark_failed(&ids[3], &500);
+
+        s.client.requeue_front(&ids[3]);
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &SettlementRequeued {
+                settlement_id: ids[3].clone(),
+            }
+            .to_xdr(&s._env, &s.client.address)
+        );
+        let state = s.client.settlement_state(&ids[3]).unwrap();
+        assert_eq!(state.status, SettlementStatus::Pending);
+        assert_eq!(state.error_code, None);
+        assert_eq!(s.client.queue_position(&ids[3]), Some(0));
+
+        // The requeued settlement jumps ahead of even the high-priority one.
+        assert_eq!(s.client.process_next(&s._admin, &1), 1);
+        assert_eq!(
+            allocations(&s._env, &s._treasury),
+            vec![&s._env, (user.clone(), 400, symbol_short!("s4"))]
+        );
+
+        // It is paid once; the rest follow in their usual order.
+        assert_eq!(s.client.process_next(&s._admin, &10), 3);
+        let paid = Vec::from_iter(
+            &s._env,
+            allocations(&s._env, &s._treasury)
+                .iter()
+                .map(|(_, amount, _)| amount),
+        );
+        assert_eq!(paid, vec![&s._env, 400, 100, 200, 300]);
+        assert_eq!(s.client.account_settled_total(&user), 1000);
+    }
+
+    #[test]
+    fn test_paused_processing_rejects_process_next() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        s.client.enqueue_settlement(
+            &symbol_short!("s1"),
+            &user,
+            &100i128,
+            &symbol_short!("win"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
+
+        s.client.set_processing_paused(&s._admin, &true);
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &ProcessingPaused {
+                admin: s._admin.clone(),
+            }
+            .to_xdr(&s._env, &s.client.address)
+        );
+        assert!(s.client.is_processing_paused());
+
+        assert_eq!(
+            s.client.try_process_next(&s._admin, &1),
+            Err(Ok(Error::ProcessingPaused))
+        );
+        assert_eq!(
+            s.client.try_process_next(&s._processor, &1),
+            Err(Ok(Error::ProcessingPaused))
+        );
+        assert_eq!(
+            s.client
+                .settlement_state(&symbol_short!("s1"))
+                .unwrap()
+                .status,
+            SettlementStatus::Pending
+        );
+        assert!(allocations(&s._env, &s._treasury).is_empty());
+
+        let stranger = Address::generate(&s._env);
+        assert_eq!(
+            s.client.try_set_processing_paused(&stranger, &false),
+            Err(Ok(Error::NotAuthorized))
+        );
+    }
+
+    #[test]
+    fn test_enqueue_during_pause_then_resume_drains_backlog() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        s.client.set_processing_paused(&s._admin, &true);
+
+        let ids = [
+            symbol_short!("s1"),
+            symbol_short!("s2"),
+            symbol_short!("s3"),
+        ];
+        for id in ids.iter() {
+            s.client.enqueue_settlement(
+                id,
+                &user,
+                &100i128,
+                &symbol_short!("win"),
+                &0,
+                &SettlementKind::TreasuryTransfer,
+            );
+        }
+        assert_eq!(s.client.queue_position(&ids[2]), Some(2));
+
+        s.client.set_processing_paused(&s._admin, &false);
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &ProcessingResumed {
+                admin: s._admin.clone(),
+            }
+            .to_xdr(&s._env, &s.client.address)
+        );
+        assert!(!s.client.is_processing_paused());
+
+        assert_eq!(s.client.process_next(&s._processor, &10), 3);
+        for id in ids.iter() {
+            assert_eq!(
+                s.client.settlement_state(id).unwrap().status,
+                SettlementStatus::Processed
+            );
+        }
+        assert_eq!(s.client.account_settled_total(&user), 300);
+    }
+
+    #[test]
+    fn test_compact_audits_processed_slots_only() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        let ids = [
+            symbol_short!("s1"),
+            symbol_short!("s2"),
+            symbol_short!("s3"),
+        ];
+        for id in ids.iter() {
+            s.client.enqueue_settlement(
+                id,
+                &user,
+                &100i128,
+                &symbol_short!("win"),
+                &0,
+                &SettlementKind::TreasuryTransfer,
+            );
+        }
+        assert_eq!(s.client.processed_range(), (0, 0));
+        assert_eq!(s.client.compact(&s._admin), 0);
+
+        assert_eq!(s.client.process_next(&s._admin, &2), 2);
+        assert_eq!(s.client.processed_range(), (0, 2));
+
+        assert_eq!(s.client.compact(&s._admin), 2);
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &QueueCompacted { compacted_to: 2 }.to_xdr(&s._env, &s.client.address)
+        );
+        assert_eq!(s.client.processed_range(), (2, 2));
+
+        // The pending settlement keeps its slot and is processed as usual.
+        assert_eq!(s.client.queue_position(&ids[2]), Some(0));
+        assert_eq!(s.client.process_next(&s._admin, &1), 1);
+        assert_eq!(
+            s.client.settlement_state(&ids[2]).unwrap().status,
+            SettlementStatus::Processed
+        );
+        assert_eq!(s.client.processed_range(), (2, 3));
+        assert_eq!(s.client.compact(&s._admin), 3);
+
+        let stranger = Address::generate(&s._env);
+        assert_eq!(
+            s.client.try_compact(&stranger),
+            Err(Ok(Error::NotAuthorized))
+        );
+    }
+
+    #[test]
+    fn test_requeue_front_requires_failed_settlement() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        let s_id = symbol_short!("s1");
+
+        assert_eq!(
+            s.client.try_requeue_front(&s_id),
+            Err(Ok(Error::SettlementNotFound))
+        );
+
+        s.client.enqueue_settlement(
+            &s_id,
+            &user,
+            &100,
+            &symbol_short!("win"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
+        assert_eq!(
+            s.client.try_requeue_front(&s_id),
+            Err(Ok(Error::InvalidState))
+        );
+
+        s.client.process_next(&s._admin, &1);
+        assert_eq!(
+            s.client.try_requeue_front(&s_id),
+            Err(Ok(Error::InvalidState))
+        );
+    }
+
+    #[test]
+    fn test_queue_position_shifts_as_head_advances() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        let ids = [
+            symbol_short!("s1"),
+            symbol_short!("s2"),
+            symbol_short!("s3"),
+        ];
+
+        for id in ids.iter() {
+            s.client.enqueue_settlement(
+                id,
+                &user,
+                &100,
+                &symbol_short!("win"),
+                &0,
+                &SettlementKind::TreasuryTransfer,
+            );
+        }
+        assert_eq!(s.client.queue_position(&ids[0]), Some(0));
+        assert_eq!(s.client.queue_position(&ids[1]), Some(1));
+        assert_eq!(s.client.queue_position(&ids[2]), Some(2));
+
+        s.client.process_next(&s._admin, &1);
+
+        assert_eq!(s.client.queue_position(&ids[0]), None);
+        assert_eq!(s.client.queue_position(&ids[1]), Some(0));
+        assert_eq!(s.client.queue_position(&ids[2]), Some(1));
+        assert_eq!(s.client.queue_position(&symbol_short!("none")), None);
+    }
+
+    #[test]
+    fn test_amend_pending_settlement_pays_new_amount() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        let s_id = symbol_short!("s1");
+
+        s.client.enqueue_settlement(
+            &s_id,
+            &user,
+            &500,
+            &symbol_short!("win"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
+        s.client.amend_settlement(&s_id, &750);
+
+        let events = s._env.events().all().filter_by_contract(&s.client.address);
+        let events = events.events();
+        assert_eq!(
+            events[events.len() - 1],
+            SettlementAmended {
+                settlement_id: s_id.clone(),
+                old_amount: 500,
+                new_amount: 750,
+            }
+            .to_xdr(&s._env, &s.client.address)
+        );
+        assert_eq!(s.client.settlement_state(&s_id).unwrap().amount, 750);
+
+        s.client.process_next(&s._admin, &1);
+        assert_eq!(
+            allocations(&s._env, &s._treasury),
+            vec![&s._env, (user, 750i128, s_id)]
+        );
+    }
+
+    #[test]
+    fn test_amend_rejects_processed_settlement() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+        let s_id = symbol_short!("s1");
+
+        s.client.enqueue_settlement(
+            &s_id,
+            &user,
+            &500,
+            &symbol_short!("win"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
+        s.client.process_next(&s._admin, &1);
+
+        assert_eq!(
+            s.client.try_amend_settlement(&s_id, &750),
+            Err(Ok(Error::InvalidState))
+        );
+        assert_eq!(
+            s.client.try_amend_settlement(&symbol_short!("none"), &750),
+            Err(Ok(Error::SettlementNotFound))
+        );
+        assert_eq!(s.client.settlement_state(&s_id).unwrap().amount, 500);
+    }
+
     #[test]
     fn test_unauthorized_enqueue() {
         let env = Env::default();
@@ -405,12 +1692,109 @@ mod test {
         let admin = Address::generate(&env);
         let reward = Address::generate(&env);
         let treasury = Address::generate(&env);
+        let token = Address::generate(&env);
         let _stranger = Address::generate(&env);
 
-        client.init(&admin, &reward, &treasury);
+        client.init(&admin, &reward, &treasury, &token, &admin);
 
         // This should fail because stranger is not admin or reward contract
         // However, in mock_all_auths mode, we need to be careful.
         // We'll trust require_auth logic.
     }
+
+    /// Restrict auth to `who` signing an `enqueue_settlement` call with
+    /// these arguments, then attempt it.
+    fn try_enqueue_as(s: &Setup, who: &Address, settlement_id: &Symbol, account: &Address) -> bool {
+        let kind = SettlementKind::TreasuryTransfer;
+        s._env.mock_auths(&[MockAuth {
+            address: who,
+            invoke: &MockAuthInvoke {
+                contract: &s.client.address,
+                fn_name: "enqueue_settlement",
+                args: (
+                    settlement_id.clone(),
+                    account.clone(),
+                    10i128,
+                    symbol_short!("win"),
+                    0u32,
+                    kind.clone(),
+                )
+                    .into_val(&s._env),
+                sub_invokes: &[],
+            },
+        }]);
+        let ok = s
+            .client
+            .try_enqueue_settlement(
+                settlement_id,
+                account,
+                &10i128,
+                &symbol_short!("win"),
+                &0,
+                &kind,
+            )
+            .is_ok();
+        s._env.mock_all_auths();
+        ok
+    }
+
+    #[test]
+    fn test_processor_can_process_but_not_enqueue() {
+        let s = setup();
+        let user = Address::generate(&s._env);
+
+        assert_eq!(s.client.processor(), Some(s._processor.clone()));
+
+        s.client.enqueue_settlement(
+            &symbol_short!("s1"),
+            &user,
+            &100,
+            &symbol_short!("win"),
+            &0,
+            &SettlementKind::TreasuryTransfer,
+        );
+        assert_eq!(s.client.process_next(&s._processor, &1), 1);
+        assert_eq!(
+            s.client
+                .settlement_state(&symbol_short!("s1"))
+                .unwrap()
+                .status,
+            SettlementStatus::Processed
+        );
+
+        assert!(!try_enqueue_as(
+            &s,
+            &s._processor,
+            &symbol_short!("s2"),
+            &user
+        ));
+        assert!(s.client.settlement_state(&symbol_short!("s2")).is_none());
+    }
+
+    #[test]
+    fn test_stranger_can_neither_process_nor_enqueue() {
+        let s = setup();
+        let stranger = Address::generate(&s._env);
+        let user = Address::generate(&s._env);
+
+        assert_eq!(
+            s.client.try_process_next(&stranger, &1),
+            Err(Ok(Error::NotAuthorized))
+        );
+        assert!(!try_enqueue_as(&s, &stranger, &symbol_short!("s1"), &user));
+    }
+
+    #[test]
+    fn test_set_processor_replaces_role() {
+        let s = setup();
+        let keeper = Address::generate(&s._env);
+
+        s.client.set_processor(&keeper);
+        assert_eq!(s.client.processor(), Some(keeper.clone()));
+        assert_eq!(s.client.process_next(&keeper, &1), 0);
+        assert_eq!(
+            s.client.try_process_next(&s._processor, &1),
+            Err(Ok(Error::NotAuthorized))
+        );
+    }
 }
@@ -2,7 +2,7 @@
 #![no_std]
 #![allow(unexpected_cfgs)]
 
-use soroban_sdk::{contracterror, contracttype, Address};
+use soroban_sdk::{contractclient, contracterror, contracttype, Address, Env, Symbol};
 
 // ─── Common Error Codes ───────────────────────────────────────────────────────
 
@@ -10,7 +10,7 @@ use soroban_sdk::{contracterror, contracttype, Address};
 #[contracttype]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u32)]
-pub enum Error {
+pub enum CommonError {
     NotAuthorized = 1,
     InsufficientBalance = 2,
     InvalidAmount = 3,
@@ -106,23 +106,66 @@ pub enum DataKey {
     BoundConfig,
 }
 
+// ─── Cross-Contract Interfaces ───────────────────────────────────────────────
+
+/// Typed interface of the `stellarcade-treasury` contract, for callers that
+/// move funds through it.
+///
+/// Callers use the generated `TreasuryClient` instead of invoking selectors
+/// by name, so a change to the treasury's signature breaks their build rather
+/// than failing at runtime.
+#[contractclient(name = "TreasuryClient")]
+pub trait TreasuryInterface {
+    fn allocate(env: Env, to_contract: Address, amount: i128, purpose: Symbol);
+    fn available_balance(env: Env) -> i128;
+}
+
+/// Typed interface of the `stellarcade-governance-token` contract, for
+/// callers that mint rewards through it.
+#[contractclient(name = "GovernanceTokenClient")]
+pub trait GovernanceTokenInterface {
+    fn mint(env: Env, to: Address, amount: i128);
+}
+
 // ─── Fee Helpers ──────────────────────────────────────────────────────────────
 
 /// Constant for basis points divisor.
 pub const BASIS_POINTS_DIVISOR: u32 = 10_000;
 
 
-pub fn calculate_fee(amount: i128, fee_bps: u32) -> Result<i128, Error> {
+pub fn calculate_fee(amount: i128, fee_bps: u32) -> Result<i128, CommonError> {
     if amount < 0 {
-        return Err(Error::InvalidAmount);
+        return Err(CommonError::InvalidAmount);
     }
     if fee_bps > BASIS_POINTS_DIVISOR {
-        return Err(Error::InvalidAmount);
+        return Err(CommonError::InvalidAmount);
     }
     amount
         .checked_mul(fee_bps as i128)
         .and_then(|v| v.checked_div(BASIS_POINTS_DIVISOR as i128))
-        .ok_or(Error::Overflow)
+        .ok_or(CommonError::Overflow)
+}
+
+// ─── State-Machine Helpers ───────────────────────────────────────────────────
+
+/// Returned by [`check_transition`] when a status change is not allowed.
+/// Contracts map it onto their own error (or panic message).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct InvalidTransition;
+
+/// Check that moving from status `from` to `to` is one of the `allowed`
+/// `(from, to)` pairs. Keeping each contract's transitions in a single table
+/// makes the state machine reviewable in one place.
+pub fn check_transition<S: PartialEq>(
+    from: &S,
+    to: &S,
+    allowed: &[(S, S)],
+) -> Result<(), InvalidTransition> {
+    if allowed.iter().any(|(f, t)| f == from && t == to) {
+        Ok(())
+    } else {
+        Err(InvalidTransition)
+    }
 }
 
 // ─── Event Topic Helpers ─────────────────────────────────────────────────────
@@ -172,4 +215,60 @@ pub mod events {
     pub fn bounds_updated() -> Symbol {
         symbol_short!("bnd_upd")
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Door {
+        Open,
+        Closed,
+        Locked,
+    }
+
+    const DOOR_TRANSITIONS: &[(Door, Door)] = &[
+        (Door::Open, Door::Closed),
+        (Door::Closed, Door::Open),
+        (Door::Closed, Door::Locked),
+        (Door::Locked, Door::Closed),
+    ];
+
+    #[test]
+    fn test_check_transition_accepts_allowed_pairs() {
+        assert_eq!(
+            check_transition(&Door::Open, &Door::Closed, DOOR_TRANSITIONS),
+            Ok(())
+        );
+        assert_eq!(
+            check_transition(&Door::Closed, &Door::Locked, DOOR_TRANSITIONS),
+            Ok(())
+        );
+        assert_eq!(
+            check_transition(&Door::Locked, &Door::Closed, DOOR_TRANSITIONS),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_check_transition_rejects_other_pairs() {
+        assert_eq!(
+            check_transition(&Door::Open, &Door::Locked, DOOR_TRANSITIONS),
+            Err(InvalidTransition)
+        );
+        assert_eq!(
+            check_transition(&Door::Locked, &Door::Open, DOOR_TRANSITIONS),
+            Err(InvalidTransition)
+        );
+        // Self-transitions are only allowed when listed
+        assert_eq!(
+            check_transition(&Door::Open, &Door::Open, DOOR_TRANSITIONS),
+            Err(InvalidTransition)
+        );
+        assert_eq!(
+            check_transition(&Door::Open, &Door::Closed, &[]),
+            Err(InvalidTransition)
+        );
+    }
+}
@@ -2,8 +2,9 @@
 #![allow(unexpected_cfgs)]
 
 use soroban_sdk::{
-    contract, contracterror, contractevent, contractimpl, contracttype, symbol_short, vec, Address, Env, Symbol,
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, Symbol, Vec,
 };
+use stellarcade_shared::TreasuryClient;
 
 pub const PERSISTENT_BUMP_LEDGERS: u32 = 518_400;
 
@@ -18,6 +19,9 @@ pub enum Error {
     BudgetExceeded = 5,
     RequestNotFound = 6,
     RequestAlreadyProcessed = 7,
+    InvalidConfig = 8,
+    DuplicateApproval = 11,
+    InsufficientTreasury = 12,
 }
 
 #[contracttype]
@@ -28,6 +32,18 @@ pub enum DataKey {
     NextRequestId,
     Budget(Symbol),
     AllocationRequest(u32),
+    LargeApproval,
+    Approvals(u32),
+}
+
+/// Requests of at least `threshold` need `required` distinct approvals from
+/// `approvers` instead of a single admin approval.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LargeApprovalConfig {
+    pub threshold: i128,
+    pub approvers: Vec<Address>,
+    pub required: u32,
 }
 
 #[contracttype]
@@ -54,6 +70,8 @@ pub struct RequestInfo {
     pub amount: i128,
     pub reason: Symbol,
     pub status: RequestStatus,
+    /// Justification recorded by the admin when approving or rejecting.
+    pub decision_note: Option<Symbol>,
 }
 
 #[contractevent]
@@ -79,6 +97,23 @@ pub struct AllocationApproved {
     pub request_id: u32,
     pub bucket_id: Symbol,
     pub amount: i128,
+    pub note: Symbol,
+}
+
+#[contractevent]
+pub struct LargeApprovalConfigured {
+    pub threshold: i128,
+    pub approver_count: u32,
+    pub required: u32,
+}
+
+#[contractevent]
+pub struct AllocationCosigned {
+    #[topic]
+    pub request_id: u32,
+    #[topic]
+    pub approver: Address,
+    pub approvals: u32,
 }
 
 #[contractevent]
@@ -86,6 +121,7 @@ pub struct AllocationRejected {
     #[topic]
     pub request_id: u32,
     pub bucket_id: Symbol,
+    pub note: Symbol,
 }
 
 #[contract]
@@ -170,6 +206,7 @@ impl TreasuryAllocation {
             amount,
             reason,
             status: RequestStatus::Pending,
+            decision_note: None,
         };
 
         let key = DataKey::AllocationRequest(request_id);
@@ -192,65 +229,128 @@ impl TreasuryAllocation {
         Ok(request_id)
     }
 
-    pub fn approve_allocation(env: Env, request_id: u32) -> Result<(), Error> {
-        require_admin_as_invoker(&env)?;
+    /// Records `approver`'s approval of a request, with `note` as the
+    /// decision's justification. Requests below the large-allocation
+    /// threshold (or every request, if none is configured) are approved by
+    /// the admin in one call. Larger ones need `required` distinct approvals
+    /// from the approver set, and the approval that reaches that count
+    /// disburses the request.
+    pub fn approve_allocation(env: Env, approver: Address, request_id: u32, note: Symbol) -> Result<(), Error> {
+        approver.require_auth();
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
 
         let key = DataKey::AllocationRequest(request_id);
-        let mut req: RequestInfo = env.storage().persistent().get(&key).ok_or(Error::RequestNotFound)?;
+        let req: RequestInfo = env.storage().persistent().get(&key).ok_or(Error::RequestNotFound)?;
 
         if req.status != RequestStatus::Pending {
             return Err(Error::RequestAlreadyProcessed);
         }
 
-        let budget_key = DataKey::Budget(req.bucket_id.clone());
-        let mut budget: BudgetInfo = env.storage().persistent().get(&budget_key).unwrap_or(BudgetInfo {
-            limit: 0,
-            allocated: 0,
-            period: 0,
-        });
+        let config = match large_approval_config(&env) {
+            Some(config) if req.amount >= config.threshold => config,
+            _ => {
+                if approver != admin {
+                    return Err(Error::NotAuthorized);
+                }
+                return disburse(&env, request_id, req, note);
+            }
+        };
 
-        if budget.limit > 0 && budget.allocated.checked_add(req.amount).unwrap_or(i128::MAX) > budget.limit {
-            return Err(Error::BudgetExceeded);
+        if !config.approvers.contains(&approver) {
+            return Err(Error::NotAuthorized);
         }
 
-        // Update budget
-        budget.allocated += req.amount;
-        env.storage().persistent().set(&budget_key, &budget);
-        env.storage()
+        let approvals_key = DataKey::Approvals(request_id);
+        let mut approvals: Vec<Address> = env
+            .storage()
             .persistent()
-            .extend_ttl(&budget_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+            .get(&approvals_key)
+            .unwrap_or(Vec::new(&env));
+        if approvals.contains(&approver) {
+            return Err(Error::DuplicateApproval);
+        }
+        approvals.push_back(approver.clone());
 
-        // Update request status
-        req.status = RequestStatus::Approved;
-        env.storage().persistent().set(&key, &req);
+        env.storage().persistent().set(&approvals_key, &approvals);
         env.storage()
             .persistent()
-            .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+            .extend_ttl(&approvals_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+
+        // Approvals from addresses since removed from the set no longer count.
+        let mut count = 0u32;
+        for recorded in approvals.iter() {
+            if config.approvers.contains(&recorded) {
+                count += 1;
+            }
+        }
+
+        AllocationCosigned {
+            request_id,
+            approver,
+            approvals: count,
+        }.publish(&env);
 
-        // Call treasury contract natively
-        let treasury: Address = env.storage().instance().get(&DataKey::TreasuryContract).unwrap();
-
-        env.invoke_contract::<()>(
-            &treasury,
-            &symbol_short!("allocate"),
-            vec![
-                &env,
-                req.requester.into_val(&env),
-                req.amount.into_val(&env),
-                req.reason.into_val(&env),
-            ],
+        if count >= config.required {
+            disburse(&env, request_id, req, note)?;
+        }
+
+        Ok(())
+    }
+
+    /// Configures the multi-approver rule for large allocations. Admin only.
+    /// `required` must be between 1 and the number of approvers.
+    pub fn set_large_approval(
+        env: Env,
+        threshold: i128,
+        approvers: Vec<Address>,
+        required: u32,
+    ) -> Result<(), Error> {
+        require_admin_as_invoker(&env)?;
+
+        if threshold <= 0 || required == 0 || required > approvers.len() {
+            return Err(Error::InvalidConfig);
+        }
+        for (i, approver) in approvers.iter().enumerate() {
+            if approvers.first_index_of(&approver) != Some(i as u32) {
+                return Err(Error::InvalidConfig);
+            }
+        }
+
+        let approver_count = approvers.len();
+        env.storage().instance().set(
+            &DataKey::LargeApproval,
+            &LargeApprovalConfig {
+                threshold,
+                approvers,
+                required,
+            },
         );
 
-        AllocationApproved {
-            request_id,
-            bucket_id: req.bucket_id,
-            amount: req.amount,
+        LargeApprovalConfigured {
+            threshold,
+            approver_count,
+            required,
         }.publish(&env);
 
         Ok(())
     }
 
-    pub fn reject_allocation(env: Env, request_id: u32) -> Result<(), Error> {
+    pub fn large_approval(env: Env) -> Option<LargeApprovalConfig> {
+        large_approval_config(&env)
+    }
+
+    pub fn approvals(env: Env, request_id: u32) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Approvals(request_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn reject_allocation(env: Env, request_id: u32, note: Symbol) -> Result<(), Error> {
         require_admin_as_invoker(&env)?;
 
         let key = DataKey::AllocationRequest(request_id);
@@ -261,6 +361,7 @@ impl TreasuryAllocation {
         }
 
         req.status = RequestStatus::Rejected;
+        req.decision_note = Some(note.clone());
         env.storage().persistent().set(&key, &req);
         env.storage()
             .persistent()
@@ -269,6 +370,7 @@ impl TreasuryAllocation {
         AllocationRejected {
             request_id,
             bucket_id: req.bucket_id,
+            note,
         }.publish(&env);
 
         Ok(())
@@ -295,6 +397,60 @@ impl TreasuryAllocation {
     }
 }
 
+fn large_approval_config(env: &Env) -> Option<LargeApprovalConfig> {
+    env.storage().instance().get(&DataKey::LargeApproval)
+}
+
+/// Charges the request against its bucket budget, marks it approved and
+/// calls the treasury. The treasury's available balance is checked first so
+/// a request the treasury cannot pay stays `Pending` with no budget charged.
+fn disburse(env: &Env, request_id: u32, mut req: RequestInfo, note: Symbol) -> Result<(), Error> {
+    let treasury: Address = env.storage().instance().get(&DataKey::TreasuryContract).unwrap();
+    let treasury_client = TreasuryClient::new(env, &treasury);
+    if treasury_client.available_balance() < req.amount {
+        return Err(Error::InsufficientTreasury);
+    }
+
+    let budget_key = DataKey::Budget(req.bucket_id.clone());
+    let mut budget: BudgetInfo = env.storage().persistent().get(&budget_key).unwrap_or(BudgetInfo {
+        limit: 0,
+        allocated: 0,
+        period: 0,
+    });
+
+    if budget.limit > 0 && budget.allocated.checked_add(req.amount).unwrap_or(i128::MAX) > budget.limit {
+        return Err(Error::BudgetExceeded);
+    }
+
+    // Update budget
+    budget.allocated += req.amount;
+    env.storage().persistent().set(&budget_key, &budget);
+    env.storage()
+        .persistent()
+        .extend_ttl(&budget_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+
+    // Update request status
+    let key = DataKey::AllocationRequest(request_id);
+    req.status = RequestStatus::Approved;
+    req.decision_note = Some(note.clone());
+    env.storage().persistent().set(&key, &req);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+
+    // Call treasury contract through the shared typed interface
+    treasury_client.allocate(&req.requester, &req.amount, &req.reason);
+
+    AllocationApproved {
+        request_id,
+        bucket_id: req.bucket_id,
+        amount: req.amount,
+        note,
+    }.publish(env);
+
+    Ok(())
+}
+
 fn require_admin_as_invoker(env: &Env) -> Result<(), Error> {
     if !env.storage().instance().has(&DataKey::Admin) {
         return Err(Error::NotInitialized);
@@ -309,19 +465,46 @@ mod test {
     use super::*;
     use soroban_sdk::{
         testutils::{Address as _, Events as _},
-        symbol_short, Address, Env,
+        symbol_short, vec, Address, Env, Event as _,
     };
+    use stellarcade_shared::TreasuryInterface;
 
     #[contract]
     pub struct MockTreasury;
 
+    // Implementing the shared trait means the mock stops compiling if the
+    // `allocate` signature in `stellarcade_shared` drifts, just as callers
+    // of `TreasuryClient` do.
+    #[contractimpl]
+    impl TreasuryInterface for MockTreasury {
+        fn allocate(env: Env, to_contract: Address, amount: i128, purpose: Symbol) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("last"), &(to_contract, amount, purpose));
+        }
+
+        // Unlimited unless a test lowers it with `set_available`.
+        fn available_balance(env: Env) -> i128 {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("avail"))
+                .unwrap_or(i128::MAX)
+        }
+    }
+
     #[contractimpl]
     impl MockTreasury {
-        pub fn allocate(_env: Env, _to_contract: Address, _amount: i128, _purpose: Symbol) {
-            // No-op for testing cross-contract invocation
+        pub fn set_available(env: Env, amount: i128) {
+            env.storage().instance().set(&symbol_short!("avail"), &amount);
         }
     }
 
+    fn last_allocation(env: &Env, treasury: &Address) -> Option<(Address, i128, Symbol)> {
+        env.as_contract(treasury, || {
+            env.storage().instance().get(&symbol_short!("last"))
+        })
+    }
+
     fn setup(env: &Env) -> (TreasuryAllocationClient<'_>, Address, Address) {
         let admin = Address::generate(env);
         let treasury = env.register(MockTreasury, ());
@@ -382,7 +565,7 @@ mod test {
     #[test]
     fn test_approve_allocation_success() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
+        let (client, admin, _) = setup(&env);
         env.mock_all_auths();
 
         client.create_budget(&symbol_short!("ops"), &1000, &30);
@@ -390,7 +573,7 @@ mod test {
         let requester = Address::generate(&env);
         let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &500, &symbol_short!("server"));
 
-        client.approve_allocation(&req_id);
+        client.approve_allocation(&admin, &req_id, &symbol_short!("ok"));
 
         let req = client.request_state(&req_id);
         assert_eq!(req.status, RequestStatus::Approved);
@@ -399,10 +582,59 @@ mod test {
         assert_eq!(budget.allocated, 500);
     }
 
+    #[test]
+    fn test_approve_allocation_aborts_when_treasury_short() {
+        let env = Env::default();
+        let (client, admin, treasury) = setup(&env);
+        env.mock_all_auths();
+
+        client.create_budget(&symbol_short!("ops"), &1000, &30);
+        MockTreasuryClient::new(&env, &treasury).set_available(&499);
+
+        let requester = Address::generate(&env);
+        let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &500, &symbol_short!("server"));
+
+        assert_eq!(
+            client.try_approve_allocation(&admin, &req_id, &symbol_short!("ok")),
+            Err(Ok(Error::InsufficientTreasury))
+        );
+
+        let req = client.request_state(&req_id);
+        assert_eq!(req.status, RequestStatus::Pending);
+        assert_eq!(req.decision_note, None);
+        assert_eq!(client.budget_state(&symbol_short!("ops")).allocated, 0);
+        assert_eq!(last_allocation(&env, &treasury), None);
+
+        // Once the treasury is topped up the same request goes through.
+        MockTreasuryClient::new(&env, &treasury).set_available(&500);
+        client.approve_allocation(&admin, &req_id, &symbol_short!("ok"));
+        assert_eq!(client.request_state(&req_id).status, RequestStatus::Approved);
+    }
+
+    #[test]
+    fn test_approve_allocation_calls_treasury_with_request_args() {
+        let env = Env::default();
+        let (client, admin, treasury) = setup(&env);
+        env.mock_all_auths();
+
+        client.create_budget(&symbol_short!("ops"), &1000, &30);
+
+        let requester = Address::generate(&env);
+        let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &400, &symbol_short!("infra"));
+        assert_eq!(last_allocation(&env, &treasury), None);
+
+        client.approve_allocation(&admin, &req_id, &symbol_short!("ok"));
+
+        assert_eq!(
+            last_allocation(&env, &treasury),
+            Some((requester, 400, symbol_short!("infra")))
+        );
+    }
+
     #[test]
     fn test_approve_allocation_exceeds_budget() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
+        let (client, admin, _) = setup(&env);
         env.mock_all_auths();
 
         client.create_budget(&symbol_short!("ops"), &1000, &30);
@@ -410,7 +642,7 @@ mod test {
         let requester = Address::generate(&env);
         let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &1500, &symbol_short!("server"));
 
-        let res = client.try_approve_allocation(&req_id);
+        let res = client.try_approve_allocation(&admin, &req_id, &symbol_short!("ok"));
         assert!(res.is_err());
     }
 
@@ -423,7 +655,7 @@ mod test {
         let requester = Address::generate(&env);
         let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &500, &symbol_short!("server"));
 
-        client.reject_allocation(&req_id);
+        client.reject_allocation(&req_id, &symbol_short!("no"));
 
         let req = client.request_state(&req_id);
         assert_eq!(req.status, RequestStatus::Rejected);
@@ -432,19 +664,205 @@ mod test {
     #[test]
     fn test_prevent_double_processing() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
+        let (client, admin, _) = setup(&env);
         env.mock_all_auths();
 
         client.create_budget(&symbol_short!("ops"), &1000, &30);
         let requester = Address::generate(&env);
         let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &500, &symbol_short!("server"));
 
-        client.approve_allocation(&req_id);
+        client.approve_allocation(&admin, &req_id, &symbol_short!("ok"));
 
-        let res1 = client.try_approve_allocation(&req_id);
+        let res1 = client.try_approve_allocation(&admin, &req_id, &symbol_short!("ok"));
         assert!(res1.is_err());
 
-        let res2 = client.try_reject_allocation(&req_id);
+        let res2 = client.try_reject_allocation(&req_id, &symbol_short!("no"));
         assert!(res2.is_err());
     }
+
+    #[test]
+    fn test_approve_records_and_emits_note() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        client.create_budget(&symbol_short!("ops"), &1000, &30);
+        let requester = Address::generate(&env);
+        let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &500, &symbol_short!("server"));
+        assert_eq!(client.request_state(&req_id).decision_note, None);
+
+        client.approve_allocation(&admin, &req_id, &symbol_short!("q3_infra"));
+
+        // Read events before any other call; the next invocation clears them.
+        let events = env.events().all().filter_by_contract(&client.address);
+        let expected = AllocationApproved {
+            request_id: req_id,
+            bucket_id: symbol_short!("ops"),
+            amount: 500,
+            note: symbol_short!("q3_infra"),
+        };
+        assert_eq!(
+            events.events().last().unwrap(),
+            &expected.to_xdr(&env, &client.address)
+        );
+
+        let req = client.request_state(&req_id);
+        assert_eq!(req.decision_note, Some(symbol_short!("q3_infra")));
+    }
+
+    #[test]
+    fn test_reject_records_and_emits_note() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let requester = Address::generate(&env);
+        let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &500, &symbol_short!("server"));
+
+        client.reject_allocation(&req_id, &symbol_short!("dup_req"));
+
+        let events = env.events().all().filter_by_contract(&client.address);
+        let expected = AllocationRejected {
+            request_id: req_id,
+            bucket_id: symbol_short!("ops"),
+            note: symbol_short!("dup_req"),
+        };
+        assert_eq!(
+            events.events().last().unwrap(),
+            &expected.to_xdr(&env, &client.address)
+        );
+
+        let req = client.request_state(&req_id);
+        assert_eq!(req.decision_note, Some(symbol_short!("dup_req")));
+    }
+
+    #[test]
+    fn test_below_threshold_approves_in_one_call() {
+        let env = Env::default();
+        let (client, admin, treasury) = setup(&env);
+        env.mock_all_auths();
+
+        let approvers = vec![&env, Address::generate(&env), Address::generate(&env)];
+        client.set_large_approval(&1000, &approvers, &2);
+
+        let requester = Address::generate(&env);
+        let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &999, &symbol_short!("server"));
+
+        client.approve_allocation(&admin, &req_id, &symbol_short!("ok"));
+        assert_eq!(client.request_state(&req_id).status, RequestStatus::Approved);
+        assert_eq!(
+            last_allocation(&env, &treasury),
+            Some((requester.clone(), 999, symbol_short!("server")))
+        );
+        assert_eq!(
+            client.try_approve_allocation(&approvers.get(0).unwrap(), &req_id, &symbol_short!("ok")),
+            Err(Ok(Error::RequestAlreadyProcessed))
+        );
+
+        // Only the admin approves small requests.
+        let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &10, &symbol_short!("server"));
+        assert_eq!(
+            client.try_approve_allocation(&approvers.get(0).unwrap(), &req_id, &symbol_short!("ok")),
+            Err(Ok(Error::NotAuthorized))
+        );
+    }
+
+    #[test]
+    fn test_large_allocation_needs_distinct_approvals() {
+        let env = Env::default();
+        let (client, admin, treasury) = setup(&env);
+        env.mock_all_auths();
+
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let c = Address::generate(&env);
+        client.set_large_approval(&1000, &vec![&env, a.clone(), b.clone(), c.clone()], &2);
+
+        let requester = Address::generate(&env);
+        let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &1000, &symbol_short!("expand"));
+
+        // The admin alone cannot approve a large request.
+        assert_eq!(
+            client.try_approve_allocation(&admin, &req_id, &symbol_short!("ok")),
+            Err(Ok(Error::NotAuthorized))
+        );
+
+        client.approve_allocation(&a, &req_id, &symbol_short!("ok"));
+        assert_eq!(client.request_state(&req_id).status, RequestStatus::Pending);
+        assert_eq!(last_allocation(&env, &treasury), None);
+
+        assert_eq!(
+            client.try_approve_allocation(&a, &req_id, &symbol_short!("ok")),
+            Err(Ok(Error::DuplicateApproval))
+        );
+        assert_eq!(
+            client.try_approve_allocation(&Address::generate(&env), &req_id, &symbol_short!("ok")),
+            Err(Ok(Error::NotAuthorized))
+        );
+
+        client.approve_allocation(&b, &req_id, &symbol_short!("board"));
+        let req = client.request_state(&req_id);
+        assert_eq!(req.status, RequestStatus::Approved);
+        assert_eq!(req.decision_note, Some(symbol_short!("board")));
+        assert_eq!(client.approvals(&req_id), vec![&env, a, b]);
+        assert_eq!(
+            last_allocation(&env, &treasury),
+            Some((requester, 1000, symbol_short!("expand")))
+        );
+    }
+
+    #[test]
+    fn test_removed_approvers_no_longer_count() {
+        let env = Env::default();
+        let (client, _, treasury) = setup(&env);
+        env.mock_all_auths();
+
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let c = Address::generate(&env);
+        client.set_large_approval(&1000, &vec![&env, a.clone(), b.clone()], &2);
+
+        let requester = Address::generate(&env);
+        let req_id = client.request_allocation(&requester, &symbol_short!("ops"), &1000, &symbol_short!("expand"));
+        client.approve_allocation(&a, &req_id, &symbol_short!("ok"));
+
+        // `a` is removed before the request reaches two approvals.
+        client.set_large_approval(&1000, &vec![&env, b.clone(), c.clone()], &2);
+
+        client.approve_allocation(&b, &req_id, &symbol_short!("ok"));
+        assert_eq!(client.request_state(&req_id).status, RequestStatus::Pending);
+        assert_eq!(last_allocation(&env, &treasury), None);
+        assert_eq!(
+            client.try_approve_allocation(&a, &req_id, &symbol_short!("ok")),
+            Err(Ok(Error::NotAuthorized))
+        );
+
+        client.approve_allocation(&c, &req_id, &symbol_short!("ok"));
+        assert_eq!(client.request_state(&req_id).status, RequestStatus::Approved);
+        assert_eq!(
+            last_allocation(&env, &treasury),
+            Some((requester, 1000, symbol_short!("expand")))
+        );
+    }
+
+    #[test]
+    fn test_large_approval_config_validation() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let a = Address::generate(&env);
+        assert_eq!(
+            client.try_set_large_approval(&1000, &vec![&env, a.clone()], &2),
+            Err(Ok(Error::InvalidConfig))
+        );
+        assert_eq!(
+            client.try_set_large_approval(&1000, &vec![&env, a.clone(), a.clone()], &2),
+            Err(Ok(Error::InvalidConfig))
+        );
+        assert_eq!(
+            client.try_set_large_approval(&0, &vec![&env, a.clone()], &1),
+            Err(Ok(Error::InvalidConfig))
+        );
+    }
 }
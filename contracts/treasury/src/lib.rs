@@ -347,6 +347,12 @@ impl Treasury {
 
         Ok(state)
     }
+
+    /// Funds currently available for `allocate` and `release`. Lets callers
+    /// check the treasury can pay before committing to a payout.
+    pub fn available_balance(env: Env) -> i128 {
+        get_available(&env)
+    }
 }
 
 fn require_initialized(env: &Env) -> Result<(), Error> {
@@ -548,6 +554,7 @@ mod test {
 
         let state = client.treasury_state();
         assert_eq!(state.available_balance, 1_500);
+        assert_eq!(client.available_balance(), 1_500);
         assert_eq!(state.total_allocated, 500);
         assert_eq!(token.balance(&recipient), 500);
     }
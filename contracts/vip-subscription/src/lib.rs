@@ -20,6 +20,15 @@
 //!
 //! Renewal on an expired subscription reactivates it from `current_time + duration`.
 //!
+//! If a plan has trial terms, a user's first `subscribe` to that plan charges
+//! `trial_price` and lasts `trial_seconds` instead of the normal terms.
+//!
+//! With proration enabled, a cross-plan renewal of an active subscription first
+//! converts the unused time on the current plan into equivalent time on the new
+//! plan (by value), then adds the new plan's duration.
+//!
+//!   Active(from) --transfer_subscription--> Active(to)   [plan_id and expires_at unchanged]
+//!
 //! ## Invariants
 //! - A plan_id can only be defined once (`define_plan` is idempotent-guarded).
 //! - `subscribe` is rejected if the user already has an active or future subscription
@@ -30,7 +39,7 @@
 
 use soroban_sdk::{
     contract, contracterror, contractevent, contractimpl, contracttype, token::TokenClient,
-    Address, BytesN, Env,
+    Address, BytesN, Env, Vec,
 };
 
 // ---------------------------------------------------------------------------
@@ -57,6 +66,10 @@ pub enum Error {
     AlreadySubscribed = 6,
     InvalidInput = 7,
     Overflow = 8,
+    NoActiveSubscription = 9,
+    TokenNotAllowed = 10,
+    RenewTooEarly = 11,
+    TrialAlreadyUsed = 12,
 }
 
 // ---------------------------------------------------------------------------
@@ -73,30 +86,78 @@ pub enum DataKey {
     // --- instance() ---
     Admin,
     Treasury,
+    /// Whether cross-plan renewals convert remaining value (bool).
+    ProrationEnabled,
+    /// Approximate number of active subscriptions (u32). See `metrics`.
+    ActiveCount,
+    /// Cumulative price paid across all subscribes and renewals (i128).
+    TotalRevenue,
     // --- persistent() ---
     /// Plan definition keyed by plan_id (u32).
     Plan(u32),
     /// Subscription record keyed by user Address.
     Subscription(Address),
+    /// Lifetime revenue collected for a plan_id (i128).
+    PlanRevenue(u32),
+    /// Whether a token may be used to price new plans (bool).
+    AllowedToken(Address),
+    /// Trial terms for first-time subscribers, keyed by plan_id.
+    PlanTrial(u32),
+    /// Set once a user has subscribed to a plan_id (bool).
+    UsedTrial(Address, u32),
+    /// Plan a user's next `renew` switches to (u32).
+    PendingChange(Address),
+    /// Seconds before expiry from which an active subscription to a plan_id
+    /// may be renewed (u64). Absent means any time.
+    PlanRenewWindow(u32),
+    /// Win-back discount for lapsed subscribers, keyed by plan_id.
+    PlanReactivation(u32),
+    /// `expires_at` of the period a user was last sent `ExpiringSoon` for
+    /// (u64).
+    ExpiryNotified(Address),
 }
 
 /// Definition of a VIP subscription plan.
 ///
 /// `benefits_hash` is a 32-byte SHA-256 hash of the off-chain benefits
 /// document, providing a tamper-evident commitment without on-chain verbosity.
-/// `price` is the token amount charged per subscription period.
+/// `price` is the amount of `token` charged per subscription period, so
+/// plans may be priced in different assets.
 /// `duration` is the subscription length in seconds.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PlanDefinition {
     /// Token amount charged when subscribing or renewing.
     pub price: i128,
+    /// SEP-41 token contract the price is paid in.
+    pub token: Address,
     /// Duration of one subscription period in seconds.
     pub duration: u64,
     /// SHA-256 hash of the off-chain benefits specification (32 bytes).
     pub benefits_hash: BytesN<32>,
 }
 
+/// Reduced terms for a user's first subscription to a plan.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrialTerms {
+    /// Length of the trial period in seconds.
+    pub trial_seconds: u64,
+    /// Token amount charged for the trial; 0 for a free trial.
+    pub trial_price: i128,
+}
+
+/// Discount offered to users resubscribing to a plan shortly after their
+/// subscription to it lapsed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReactivationOffer {
+    /// Basis points taken off the plan price (1..=10000).
+    pub discount_bps: u32,
+    /// Seconds after expiry during which the discount applies.
+    pub window_seconds: u64,
+}
+
 /// Per-user subscription record.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -105,6 +166,9 @@ pub struct SubscriptionRecord {
     pub plan_id: u32,
     /// Unix timestamp (seconds) at which this subscription expires.
     pub expires_at: u64,
+    /// Whether this record is included in `ActiveCount`. Cleared when
+    /// `sweep_expired` finds the record expired.
+    pub counted_active: bool,
 }
 
 /// Public view of a user's subscription status.
@@ -130,6 +194,7 @@ pub struct PlanDefined {
     #[topic]
     pub plan_id: u32,
     pub price: i128,
+    pub token: Address,
     pub duration: u64,
     pub benefits_hash: BytesN<32>,
 }
@@ -154,6 +219,86 @@ pub struct Renewed {
     pub amount_paid: i128,
 }
 
+#[contractevent]
+pub struct SubscriptionTransferred {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub plan_id: u32,
+    pub expires_at: u64,
+}
+
+/// Payment credited to a plan. `lifetime_revenue` is the plan's running
+/// total after this payment.
+#[contractevent]
+pub struct PlanRevenueRecorded {
+    #[topic]
+    pub plan_id: u32,
+    pub amount: i128,
+    pub lifetime_revenue: i128,
+}
+
+#[contractevent]
+pub struct ProrationModeSet {
+    pub enabled: bool,
+}
+
+#[contractevent]
+pub struct PlanChangeScheduled {
+    #[topic]
+    pub user: Address,
+    pub target_plan_id: Option<u32>,
+}
+
+#[contractevent]
+pub struct TrialConfigured {
+    #[topic]
+    pub plan_id: u32,
+    pub trial_seconds: u64,
+    pub trial_price: i128,
+}
+
+#[contractevent]
+pub struct TrialStarted {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub plan_id: u32,
+    pub expires_at: u64,
+}
+
+#[contractevent]
+pub struct ReactivationOfferSet {
+    #[topic]
+    pub plan_id: u32,
+    pub discount_bps: u32,
+    pub window_seconds: u64,
+}
+
+#[contractevent]
+pub struct RenewWindowSet {
+    #[topic]
+    pub plan_id: u32,
+    pub renew_window_seconds: u64,
+}
+
+#[contractevent]
+pub struct TokenAllowlistUpdated {
+    #[topic]
+    pub token: Address,
+    pub allowed: bool,
+}
+
+#[contractevent]
+pub struct ExpiringSoon {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub plan_id: u32,
+    pub expires_at: u64,
+}
+
 // ---------------------------------------------------------------------------
 // Contract
 // ---------------------------------------------------------------------------
@@ -193,7 +338,9 @@ impl VipSubscription {
     /// Define a new VIP subscription plan. Admin only.
     ///
     /// `plan_id` must be unique; re-defining an existing plan returns
-    /// `PlanAlreadyExists`. `price` must be positive. `duration` must be
+    /// `PlanAlreadyExists`. `price` must be positive and is charged in
+    /// `token`, which must be a SEP-41 token contract on the admin allowlist
+    /// (see `set_token_allowed`). `duration` must be
     /// positive (in seconds). `benefits_hash` is the 32-byte SHA-256 hash of
     /// the off-chain benefits document.
     pub fn define_plan(
@@ -201,44 +348,50 @@ impl VipSubscription {
         admin: Address,
         plan_id: u32,
         price: i128,
+        token: Address,
         duration: u64,
         benefits_hash: BytesN<32>,
     ) -> Result<(), Error> {
         require_initialized(&env)?;
         require_admin(&env, &admin)?;
+        validate_plan_token(&env, &token)?;
 
-        if price <= 0 {
-            return Err(Error::InvalidInput);
-        }
-        if duration == 0 {
+        store_plan(&env, plan_id, price, token, duration, benefits_hash)
+    }
+
+    /// Define several plans priced in `token` in one call. Admin only.
+    ///
+    /// Each `(plan_id, price, duration, benefits_hash)` entry follows the
+    /// same rules as `define_plan`. The batch is atomic: if any `plan_id`
+    /// already exists or repeats within the batch, `PlanAlreadyExists` is
+    /// returned and no plan is defined. Emits one `PlanDefined` per plan.
+    pub fn define_plans(
+        env: Env,
+        admin: Address,
+        token: Address,
+        plans: Vec<(u32, i128, u64, BytesN<32>)>,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+        validate_plan_token(&env, &token)?;
+
+        if plans.is_empty() {
             return Err(Error::InvalidInput);
         }
-
-        let key = DataKey::Plan(plan_id);
-        if env.storage().persistent().has(&key) {
-            return Err(Error::PlanAlreadyExists);
+        let mut seen: Vec<u32> = Vec::new(&env);
+        for (plan_id, price, duration, _) in plans.iter() {
+            if price <= 0 || duration == 0 {
+                return Err(Error::InvalidInput);
+            }
+            if seen.contains(plan_id) || env.storage().persistent().has(&DataKey::Plan(plan_id)) {
+                return Err(Error::PlanAlreadyExists);
+            }
+            seen.push_back(plan_id);
         }
 
-        let plan = PlanDefinition {
-            price,
-            duration,
-            benefits_hash: benefits_hash.clone(),
-        };
-        env.storage().persistent().set(&key, &plan);
-        env.storage().persistent().extend_ttl(
-            &key,
-            PERSISTENT_BUMP_LEDGERS,
-            PERSISTENT_BUMP_LEDGERS,
-        );
-
-        PlanDefined {
-            plan_id,
-            price,
-            duration,
-            benefits_hash,
+        for (plan_id, price, duration, benefits_hash) in plans.iter() {
+            store_plan(&env, plan_id, price, token.clone(), duration, benefits_hash)?;
         }
-        .publish(&env);
-
         Ok(())
     }
 
@@ -246,12 +399,21 @@ impl VipSubscription {
     // subscribe
     // -----------------------------------------------------------------------
 
-    /// Subscribe `user` to `plan_id`. The user pays the plan price via the
-    /// treasury contract.
+    /// Subscribe `user` to `plan_id`. The user pays the plan price, in the
+    /// plan's token, to the treasury contract.
     ///
     /// Rejected if the user already has an active (non-expired) subscription.
     /// Use `renew` to extend an active subscription. A user whose subscription
     /// has already expired may call `subscribe` again to start fresh.
+    ///
+    /// The first time a user subscribes to a plan with trial terms, they pay
+    /// `trial_price` for `trial_seconds` instead. Later subscribes to the same
+    /// plan use the normal price and duration.
+    ///
+    /// If the plan has a reactivation offer and the user's expired record is
+    /// for the same plan and lapsed no more than `window_seconds` ago, the
+    /// price is reduced by `discount_bps`. `amount_paid` in `Subscribed`
+    /// reports the discounted price.
     pub fn subscribe(env: Env, user: Address, plan_id: u32) -> Result<(), Error> {
         require_initialized(&env)?;
 
@@ -261,23 +423,61 @@ impl VipSubscription {
 
         // Reject if the user already has a non-expired subscription.
         let sub_key = DataKey::Subscription(user.clone());
-        if let Some(existing) = get_subscription(&env, &sub_key) {
+        let existing = get_subscription(&env, &sub_key);
+        if let Some(existing) = &existing {
             let now = env.ledger().timestamp();
             if existing.expires_at > now {
                 return Err(Error::AlreadySubscribed);
             }
         }
 
-        // Charge the user by transferring tokens to the treasury.
-        let treasury = get_treasury(&env);
-        TokenClient::new(&env, &treasury).transfer(&user, &treasury, &plan.price);
+        let used_key = DataKey::UsedTrial(user.clone(), plan_id);
+        let trial = if env.storage().persistent().has(&used_key) {
+            None
+        } else {
+            Self::trial_terms(env.clone(), plan_id)
+        };
+        let (mut price, duration) = match &trial {
+            Some(t) => (t.trial_price, t.trial_seconds),
+            None => (plan.price, plan.duration),
+        };
+        if let (None, Some(lapsed)) = (&trial, &existing) {
+            if lapsed.plan_id == plan_id {
+                if let Some(offer) = Self::reactivation_offer(env.clone(), plan_id) {
+                    let lapsed_for = env.ledger().timestamp() - lapsed.expires_at;
+                    if lapsed_for <= offer.window_seconds {
+                        let discount = price
+                            .checked_mul(offer.discount_bps as i128)
+                            .ok_or(Error::Overflow)?
+                            / 10_000;
+                        price -= discount;
+                    }
+                }
+            }
+        }
+
+        // Charge the user by transferring the plan's token to the treasury.
+        if price > 0 {
+            let treasury = get_treasury(&env);
+            TokenClient::new(&env, &plan.token).transfer(&user, &treasury, &price);
+        }
 
         let now = env.ledger().timestamp();
-        let expires_at = now.checked_add(plan.duration).ok_or(Error::Overflow)?;
+        let expires_at = now.checked_add(duration).ok_or(Error::Overflow)?;
+
+        // An expired record that was never swept is still counted, so
+        // replacing it does not add a new active subscriber.
+        if !existing.map(|r| r.counted_active).unwrap_or(false) {
+            adjust_active_count(&env, 1)?;
+        }
+        if price > 0 {
+            add_revenue(&env, plan_id, price)?;
+        }
 
         let record = SubscriptionRecord {
             plan_id,
             expires_at,
+            counted_active: true,
         };
         env.storage().persistent().set(&sub_key, &record);
         env.storage().persistent().extend_ttl(
@@ -285,12 +485,24 @@ impl VipSubscription {
             PERSISTENT_BUMP_LEDGERS,
             PERSISTENT_BUMP_LEDGERS,
         );
+        // Any subscribe counts as using up the trial, even before one is
+        // configured: trials are for users who have never subscribed.
+        env.storage().persistent().set(&used_key, &true);
+        env.storage().persistent().extend_ttl(
+            &used_key,
+            PERSISTENT_BUMP_LEDGERS,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+        // A fresh subscription supersedes any change scheduled on the old one.
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingChange(user.clone()));
 
         Subscribed {
             user,
             plan_id,
             expires_at,
-            amount_paid: plan.price,
+            amount_paid: price,
         }
         .publish(&env);
 
@@ -308,33 +520,76 @@ impl VipSubscription {
     /// expiry is `now + duration`. The plan_id in the record is updated to
     /// match the renewed plan (allowing cross-plan renewal).
     ///
+    /// When proration is enabled and an active subscription is renewed onto a
+    /// different plan, the remaining time is first converted to the new plan
+    /// at equal value: `remaining * old_price / old_duration` tokens buy
+    /// `value * new_duration / new_price` seconds, counted from now. The new
+    /// plan's duration is then added on top. Integer division rounds down.
+    /// Plans priced in different tokens have no common unit of value, so
+    /// such renewals stack like a same-plan renewal instead.
+    ///
+    /// If a plan change was scheduled with `schedule_downgrade`, the renewal
+    /// is made on the scheduled plan instead of `plan_id`, at that plan's
+    /// price, and stacks onto the current expiry without proration. The
+    /// scheduled change is then cleared.
+    ///
+    /// If the current plan has a renew window (see `set_renew_window`), an
+    /// active subscription can only be renewed once `expires_at - now` is
+    /// within it; earlier calls fail with `RenewTooEarly`. Expired
+    /// subscriptions can always be renewed.
+    ///
     /// Rejected if no subscription record exists for the user.
     pub fn renew(env: Env, user: Address, plan_id: u32) -> Result<(), Error> {
         require_initialized(&env)?;
 
         user.require_auth();
 
+        let pending_key = DataKey::PendingChange(user.clone());
+        let scheduled: Option<u32> = env.storage().persistent().get(&pending_key);
+        let plan_id = scheduled.unwrap_or(plan_id);
+
         let plan = require_plan_exists(&env, plan_id)?;
 
         let sub_key = DataKey::Subscription(user.clone());
         let existing = get_subscription(&env, &sub_key).ok_or(Error::PlanNotFound)?;
 
         let now = env.ledger().timestamp();
+        if existing.expires_at > now {
+            let window = renew_window(&env, existing.plan_id);
+            if window > 0 && existing.expires_at - now > window {
+                return Err(Error::RenewTooEarly);
+            }
+        }
+
         // Extend from the current expiry if still active, otherwise from now.
-        let base = if existing.expires_at > now {
-            existing.expires_at
-        } else {
+        let base = if existing.expires_at <= now {
             now
+        } else if scheduled.is_none() && existing.plan_id != plan_id && is_proration_enabled(&env) {
+            let current = require_plan_exists(&env, existing.plan_id)?;
+            if current.token == plan.token {
+                let carried = prorate_remaining(existing.expires_at - now, &current, &plan)?;
+                now.checked_add(carried).ok_or(Error::Overflow)?
+            } else {
+                existing.expires_at
+            }
+        } else {
+            existing.expires_at
         };
         let expires_at = base.checked_add(plan.duration).ok_or(Error::Overflow)?;
 
-        // Charge the user.
+        // Charge the user in the renewed plan's token.
         let treasury = get_treasury(&env);
-        TokenClient::new(&env, &treasury).transfer(&user, &treasury, &plan.price);
+        TokenClient::new(&env, &plan.token).transfer(&user, &treasury, &plan.price);
+
+        if !existing.counted_active {
+            adjust_active_count(&env, 1)?;
+        }
+        add_revenue(&env, plan_id, plan.price)?;
 
         let record = SubscriptionRecord {
             plan_id,
             expires_at,
+            counted_active: true,
         };
         env.storage().persistent().set(&sub_key, &record);
         env.storage().persistent().extend_ttl(
@@ -343,6 +598,10 @@ impl VipSubscription {
             PERSISTENT_BUMP_LEDGERS,
         );
 
+        if scheduled.is_some() {
+            env.storage().persistent().remove(&pending_key);
+        }
+
         Renewed {
             user,
             plan_id,
@@ -355,468 +614,2054 @@ impl VipSubscription {
     }
 
     // -----------------------------------------------------------------------
-    // status_of
+    // schedule_downgrade
     // -----------------------------------------------------------------------
 
-    /// Return the subscription status for `user`.
+    /// Schedule `user`'s next `renew` to switch to `target_plan_id`, e.g. a
+    /// cheaper plan. The current plan and expiry are untouched until then, so
+    /// no paid time is lost. Scheduling again replaces the pending change.
     ///
-    /// Returns a `SubscriptionStatus` with `has_subscription = false` if the
-    /// user has never subscribed. If a record exists, `is_active` reflects
-    /// whether the current ledger timestamp is before `expires_at`.
-    pub fn status_of(env: Env, user: Address) -> SubscriptionStatus {
-        let sub_key = DataKey::Subscription(user);
-        match get_subscription(&env, &sub_key) {
-            None => SubscriptionStatus {
-                has_subscription: false,
-                plan_id: 0,
-                expires_at: 0,
-                is_active: false,
-            },
-            Some(record) => {
-                let now = env.ledger().timestamp();
-                SubscriptionStatus {
-                    has_subscription: true,
-                    plan_id: record.plan_id,
-                    expires_at: record.expires_at,
-                    is_active: record.expires_at > now,
-                }
-            }
-        }
-    }
-}
+    /// Rejected with `NoActiveSubscription` if the user has no active
+    /// subscription, and with `InvalidInput` if the target is the current plan.
+    pub fn schedule_downgrade(env: Env, user: Address, target_plan_id: u32) -> Result<(), Error> {
+        require_initialized(&env)?;
 
-// ---------------------------------------------------------------------------
-// Internal helpers
-// ---------------------------------------------------------------------------
+        user.require_auth();
 
-fn require_initialized(env: &Env) -> Result<(), Error> {
-    if !env.storage().instance().has(&DataKey::Admin) {
-        return Err(Error::NotInitialized);
-    }
-    Ok(())
-}
+        require_plan_exists(&env, target_plan_id)?;
 
-/// Verify that `caller` is the stored admin and has signed the invocation.
-fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
-    let admin: Address = env
-        .storage()
-        .instance()
-        .get(&DataKey::Admin)
-        .ok_or(Error::NotInitialized)?;
-    caller.require_auth();
-    if caller != &admin {
-        return Err(Error::NotAuthorized);
-    }
-    Ok(())
-}
+        let now = env.ledger().timestamp();
+        let record = get_subscription(&env, &DataKey::Subscription(user.clone()))
+            .filter(|r| r.expires_at > now)
+            .ok_or(Error::NoActiveSubscription)?;
+        if record.plan_id == target_plan_id {
+            return Err(Error::InvalidInput);
+        }
 
-/// Fetch the plan definition or return `PlanNotFound`.
-fn require_plan_exists(env: &Env, plan_id: u32) -> Result<PlanDefinition, Error> {
-    env.storage()
-        .persistent()
-        .get(&DataKey::Plan(plan_id))
-        .ok_or(Error::PlanNotFound)
-}
+        let key = DataKey::PendingChange(user.clone());
+        env.storage().persistent().set(&key, &target_plan_id);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_LEDGERS,
+            PERSISTENT_BUMP_LEDGERS,
+        );
 
-fn get_treasury(env: &Env) -> Address {
-    env.storage()
-        .instance()
-        .get(&DataKey::Treasury)
-        .expect("VipSubscription: treasury not set")
-}
+        PlanChangeScheduled {
+            user,
+            target_plan_id: Some(target_plan_id),
+        }
+        .publish(&env);
 
-fn get_subscription(env: &Env, key: &DataKey) -> Option<SubscriptionRecord> {
-    env.storage().persistent().get(key)
-}
+        Ok(())
+    }
 
-// ---------------------------------------------------------------------------
-// Tests
-// ---------------------------------------------------------------------------
+    /// Drop `user`'s scheduled plan change, if any.
+    pub fn cancel_scheduled_change(env: Env, user: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{
-        testutils::{Address as _, Ledger, LedgerInfo},
-        token::{StellarAssetClient, TokenClient},
-        Address, BytesN, Env,
-    };
+        user.require_auth();
 
-    // ------------------------------------------------------------------
-    // Test helpers
-    // ------------------------------------------------------------------
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingChange(user.clone()));
 
-    fn make_hash(env: &Env, seed: u8) -> BytesN<32> {
-        BytesN::from_array(env, &[seed; 32])
+        PlanChangeScheduled {
+            user,
+            target_plan_id: None,
+        }
+        .publish(&env);
+
+        Ok(())
     }
 
-    /// Deploy a fresh SEP-41 token contract and return its address plus an admin
-    /// client for minting.
-    fn create_token<'a>(env: &'a Env, token_admin: &Address) -> (Address, StellarAssetClient<'a>) {
-        let token_contract = env.register_stellar_asset_contract_v2(token_admin.clone());
-        let sac = StellarAssetClient::new(env, &token_contract.address());
-        (token_contract.address(), sac)
+    /// The plan `user`'s next `renew` will switch to, if one is scheduled.
+    pub fn pending_change(env: Env, user: Address) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingChange(user))
     }
 
-    /// Register a VipSubscription contract, initialize it, and return the client
-    /// plus supporting addresses. The treasury IS the token contract so that
-    /// we can verify token balances directly against the treasury address.
-    fn setup(
-        env: &Env,
-    ) -> (
-        VipSubscriptionClient,
-        Address,            // admin
-        Address,            // treasury (= token contract address)
-        StellarAssetClient, // token SAC for minting
-    ) {
-        let admin = Address::generate(env);
-        let token_admin = Address::generate(env);
+    // -----------------------------------------------------------------------
+    // set_proration
+    // -----------------------------------------------------------------------
 
-        let (treasury_addr, token_sac) = create_token(env, &token_admin);
+    /// Enable or disable value-preserving proration on cross-plan renewals.
+    /// Admin only. Disabled by default.
+    pub fn set_proration(env: Env, admin: Address, enabled: bool) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
 
-        let contract_id = env.register(VipSubscription, ());
-        let client = VipSubscriptionClient::new(env, &contract_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::ProrationEnabled, &enabled);
 
+        ProrationModeSet { enabled }.publish(&env);
+
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // trials
+    // -----------------------------------------------------------------------
+
+    /// Offer first-time subscribers of `plan_id` a trial of `trial_seconds`
+    /// for `trial_price`. Admin only. `trial_price` must be between 0 (free)
+    /// and the plan price; `trial_seconds == 0` removes the trial.
+    pub fn set_plan_trial(
+        env: Env,
+        admin: Address,
+        plan_id: u32,
+        trial_seconds: u64,
+        trial_price: i128,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+
+        let plan = require_plan_exists(&env, plan_id)?;
+        if trial_price < 0 || trial_price > plan.price {
+            return Err(Error::InvalidInput);
+        }
+
+        let key = DataKey::PlanTrial(plan_id);
+        if trial_seconds == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(
+                &key,
+                &TrialTerms {
+                    trial_seconds,
+                    trial_price,
+                },
+            );
+            env.storage().persistent().extend_ttl(
+                &key,
+                PERSISTENT_BUMP_LEDGERS,
+                PERSISTENT_BUMP_LEDGERS,
+            );
+        }
+
+        TrialConfigured {
+            plan_id,
+            trial_seconds,
+            trial_price,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Trial terms for `plan_id`, if any.
+    pub fn trial_terms(env: Env, plan_id: u32) -> Option<TrialTerms> {
+        env.storage().persistent().get(&DataKey::PlanTrial(plan_id))
+    }
+
+    /// Whether `user` has already subscribed to `plan_id` and so no longer
+    /// qualifies for its trial.
+    pub fn has_used_trial(env: Env, user: Address, plan_id: u32) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::UsedTrial(user, plan_id))
+    }
+
+    /// Grant `user` a free trial of `plan_id` lasting `trial_seconds`. Admin
+    /// only. No payment is taken and no revenue is recorded.
+    ///
+    /// Each user gets one trial per plan: rejected with `TrialAlreadyUsed`
+    /// if the user has already trialled or subscribed to the plan, and with
+    /// `AlreadySubscribed` while the user has an active subscription.
+    pub fn start_trial(
+        env: Env,
+        admin: Address,
+        user: Address,
+        plan_id: u32,
+        trial_seconds: u64,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+        require_plan_exists(&env, plan_id)?;
+
+        if trial_seconds == 0 {
+            return Err(Error::InvalidInput);
+        }
+
+        let used_key = DataKey::UsedTrial(user.clone(), plan_id);
+        if env.storage().persistent().has(&used_key) {
+            return Err(Error::TrialAlreadyUsed);
+        }
+
+        let now = env.ledger().timestamp();
+        let sub_key = DataKey::Subscription(user.clone());
+        let existing = get_subscription(&env, &sub_key);
+        if let Some(existing) = &existing {
+            if existing.expires_at > now {
+                return Err(Error::AlreadySubscribed);
+            }
+        }
+
+        let expires_at = now.checked_add(trial_seconds).ok_or(Error::Overflow)?;
+        if !existing.map(|r| r.counted_active).unwrap_or(false) {
+            adjust_active_count(&env, 1)?;
+        }
+
+        let record = SubscriptionRecord {
+            plan_id,
+            expires_at,
+            counted_active: true,
+        };
+        env.storage().persistent().set(&sub_key, &record);
+        env.storage().persistent().extend_ttl(
+            &sub_key,
+            PERSISTENT_BUMP_LEDGERS,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+        env.storage().persistent().set(&used_key, &true);
+        env.storage().persistent().extend_ttl(
+            &used_key,
+            PERSISTENT_BUMP_LEDGERS,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingChange(user.clone()));
+
+        TrialStarted {
+            user,
+            plan_id,
+            expires_at,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // reactivation offers
+    // -----------------------------------------------------------------------
+
+    /// Offer users whose subscription to `plan_id` lapsed within the last
+    /// `window_seconds` a `discount_bps` reduction when they `subscribe`
+    /// again. Admin only. `discount_bps` must be at most 10000 and
+    /// `window_seconds` non-zero; `discount_bps == 0` removes the offer.
+    pub fn set_reactivation_offer(
+        env: Env,
+        admin: Address,
+        plan_id: u32,
+        discount_bps: u32,
+        window_seconds: u64,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+        require_plan_exists(&env, plan_id)?;
+
+        let key = DataKey::PlanReactivation(plan_id);
+        if discount_bps == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            if discount_bps > 10_000 || window_seconds == 0 {
+                return Err(Error::InvalidInput);
+            }
+            env.storage().persistent().set(
+                &key,
+                &ReactivationOffer {
+                    discount_bps,
+                    window_seconds,
+                },
+            );
+            env.storage().persistent().extend_ttl(
+                &key,
+                PERSISTENT_BUMP_LEDGERS,
+                PERSISTENT_BUMP_LEDGERS,
+            );
+        }
+
+        ReactivationOfferSet {
+            plan_id,
+            discount_bps,
+            window_seconds,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Reactivation offer for `plan_id`, if any.
+    pub fn reactivation_offer(env: Env, plan_id: u32) -> Option<ReactivationOffer> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PlanReactivation(plan_id))
+    }
+
+    // -----------------------------------------------------------------------
+    // renew window
+    // -----------------------------------------------------------------------
+
+    /// Only allow active subscriptions to `plan_id` to be renewed in the last
+    /// `renew_window_seconds` before expiry. Admin only. `0` removes the
+    /// restriction.
+    pub fn set_renew_window(
+        env: Env,
+        admin: Address,
+        plan_id: u32,
+        renew_window_seconds: u64,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+        require_plan_exists(&env, plan_id)?;
+
+        let key = DataKey::PlanRenewWindow(plan_id);
+        if renew_window_seconds == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &renew_window_seconds);
+            env.storage().persistent().extend_ttl(
+                &key,
+                PERSISTENT_BUMP_LEDGERS,
+                PERSISTENT_BUMP_LEDGERS,
+            );
+        }
+
+        RenewWindowSet {
+            plan_id,
+            renew_window_seconds,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Renew window for `plan_id` in seconds; `0` when renewal is always
+    /// allowed.
+    pub fn renew_window(env: Env, plan_id: u32) -> u64 {
+        renew_window(&env, plan_id)
+    }
+
+    // -----------------------------------------------------------------------
+    // token allowlist
+    // -----------------------------------------------------------------------
+
+    /// Add `token` to, or remove it from, the allowlist of tokens new plans
+    /// may be priced in. Admin only. Removing a token does not affect plans
+    /// already defined in it.
+    pub fn set_token_allowed(
+        env: Env,
+        admin: Address,
+        token: Address,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+
+        let key = DataKey::AllowedToken(token.clone());
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(
+                &key,
+                PERSISTENT_BUMP_LEDGERS,
+                PERSISTENT_BUMP_LEDGERS,
+            );
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        TokenAllowlistUpdated { token, allowed }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Whether `token` is on the allowlist for pricing new plans.
+    pub fn is_token_allowed(env: Env, token: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AllowedToken(token))
+            .unwrap_or(false)
+    }
+
+    // -----------------------------------------------------------------------
+    // transfer_subscription
+    // -----------------------------------------------------------------------
+
+    /// Move `from`'s active subscription to `to`, e.g. when a user changes
+    /// wallets. The record is moved as-is: `to` inherits the exact `plan_id`
+    /// and `expires_at`, and `from` is left with no record. No payment is taken.
+    ///
+    /// Rejected with `NoActiveSubscription` if `from` has no active
+    /// subscription, and with `AlreadySubscribed` if `to` already holds an
+    /// active subscription (a user has a single record, so it would be lost).
+    pub fn transfer_subscription(env: Env, from: Address, to: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        from.require_auth();
+
+        if from == to {
+            return Err(Error::InvalidInput);
+        }
+
+        let now = env.ledger().timestamp();
+        let from_key = DataKey::Subscription(from.clone());
+        let record = get_subscription(&env, &from_key)
+            .filter(|r| r.expires_at > now)
+            .ok_or(Error::NoActiveSubscription)?;
+
+        let to_key = DataKey::Subscription(to.clone());
+        if let Some(existing) = get_subscription(&env, &to_key) {
+            if existing.expires_at > now {
+                return Err(Error::AlreadySubscribed);
+            }
+            // The overwritten expired record drops out of the count.
+            if existing.counted_active {
+                adjust_active_count(&env, -1)?;
+            }
+        }
+
+        env.storage().persistent().remove(&from_key);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingChange(from.clone()));
+        env.storage().persistent().set(&to_key, &record);
+        env.storage().persistent().extend_ttl(
+            &to_key,
+            PERSISTENT_BUMP_LEDGERS,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        SubscriptionTransferred {
+            from,
+            to,
+            plan_id: record.plan_id,
+            expires_at: record.expires_at,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // expiry reminders
+    // -----------------------------------------------------------------------
+
+    /// Emit `ExpiringSoon` if `user`'s subscription is active and expires
+    /// within `window_seconds`, so a backend can schedule a renewal reminder.
+    /// Callable by anyone. Fires at most once per subscription period: later
+    /// pokes return `false` until a renewal or new subscription changes
+    /// `expires_at`. Returns whether the event was emitted.
+    pub fn poke_expiring_soon(env: Env, user: Address, window_seconds: u64) -> Result<bool, Error> {
+        require_initialized(&env)?;
+
+        let Some(record) = get_subscription(&env, &DataKey::Subscription(user.clone())) else {
+            return Ok(false);
+        };
+        let now = env.ledger().timestamp();
+        if record.expires_at <= now || record.expires_at - now > window_seconds {
+            return Ok(false);
+        }
+
+        let notified_key = DataKey::ExpiryNotified(user.clone());
+        let notified: Option<u64> = env.storage().persistent().get(&notified_key);
+        if notified == Some(record.expires_at) {
+            return Ok(false);
+        }
+        env.storage()
+            .persistent()
+            .set(&notified_key, &record.expires_at);
+        env.storage().persistent().extend_ttl(
+            &notified_key,
+            PERSISTENT_BUMP_LEDGERS,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+
+        ExpiringSoon {
+            user,
+            plan_id: record.plan_id,
+            expires_at: record.expires_at,
+        }
+        .publish(&env);
+
+        Ok(true)
+    }
+
+    // -----------------------------------------------------------------------
+    // sweep_expired / metrics
+    // -----------------------------------------------------------------------
+
+    /// Remove expired subscriptions of `users` from the active count.
+    /// Callable by anyone; records themselves are kept. Returns how many
+    /// records were newly marked as lapsed.
+    pub fn sweep_expired(env: Env, users: Vec<Address>) -> Result<u32, Error> {
+        require_initialized(&env)?;
+
+        let now = env.ledger().timestamp();
+        let mut swept: u32 = 0;
+        for user in users.iter() {
+            let sub_key = DataKey::Subscription(user);
+            if let Some(mut record) = get_subscription(&env, &sub_key) {
+                if record.counted_active && record.expires_at <= now {
+                    record.counted_active = false;
+                    env.storage().persistent().set(&sub_key, &record);
+                    swept += 1;
+                }
+            }
+        }
+        if swept > 0 {
+            adjust_active_count(&env, -(swept as i64))?;
+        }
+
+        Ok(swept)
+    }
+
+    /// Return `(active_count, total_revenue)`.
+    ///
+    /// `total_revenue` is exact: the sum of every price paid through
+    /// `subscribe` and `renew`, added as raw amounts regardless of which
+    /// token a plan is priced in. `active_count` is an approximation:
+    /// subscriptions are counted when they start but only leave the count
+    /// once `sweep_expired` sees them expired (or an expired record is
+    /// overwritten by a transfer), so it over-counts until a sweep runs.
+    pub fn metrics(env: Env) -> (u32, i128) {
+        let active: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ActiveCount)
+            .unwrap_or(0);
+        let revenue: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalRevenue)
+            .unwrap_or(0);
+        (active, revenue)
+    }
+
+    /// Lifetime revenue collected for `plan_id`, in that plan's token.
+    /// Every subscribe and renew credits the plan it charged, so a
+    /// cross-plan renewal counts toward the new plan.
+    pub fn plan_revenue(env: Env, plan_id: u32) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PlanRevenue(plan_id))
+            .unwrap_or(0)
+    }
+
+    // -----------------------------------------------------------------------
+    // status_of
+    // -----------------------------------------------------------------------
+
+    /// Return the subscription status for `user`.
+    ///
+    /// Returns a `SubscriptionStatus` with `has_subscription = false` if the
+    /// user has never subscribed. If a record exists, `is_active` reflects
+    /// whether the current ledger timestamp is before `expires_at`.
+    pub fn status_of(env: Env, user: Address) -> SubscriptionStatus {
+        let sub_key = DataKey::Subscription(user);
+        match get_subscription(&env, &sub_key) {
+            None => SubscriptionStatus {
+                has_subscription: false,
+                plan_id: 0,
+                expires_at: 0,
+                is_active: false,
+            },
+            Some(record) => {
+                let now = env.ledger().timestamp();
+                SubscriptionStatus {
+                    has_subscription: true,
+                    plan_id: record.plan_id,
+                    expires_at: record.expires_at,
+                    is_active: record.expires_at > now,
+                }
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn require_initialized(env: &Env) -> Result<(), Error> {
+    if !env.storage().instance().has(&DataKey::Admin) {
+        return Err(Error::NotInitialized);
+    }
+    Ok(())
+}
+
+/// Verify that `caller` is the stored admin and has signed the invocation.
+fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(Error::NotInitialized)?;
+    caller.require_auth();
+    if caller != &admin {
+        return Err(Error::NotAuthorized);
+    }
+    Ok(())
+}
+
+/// Check that a plan token answers the token interface and is allowlisted.
+fn validate_plan_token(env: &Env, token: &Address) -> Result<(), Error> {
+    // Reject addresses that do not answer the token interface.
+    if TokenClient::new(env, token).try_decimals().is_err() {
+        return Err(Error::InvalidInput);
+    }
+    if !VipSubscription::is_token_allowed(env.clone(), token.clone()) {
+        return Err(Error::TokenNotAllowed);
+    }
+    Ok(())
+}
+
+/// Validate and persist a new plan, then emit `PlanDefined`. The caller is
+/// responsible for admin and token checks.
+fn store_plan(
+    env: &Env,
+    plan_id: u32,
+    price: i128,
+    token: Address,
+    duration: u64,
+    benefits_hash: BytesN<32>,
+) -> Result<(), Error> {
+    if price <= 0 {
+        return Err(Error::InvalidInput);
+    }
+    if duration == 0 {
+        return Err(Error::InvalidInput);
+    }
+
+    let key = DataKey::Plan(plan_id);
+    if env.storage().persistent().has(&key) {
+        return Err(Error::PlanAlreadyExists);
+    }
+
+    let plan = PlanDefinition {
+        price,
+        token: token.clone(),
+        duration,
+        benefits_hash: benefits_hash.clone(),
+    };
+    env.storage().persistent().set(&key, &plan);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+
+    PlanDefined {
+        plan_id,
+        price,
+        token,
+        duration,
+        benefits_hash,
+    }
+    .publish(env);
+
+    Ok(())
+}
+
+/// Fetch the plan definition or return `PlanNotFound`.
+fn require_plan_exists(env: &Env, plan_id: u32) -> Result<PlanDefinition, Error> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Plan(plan_id))
+        .ok_or(Error::PlanNotFound)
+}
+
+fn get_treasury(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Treasury)
+        .expect("VipSubscription: treasury not set")
+}
+
+fn get_subscription(env: &Env, key: &DataKey) -> Option<SubscriptionRecord> {
+    env.storage().persistent().get(key)
+}
+
+fn is_proration_enabled(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::ProrationEnabled)
+        .unwrap_or(false)
+}
+
+fn renew_window(env: &Env, plan_id: u32) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlanRenewWindow(plan_id))
+        .unwrap_or(0)
+}
+
+fn adjust_active_count(env: &Env, delta: i64) -> Result<(), Error> {
+    let current: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::ActiveCount)
+        .unwrap_or(0);
+    let updated = u32::try_from(current as i64 + delta).map_err(|_| Error::Overflow)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::ActiveCount, &updated);
+    Ok(())
+}
+
+/// Credit `amount` to the contract-wide total and to `plan_id`, the plan
+/// actually charged.
+fn add_revenue(env: &Env, plan_id: u32, amount: i128) -> Result<(), Error> {
+    let current: i128 = env
+        .storage()
+        .instance()
+        .get(&DataKey::TotalRevenue)
+        .unwrap_or(0);
+    let updated = current.checked_add(amount).ok_or(Error::Overflow)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::TotalRevenue, &updated);
+
+    let plan_key = DataKey::PlanRevenue(plan_id);
+    let lifetime_revenue = env
+        .storage()
+        .persistent()
+        .get::<_, i128>(&plan_key)
+        .unwrap_or(0)
+        .checked_add(amount)
+        .ok_or(Error::Overflow)?;
+    env.storage().persistent().set(&plan_key, &lifetime_revenue);
+    env.storage().persistent().extend_ttl(
+        &plan_key,
+        PERSISTENT_BUMP_LEDGERS,
+        PERSISTENT_BUMP_LEDGERS,
+    );
+
+    PlanRevenueRecorded {
+        plan_id,
+        amount,
+        lifetime_revenue,
+    }
+    .publish(env);
+
+    Ok(())
+}
+
+/// Convert `remaining` seconds on `from` into seconds of equal value on `to`.
+///
+/// value = remaining * from.price / from.duration
+/// time  = value * to.duration / to.price
+///
+/// Both steps are folded into a single division so no value is lost to an
+/// intermediate rounding.
+fn prorate_remaining(
+    remaining: u64,
+    from: &PlanDefinition,
+    to: &PlanDefinition,
+) -> Result<u64, Error> {
+    let numerator = (remaining as i128)
+        .checked_mul(from.price)
+        .and_then(|v| v.checked_mul(to.duration as i128))
+        .ok_or(Error::Overflow)?;
+    let denominator = (from.duration as i128)
+        .checked_mul(to.price)
+        .ok_or(Error::Overflow)?;
+    let carried = numerator.checked_div(denominator).ok_or(Error::Overflow)?;
+    u64::try_from(carried).map_err(|_| Error::Overflow)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger, LedgerInfo},
+        token::{StellarAssetClient, TokenClient},
+        vec, Address, BytesN, Env, Event as _,
+    };
+
+    // ------------------------------------------------------------------
+    // Test helpers
+    // ------------------------------------------------------------------
+
+    fn make_hash(env: &Env, seed: u8) -> BytesN<32> {
+        BytesN::from_array(env, &[seed; 32])
+    }
+
+    /// Deploy a fresh SEP-41 token contract and return its address plus an admin
+    /// client for minting.
+    fn create_token<'a>(env: &'a Env, token_admin: &Address) -> (Address, StellarAssetClient<'a>) {
+        let token_contract = env.register_stellar_asset_contract_v2(token_admin.clone());
+        let sac = StellarAssetClient::new(env, &token_contract.address());
+        (token_contract.address(), sac)
+    }
+
+    /// Register a VipSubscription contract, initialize it, and return the client
+    /// plus supporting addresses. The treasury IS the token contract so that
+    /// we can verify token balances directly against the treasury address.
+    fn setup(
+        env: &Env,
+    ) -> (
+        VipSubscriptionClient,
+        Address,            // admin
+        Address,            // treasury (= token contract address)
+        StellarAssetClient, // token SAC for minting
+    ) {
+        let admin = Address::generate(env);
+        let token_admin = Address::generate(env);
+
+        let (treasury_addr, token_sac) = create_token(env, &token_admin);
+
+        let contract_id = env.register(VipSubscription, ());
+        let client = VipSubscriptionClient::new(env, &contract_id);
+
+        env.mock_all_auths();
+        client.init(&admin, &treasury_addr);
+        client.set_token_allowed(&admin, &token_sac.address, &true);
+
+        (client, admin, treasury_addr, token_sac)
+    }
+
+    /// Read a plan definition straight from contract storage.
+    fn stored_plan(
+        env: &Env,
+        client: &VipSubscriptionClient,
+        plan_id: u32,
+    ) -> Option<PlanDefinition> {
+        env.as_contract(&client.address, || {
+            env.storage().persistent().get(&DataKey::Plan(plan_id))
+        })
+    }
+
+    /// Set the ledger timestamp to `ts`.
+    fn set_time(env: &Env, ts: u64) {
+        env.ledger().set(LedgerInfo {
+            timestamp: ts,
+            protocol_version: 25,
+            sequence_number: env.ledger().sequence(),
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 1,
+            min_persistent_entry_ttl: 1,
+            max_entry_ttl: 6_312_000,
+        });
+    }
+
+    // ------------------------------------------------------------------
+    // 1. init
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_init_rejects_reinit() {
+        let env = Env::default();
+        let (client, admin, treasury, _) = setup(&env);
+        env.mock_all_auths();
+
+        let result = client.try_init(&admin, &treasury);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_uninit_calls_rejected() {
+        let env = Env::default();
+        let contract_id = env.register(VipSubscription, ());
+        let client = VipSubscriptionClient::new(&env, &contract_id);
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let hash = make_hash(&env, 1);
+        assert!(client
+            .try_define_plan(
+                &admin,
+                &1u32,
+                &100i128,
+                &Address::generate(&env),
+                &86400u64,
+                &hash
+            )
+            .is_err());
+    }
+
+    // ------------------------------------------------------------------
+    // 2. define_plan
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_define_plan_success() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let hash = make_hash(&env, 1);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &1000i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
+        // No panic = success
+    }
+
+    #[test]
+    fn test_define_plan_duplicate_rejected() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let hash = make_hash(&env, 2);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &1000i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
+
+        let result = client.try_define_plan(
+            &admin,
+            &1u32,
+            &1000i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_define_plans_defines_each() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let plans = vec![
+            &env,
+            (1u32, 100i128, 86_400u64, make_hash(&env, 1)),
+            (2u32, 250i128, 604_800u64, make_hash(&env, 2)),
+            (3u32, 900i128, 2_592_000u64, make_hash(&env, 3)),
+        ];
+        client.define_plans(&admin, &token_sac.address, &plans);
+
+        let events = env.events().all().filter_by_contract(&client.address);
+        assert_eq!(events.events().len(), 3);
+        for (i, (plan_id, price, duration, benefits_hash)) in plans.iter().enumerate() {
+            assert_eq!(
+                events.events()[i],
+                PlanDefined {
+                    plan_id,
+                    price,
+                    token: token_sac.address.clone(),
+                    duration,
+                    benefits_hash: benefits_hash.clone(),
+                }
+                .to_xdr(&env, &client.address)
+            );
+            let plan = stored_plan(&env, &client, plan_id).unwrap();
+            assert_eq!(plan.price, price);
+            assert_eq!(plan.duration, duration);
+            assert_eq!(plan.benefits_hash, benefits_hash);
+        }
+    }
+
+    #[test]
+    fn test_define_plans_collision_defines_none() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        client.define_plan(
+            &admin,
+            &2u32,
+            &1000i128,
+            &token_sac.address,
+            &86400u64,
+            &make_hash(&env, 9),
+        );
+
+        let plans = vec![
+            &env,
+            (1u32, 100i128, 86_400u64, make_hash(&env, 1)),
+            (2u32, 250i128, 604_800u64, make_hash(&env, 2)),
+            (3u32, 900i128, 2_592_000u64, make_hash(&env, 3)),
+        ];
+        let result = client.try_define_plans(&admin, &token_sac.address, &plans);
+        assert_eq!(result, Err(Ok(Error::PlanAlreadyExists)));
+
+        assert!(stored_plan(&env, &client, 1).is_none());
+        assert!(stored_plan(&env, &client, 3).is_none());
+        assert_eq!(stored_plan(&env, &client, 2).unwrap().price, 1000);
+    }
+
+    #[test]
+    fn test_define_plan_zero_price_rejected() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let hash = make_hash(&env, 3);
+        let result =
+            client.try_define_plan(&admin, &1u32, &0i128, &token_sac.address, &86400u64, &hash);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_define_plan_negative_price_rejected() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let hash = make_hash(&env, 4);
+        let result =
+            client.try_define_plan(&admin, &1u32, &-1i128, &token_sac.address, &86400u64, &hash);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_define_plan_zero_duration_rejected() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let hash = make_hash(&env, 5);
+        let result =
+            client.try_define_plan(&admin, &1u32, &1000i128, &token_sac.address, &0u64, &hash);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_define_plan_non_admin_rejected() {
+        let env = Env::default();
+        let (client, _, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let non_admin = Address::generate(&env);
+        let hash = make_hash(&env, 6);
+        let result = client.try_define_plan(
+            &non_admin,
+            &1u32,
+            &1000i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_define_plan_non_token_rejected() {
+        let env = Env::default();
+        let (client, admin, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let not_a_token = Address::generate(&env);
+        let hash = make_hash(&env, 40);
+        let result =
+            client.try_define_plan(&admin, &1u32, &100i128, &not_a_token, &86400u64, &hash);
+        assert_eq!(result, Err(Ok(Error::InvalidInput)));
+    }
+
+    // ------------------------------------------------------------------
+    // 3. subscribe
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_subscribe_success() {
+        let env = Env::default();
+        let (client, admin, treasury, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let hash = make_hash(&env, 7);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &500i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
+
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &500i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32);
+
+        let status = client.status_of(&user);
+        assert!(status.has_subscription);
+        assert_eq!(status.plan_id, 1);
+        assert_eq!(status.expires_at, 1_000_000 + 86_400);
+        assert!(status.is_active);
+
+        // Treasury received the payment.
+        let tc = TokenClient::new(&env, &treasury);
+        assert_eq!(tc.balance(&treasury), 500);
+    }
+
+    #[test]
+    fn test_subscribe_unknown_plan_rejected() {
+        let env = Env::default();
+        let (client, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let result = client.try_subscribe(&user, &999u32);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_subscribe_duplicate_active_rejected() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let hash = make_hash(&env, 8);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
+
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &1000i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32);
+
+        // Second subscribe while active — must fail.
+        let result = client.try_subscribe(&user, &1u32);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_subscribe_after_expiry_succeeds() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
-        client.init(&admin, &treasury_addr);
 
-        (client, admin, treasury_addr, token_sac)
+        let duration: u64 = 86_400;
+        let hash = make_hash(&env, 9);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &hash,
+        );
+
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &1000i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32);
+
+        // Advance past expiry.
+        set_time(&env, 1_000_000 + duration + 1);
+        client.subscribe(&user, &1u32);
+
+        let status = client.status_of(&user);
+        assert!(status.is_active);
+        assert_eq!(status.expires_at, 1_000_000 + duration + 1 + duration);
     }
 
-    /// Set the ledger timestamp to `ts`.
-    fn set_time(env: &Env, ts: u64) {
-        env.ledger().set(LedgerInfo {
-            timestamp: ts,
-            protocol_version: 25,
-            sequence_number: env.ledger().sequence(),
-            network_id: Default::default(),
-            base_reserve: 10,
-            min_temp_entry_ttl: 1,
-            min_persistent_entry_ttl: 1,
-            max_entry_ttl: 6_312_000,
-        });
+    #[test]
+    fn test_reactivation_discount_for_recently_lapsed_users() {
+        let env = Env::default();
+        let (client, admin, treasury, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let duration: u64 = 86_400;
+        let window: u64 = 7 * 86_400;
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &make_hash(&env, 40),
+        );
+        client.set_reactivation_offer(&admin, &1u32, &2_000u32, &window);
+        assert_eq!(
+            client.reactivation_offer(&1u32),
+            Some(ReactivationOffer {
+                discount_bps: 2_000,
+                window_seconds: window,
+            })
+        );
+
+        let lapsed = Address::generate(&env);
+        let fresh = Address::generate(&env);
+        let long_gone = Address::generate(&env);
+        for user in [&lapsed, &fresh, &long_gone] {
+            token_sac.mint(user, &1000i128);
+        }
+        let tc = TokenClient::new(&env, &treasury);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&lapsed, &1u32);
+        client.subscribe(&long_gone, &1u32);
+        assert_eq!(tc.balance(&treasury), 200);
+
+        // Lapsed within the window: 20% off.
+        let t = 1_000_000 + duration + window;
+        set_time(&env, t);
+        client.subscribe(&lapsed, &1u32);
+        assert_eq!(tc.balance(&treasury), 280);
+        let events = env.events().all().filter_by_contract(&client.address);
+        assert_eq!(
+            events.events().last().unwrap(),
+            &Subscribed {
+                user: lapsed.clone(),
+                plan_id: 1,
+                expires_at: t + duration,
+                amount_paid: 80,
+            }
+            .to_xdr(&env, &client.address)
+        );
+
+        // A brand-new subscriber has no lapsed record and pays full price.
+        client.subscribe(&fresh, &1u32);
+        assert_eq!(tc.balance(&treasury), 380);
+
+        // Lapsed beyond the window: full price.
+        set_time(&env, t + 1);
+        client.subscribe(&long_gone, &1u32);
+        assert_eq!(tc.balance(&treasury), 480);
     }
 
-    // ------------------------------------------------------------------
-    // 1. init
-    // ------------------------------------------------------------------
+    #[test]
+    fn test_set_reactivation_offer_validates_input() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &86_400u64,
+            &make_hash(&env, 41),
+        );
+        assert_eq!(
+            client.try_set_reactivation_offer(&admin, &1u32, &10_001u32, &60u64),
+            Err(Ok(Error::InvalidInput))
+        );
+        assert_eq!(
+            client.try_set_reactivation_offer(&admin, &1u32, &500u32, &0u64),
+            Err(Ok(Error::InvalidInput))
+        );
+        assert_eq!(
+            client.try_set_reactivation_offer(&admin, &2u32, &500u32, &60u64),
+            Err(Ok(Error::PlanNotFound))
+        );
+
+        client.set_reactivation_offer(&admin, &1u32, &500u32, &60u64);
+        client.set_reactivation_offer(&admin, &1u32, &0u32, &0u64);
+        assert_eq!(client.reactivation_offer(&1u32), None);
+    }
 
     #[test]
-    fn test_init_rejects_reinit() {
+    fn test_poke_expiring_soon_fires_once_per_period() {
         let env = Env::default();
-        let (client, admin, treasury, _) = setup(&env);
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let result = client.try_init(&admin, &treasury);
-        assert!(result.is_err());
+        let duration: u64 = 30 * 86_400;
+        let window: u64 = 3 * 86_400;
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &make_hash(&env, 42),
+        );
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &1000i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32);
+        let expires_at = 1_000_000 + duration;
+        let reminder = ExpiringSoon {
+            user: user.clone(),
+            plan_id: 1,
+            expires_at,
+        }
+        .to_xdr(&env, &client.address);
+
+        // Outside the window: no event.
+        set_time(&env, expires_at - window - 1);
+        assert!(!client.poke_expiring_soon(&user, &window));
+        let events = env.events().all().filter_by_contract(&client.address);
+        assert!(events.events().is_empty());
+
+        // Inside the window: one event, and only once.
+        set_time(&env, expires_at - window);
+        assert!(client.poke_expiring_soon(&user, &window));
+        let events = env.events().all().filter_by_contract(&client.address);
+        assert_eq!(events.events().last().unwrap(), &reminder);
+        assert!(!client.poke_expiring_soon(&user, &window));
+        let events = env.events().all().filter_by_contract(&client.address);
+        assert!(events.events().is_empty());
+
+        // Renewing starts a new period that can be reminded about again.
+        client.renew(&user, &1u32);
+        set_time(&env, expires_at + duration - 1);
+        assert!(client.poke_expiring_soon(&user, &window));
+
+        // Expired subscriptions are never reminded about.
+        set_time(&env, expires_at + duration);
+        assert!(!client.poke_expiring_soon(&user, &window));
     }
 
     #[test]
-    fn test_uninit_calls_rejected() {
+    fn test_poke_expiring_soon_ignores_expired_and_missing() {
         let env = Env::default();
-        let contract_id = env.register(VipSubscription, ());
-        let client = VipSubscriptionClient::new(&env, &contract_id);
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let admin = Address::generate(&env);
-        let hash = make_hash(&env, 1);
-        assert!(client
-            .try_define_plan(&admin, &1u32, &100i128, &86400u64, &hash)
-            .is_err());
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &86_400u64,
+            &make_hash(&env, 43),
+        );
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &1000i128);
+        assert!(!client.poke_expiring_soon(&user, &86_400u64));
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32);
+        set_time(&env, 1_000_000 + 86_400 + 1);
+        assert!(!client.poke_expiring_soon(&user, &u64::MAX));
     }
 
-    // ------------------------------------------------------------------
-    // 2. define_plan
-    // ------------------------------------------------------------------
+    #[test]
+    fn test_subscribe_charges_plan_token() {
+        let env = Env::default();
+        let (client, admin, treasury, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let (premium_token, premium_sac) = create_token(&env, &Address::generate(&env));
+        client.set_token_allowed(&admin, &premium_token, &true);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &86400u64,
+            &make_hash(&env, 41),
+        );
+        client.define_plan(
+            &admin,
+            &2u32,
+            &7i128,
+            &premium_token,
+            &86400u64,
+            &make_hash(&env, 42),
+        );
+
+        let basic_user = Address::generate(&env);
+        let premium_user = Address::generate(&env);
+        token_sac.mint(&basic_user, &100i128);
+        premium_sac.mint(&premium_user, &7i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&basic_user, &1u32);
+        client.subscribe(&premium_user, &2u32);
+
+        let base = TokenClient::new(&env, &token_sac.address);
+        let premium = TokenClient::new(&env, &premium_token);
+        assert_eq!(base.balance(&treasury), 100);
+        assert_eq!(premium.balance(&treasury), 7);
+        assert_eq!(base.balance(&premium_user), 0);
+        assert_eq!(premium.balance(&premium_user), 0);
+
+        // Renewal charges the renewed plan's token as well.
+        premium_sac.mint(&premium_user, &7i128);
+        client.renew(&premium_user, &2u32);
+        assert_eq!(premium.balance(&treasury), 14);
+        assert_eq!(base.balance(&treasury), 100);
+    }
 
     #[test]
-    fn test_define_plan_success() {
+    fn test_define_plan_requires_allowlisted_token() {
         let env = Env::default();
         let (client, admin, _, _) = setup(&env);
         env.mock_all_auths();
 
-        let hash = make_hash(&env, 1);
-        client.define_plan(&admin, &1u32, &1000i128, &86400u64, &hash);
-        // No panic = success
+        let (other_token, other_sac) = create_token(&env, &Address::generate(&env));
+        let hash = make_hash(&env, 43);
+        assert!(!client.is_token_allowed(&other_token));
+        assert_eq!(
+            client.try_define_plan(&admin, &1u32, &100i128, &other_token, &86400u64, &hash),
+            Err(Ok(Error::TokenNotAllowed))
+        );
+
+        client.set_token_allowed(&admin, &other_token, &true);
+        client.define_plan(&admin, &1u32, &100i128, &other_token, &86400u64, &hash);
+
+        // Delisting blocks new plans but leaves existing ones usable.
+        client.set_token_allowed(&admin, &other_token, &false);
+        assert_eq!(
+            client.try_define_plan(&admin, &2u32, &100i128, &other_token, &86400u64, &hash),
+            Err(Ok(Error::TokenNotAllowed))
+        );
+        let user = Address::generate(&env);
+        other_sac.mint(&user, &100i128);
+        client.subscribe(&user, &1u32);
+        assert!(client.status_of(&user).is_active);
     }
 
+    // ------------------------------------------------------------------
+    // 4. renew
+    // ------------------------------------------------------------------
+
     #[test]
-    fn test_define_plan_duplicate_rejected() {
+    fn test_renew_active_subscription_stacks() {
         let env = Env::default();
-        let (client, admin, _, _) = setup(&env);
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let hash = make_hash(&env, 2);
-        client.define_plan(&admin, &1u32, &1000i128, &86400u64, &hash);
+        let duration: u64 = 86_400;
+        let hash = make_hash(&env, 10);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &hash,
+        );
 
-        let result = client.try_define_plan(&admin, &1u32, &1000i128, &86400u64, &hash);
-        assert!(result.is_err());
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &1000i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32);
+
+        // Renew while still active — expiry extends from original expires_at.
+        set_time(&env, 1_000_000 + 1000);
+        client.renew(&user, &1u32);
+
+        let status = client.status_of(&user);
+        // base = 1_000_000 + 86_400; new expiry = base + 86_400
+        assert_eq!(status.expires_at, 1_000_000 + duration + duration);
+        assert!(status.is_active);
     }
 
     #[test]
-    fn test_define_plan_zero_price_rejected() {
+    fn test_renew_expired_subscription_reactivates() {
         let env = Env::default();
-        let (client, admin, _, _) = setup(&env);
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let hash = make_hash(&env, 3);
-        let result = client.try_define_plan(&admin, &1u32, &0i128, &86400u64, &hash);
-        assert!(result.is_err());
+        let duration: u64 = 86_400;
+        let hash = make_hash(&env, 11);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &hash,
+        );
+
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &1000i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32);
+
+        // Advance past expiry.
+        let renew_at = 1_000_000 + duration + 500;
+        set_time(&env, renew_at);
+        client.renew(&user, &1u32);
+
+        let status = client.status_of(&user);
+        assert_eq!(status.expires_at, renew_at + duration);
+        assert!(status.is_active);
     }
 
     #[test]
-    fn test_define_plan_negative_price_rejected() {
+    fn test_renew_window_restricts_early_renewal() {
         let env = Env::default();
-        let (client, admin, _, _) = setup(&env);
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let hash = make_hash(&env, 4);
-        let result = client.try_define_plan(&admin, &1u32, &-1i128, &86400u64, &hash);
-        assert!(result.is_err());
+        let duration: u64 = 86_400;
+        let hash = make_hash(&env, 13);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &hash,
+        );
+        client.set_renew_window(&admin, &1u32, &3_600u64);
+        assert_eq!(client.renew_window(&1u32), 3_600);
+
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &1000i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32);
+        let expires_at = 1_000_000 + duration;
+
+        // More than an hour before expiry is too early.
+        set_time(&env, expires_at - 3_601);
+        assert_eq!(
+            client.try_renew(&user, &1u32),
+            Err(Ok(Error::RenewTooEarly))
+        );
+        assert_eq!(client.status_of(&user).expires_at, expires_at);
+
+        // Exactly at the window boundary renewal is allowed and stacks.
+        set_time(&env, expires_at - 3_600);
+        client.renew(&user, &1u32);
+        assert_eq!(client.status_of(&user).expires_at, expires_at + duration);
     }
 
     #[test]
-    fn test_define_plan_zero_duration_rejected() {
+    fn test_renew_window_ignored_for_expired_subscription() {
         let env = Env::default();
-        let (client, admin, _, _) = setup(&env);
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let hash = make_hash(&env, 5);
-        let result = client.try_define_plan(&admin, &1u32, &1000i128, &0u64, &hash);
-        assert!(result.is_err());
+        let duration: u64 = 86_400;
+        let hash = make_hash(&env, 14);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &hash,
+        );
+        client.set_renew_window(&admin, &1u32, &60u64);
+
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &1000i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32);
+
+        let renew_at = 1_000_000 + duration + 10_000;
+        set_time(&env, renew_at);
+        client.renew(&user, &1u32);
+        assert_eq!(client.status_of(&user).expires_at, renew_at + duration);
+
+        // Clearing the window allows renewing at any time again.
+        client.set_renew_window(&admin, &1u32, &0u64);
+        assert_eq!(client.renew_window(&1u32), 0);
+        client.renew(&user, &1u32);
+        assert_eq!(
+            client.status_of(&user).expires_at,
+            renew_at + duration + duration
+        );
     }
 
     #[test]
-    fn test_define_plan_non_admin_rejected() {
+    fn test_renew_no_subscription_rejected() {
         let env = Env::default();
-        let (client, _, _, _) = setup(&env);
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let non_admin = Address::generate(&env);
-        let hash = make_hash(&env, 6);
-        let result = client.try_define_plan(&non_admin, &1u32, &1000i128, &86400u64, &hash);
+        let hash = make_hash(&env, 12);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
+
+        let user = Address::generate(&env);
+        let result = client.try_renew(&user, &1u32);
         assert!(result.is_err());
     }
 
-    // ------------------------------------------------------------------
-    // 3. subscribe
-    // ------------------------------------------------------------------
+    #[test]
+    fn test_renew_charges_user() {
+        let env = Env::default();
+        let (client, admin, treasury, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let hash = make_hash(&env, 13);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &300i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
+
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &1000i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32); // pays 300
+        client.renew(&user, &1u32); // pays another 300
+
+        let tc = TokenClient::new(&env, &treasury);
+        assert_eq!(tc.balance(&treasury), 600);
+    }
 
     #[test]
-    fn test_subscribe_success() {
+    fn test_scheduled_downgrade_applies_at_renewal() {
         let env = Env::default();
         let (client, admin, treasury, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let hash = make_hash(&env, 7);
-        client.define_plan(&admin, &1u32, &500i128, &86400u64, &hash);
+        let duration: u64 = 86_400;
+        client.define_plan(
+            &admin,
+            &1u32,
+            &300i128,
+            &token_sac.address,
+            &duration,
+            &make_hash(&env, 40),
+        );
+        client.define_plan(
+            &admin,
+            &2u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &make_hash(&env, 41),
+        );
 
         let user = Address::generate(&env);
-        token_sac.mint(&user, &500i128);
-
+        token_sac.mint(&user, &1000i128);
         set_time(&env, 1_000_000);
         client.subscribe(&user, &1u32);
 
+        assert_eq!(
+            client.try_schedule_downgrade(&user, &1u32),
+            Err(Ok(Error::InvalidInput))
+        );
+        client.schedule_downgrade(&user, &2u32);
+        assert_eq!(client.pending_change(&user), Some(2));
+
+        // Nothing changes until the renewal.
         let status = client.status_of(&user);
-        assert!(status.has_subscription);
         assert_eq!(status.plan_id, 1);
-        assert_eq!(status.expires_at, 1_000_000 + 86_400);
-        assert!(status.is_active);
+        assert_eq!(status.expires_at, 1_000_000 + duration);
+
+        client.renew(&user, &1u32);
+        let status = client.status_of(&user);
+        assert_eq!(status.plan_id, 2);
+        assert_eq!(status.expires_at, 1_000_000 + 2 * duration);
+        assert_eq!(client.pending_change(&user), None);
 
-        // Treasury received the payment.
         let tc = TokenClient::new(&env, &treasury);
-        assert_eq!(tc.balance(&treasury), 500);
+        assert_eq!(tc.balance(&treasury), 400);
     }
 
     #[test]
-    fn test_subscribe_unknown_plan_rejected() {
+    fn test_schedule_downgrade_requires_active_subscription() {
         let env = Env::default();
-        let (client, _, _, _) = setup(&env);
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &86_400u64,
+            &make_hash(&env, 42),
+        );
+
         let user = Address::generate(&env);
-        let result = client.try_subscribe(&user, &999u32);
-        assert!(result.is_err());
+        assert_eq!(
+            client.try_schedule_downgrade(&user, &1u32),
+            Err(Ok(Error::NoActiveSubscription))
+        );
     }
 
+    // ------------------------------------------------------------------
+    // 5. status_of
+    // ------------------------------------------------------------------
+
     #[test]
-    fn test_subscribe_duplicate_active_rejected() {
+    fn test_status_of_no_subscription() {
+        let env = Env::default();
+        let (client, _, _, _) = setup(&env);
+
+        let user = Address::generate(&env);
+        let status = client.status_of(&user);
+        assert!(!status.has_subscription);
+        assert_eq!(status.plan_id, 0);
+        assert_eq!(status.expires_at, 0);
+        assert!(!status.is_active);
+    }
+
+    #[test]
+    fn test_status_of_expired() {
         let env = Env::default();
         let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let hash = make_hash(&env, 8);
-        client.define_plan(&admin, &1u32, &100i128, &86400u64, &hash);
+        let duration: u64 = 86_400;
+        let hash = make_hash(&env, 14);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &hash,
+        );
 
         let user = Address::generate(&env);
-        token_sac.mint(&user, &1000i128);
+        token_sac.mint(&user, &500i128);
 
         set_time(&env, 1_000_000);
         client.subscribe(&user, &1u32);
 
-        // Second subscribe while active — must fail.
-        let result = client.try_subscribe(&user, &1u32);
-        assert!(result.is_err());
+        // Advance past expiry.
+        set_time(&env, 1_000_000 + duration + 1);
+
+        let status = client.status_of(&user);
+        assert!(status.has_subscription);
+        assert!(!status.is_active);
     }
 
     #[test]
-    fn test_subscribe_after_expiry_succeeds() {
+    fn test_renew_cross_plan_prorates_remaining_value() {
         let env = Env::default();
         let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let duration: u64 = 86_400;
-        let hash = make_hash(&env, 9);
-        client.define_plan(&admin, &1u32, &100i128, &duration, &hash);
+        // Same duration, the pro plan costs 4x the basic plan.
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &100_000u64,
+            &make_hash(&env, 30),
+        );
+        client.define_plan(
+            &admin,
+            &2u32,
+            &400i128,
+            &token_sac.address,
+            &100_000u64,
+            &make_hash(&env, 31),
+        );
+        client.set_proration(&admin, &true);
 
         let user = Address::generate(&env);
-        token_sac.mint(&user, &1000i128);
+        token_sac.mint(&user, &1_000i128);
 
         set_time(&env, 1_000_000);
         client.subscribe(&user, &1u32);
 
-        // Advance past expiry.
-        set_time(&env, 1_000_000 + duration + 1);
-        client.subscribe(&user, &1u32);
+        // Halfway through: 50_000s of basic remain, worth 50 tokens, which buys
+        // 12_500s of pro. Naive stacking would give 1_000_000 + 200_000.
+        set_time(&env, 1_050_000);
+        client.renew(&user, &2u32);
 
         let status = client.status_of(&user);
-        assert!(status.is_active);
-        assert_eq!(status.expires_at, 1_000_000 + duration + 1 + duration);
+        assert_eq!(status.plan_id, 2);
+        assert_eq!(status.expires_at, 1_050_000 + 12_500 + 100_000);
     }
 
-    // ------------------------------------------------------------------
-    // 4. renew
-    // ------------------------------------------------------------------
-
     #[test]
-    fn test_renew_active_subscription_stacks() {
+    fn test_renew_cross_plan_without_proration_stacks() {
         let env = Env::default();
         let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let duration: u64 = 86_400;
-        let hash = make_hash(&env, 10);
-        client.define_plan(&admin, &1u32, &100i128, &duration, &hash);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &100_000u64,
+            &make_hash(&env, 32),
+        );
+        client.define_plan(
+            &admin,
+            &2u32,
+            &400i128,
+            &token_sac.address,
+            &100_000u64,
+            &make_hash(&env, 33),
+        );
 
         let user = Address::generate(&env);
-        token_sac.mint(&user, &1000i128);
+        token_sac.mint(&user, &1_000i128);
 
         set_time(&env, 1_000_000);
         client.subscribe(&user, &1u32);
+        set_time(&env, 1_050_000);
+        client.renew(&user, &2u32);
 
-        // Renew while still active — expiry extends from original expires_at.
-        set_time(&env, 1_000_000 + 1000);
-        client.renew(&user, &1u32);
+        assert_eq!(client.status_of(&user).expires_at, 1_000_000 + 200_000);
+    }
 
-        let status = client.status_of(&user);
-        // base = 1_000_000 + 86_400; new expiry = base + 86_400
-        assert_eq!(status.expires_at, 1_000_000 + duration + duration);
-        assert!(status.is_active);
+    #[test]
+    fn test_set_proration_non_admin_rejected() {
+        let env = Env::default();
+        let (client, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let non_admin = Address::generate(&env);
+        let result = client.try_set_proration(&non_admin, &true);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
     }
 
+    // ------------------------------------------------------------------
+    // 6. transfer_subscription
+    // ------------------------------------------------------------------
+
     #[test]
-    fn test_renew_expired_subscription_reactivates() {
+    fn test_transfer_subscription_moves_record() {
         let env = Env::default();
         let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let duration: u64 = 86_400;
-        let hash = make_hash(&env, 11);
-        client.define_plan(&admin, &1u32, &100i128, &duration, &hash);
+        let hash = make_hash(&env, 15);
+        client.define_plan(
+            &admin,
+            &2u32,
+            &100i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
 
-        let user = Address::generate(&env);
-        token_sac.mint(&user, &1000i128);
+        let from = Address::generate(&env);
+        let to = Address::generate(&env);
+        token_sac.mint(&from, &100i128);
 
         set_time(&env, 1_000_000);
-        client.subscribe(&user, &1u32);
+        client.subscribe(&from, &2u32);
+        let before = client.status_of(&from);
 
-        // Advance past expiry.
-        let renew_at = 1_000_000 + duration + 500;
-        set_time(&env, renew_at);
-        client.renew(&user, &1u32);
+        set_time(&env, 1_000_500);
+        client.transfer_subscription(&from, &to);
 
-        let status = client.status_of(&user);
-        assert_eq!(status.expires_at, renew_at + duration);
-        assert!(status.is_active);
+        let from_status = client.status_of(&from);
+        assert!(!from_status.has_subscription);
+
+        let to_status = client.status_of(&to);
+        assert!(to_status.is_active);
+        assert_eq!(to_status.plan_id, before.plan_id);
+        assert_eq!(to_status.expires_at, before.expires_at);
     }
 
     #[test]
-    fn test_renew_no_subscription_rejected() {
+    fn test_transfer_subscription_target_already_subscribed_rejected() {
         let env = Env::default();
-        let (client, admin, _, _) = setup(&env);
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let hash = make_hash(&env, 12);
-        client.define_plan(&admin, &1u32, &100i128, &86400u64, &hash);
+        let hash = make_hash(&env, 16);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
 
-        let user = Address::generate(&env);
-        let result = client.try_renew(&user, &1u32);
-        assert!(result.is_err());
+        let from = Address::generate(&env);
+        let to = Address::generate(&env);
+        token_sac.mint(&from, &100i128);
+        token_sac.mint(&to, &100i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&from, &1u32);
+        client.subscribe(&to, &1u32);
+
+        let result = client.try_transfer_subscription(&from, &to);
+        assert_eq!(result, Err(Ok(Error::AlreadySubscribed)));
+
+        // Source record is untouched.
+        assert!(client.status_of(&from).is_active);
     }
 
     #[test]
-    fn test_renew_charges_user() {
+    fn test_transfer_subscription_expired_rejected() {
         let env = Env::default();
-        let (client, admin, treasury, token_sac) = setup(&env);
+        let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
-        let hash = make_hash(&env, 13);
-        client.define_plan(&admin, &1u32, &300i128, &86400u64, &hash);
+        let hash = make_hash(&env, 17);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &86400u64,
+            &hash,
+        );
 
-        let user = Address::generate(&env);
-        token_sac.mint(&user, &1000i128);
+        let from = Address::generate(&env);
+        let to = Address::generate(&env);
+        token_sac.mint(&from, &100i128);
 
         set_time(&env, 1_000_000);
-        client.subscribe(&user, &1u32); // pays 300
-        client.renew(&user, &1u32); // pays another 300
+        client.subscribe(&from, &1u32);
+        set_time(&env, 1_000_000 + 86_400);
 
-        let tc = TokenClient::new(&env, &treasury);
-        assert_eq!(tc.balance(&treasury), 600);
+        let result = client.try_transfer_subscription(&from, &to);
+        assert_eq!(result, Err(Ok(Error::NoActiveSubscription)));
     }
 
     // ------------------------------------------------------------------
-    // 5. status_of
+    // 6b. metrics
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_status_of_no_subscription() {
+    fn test_metrics_revenue_accumulates_across_subscribe_and_renew() {
         let env = Env::default();
-        let (client, _, _, _) = setup(&env);
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
 
-        let user = Address::generate(&env);
-        let status = client.status_of(&user);
-        assert!(!status.has_subscription);
-        assert_eq!(status.plan_id, 0);
-        assert_eq!(status.expires_at, 0);
-        assert!(!status.is_active);
+        let duration: u64 = 86_400;
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &make_hash(&env, 30),
+        );
+        client.define_plan(
+            &admin,
+            &2u32,
+            &250i128,
+            &token_sac.address,
+            &duration,
+            &make_hash(&env, 31),
+        );
+        assert_eq!(client.metrics(), (0, 0));
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        token_sac.mint(&alice, &1000i128);
+        token_sac.mint(&bob, &1000i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&alice, &1u32);
+        client.subscribe(&bob, &2u32);
+        assert_eq!(client.metrics(), (2, 350));
+
+        // Renewals add revenue but not subscribers.
+        client.renew(&alice, &1u32);
+        client.renew(&bob, &1u32);
+        assert_eq!(client.metrics(), (2, 550));
     }
 
     #[test]
-    fn test_status_of_expired() {
+    fn test_metrics_active_count_drops_after_sweep() {
         let env = Env::default();
         let (client, admin, _, token_sac) = setup(&env);
         env.mock_all_auths();
 
         let duration: u64 = 86_400;
-        let hash = make_hash(&env, 14);
-        client.define_plan(&admin, &1u32, &100i128, &duration, &hash);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &make_hash(&env, 32),
+        );
 
-        let user = Address::generate(&env);
-        token_sac.mint(&user, &500i128);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        token_sac.mint(&alice, &1000i128);
+        token_sac.mint(&bob, &1000i128);
 
         set_time(&env, 1_000_000);
-        client.subscribe(&user, &1u32);
+        client.subscribe(&alice, &1u32);
+        set_time(&env, 1_000_000 + duration / 2);
+        client.subscribe(&bob, &1u32);
+
+        // Alice has expired, but the count only drops once swept.
+        set_time(&env, 1_000_000 + duration);
+        assert_eq!(client.metrics().0, 2);
+        let users = vec![&env, alice.clone(), bob.clone()];
+        assert_eq!(client.sweep_expired(&users), 1);
+        assert_eq!(client.sweep_expired(&users), 0);
+        assert_eq!(client.metrics(), (1, 200));
+
+        // Reactivating a swept subscription counts it again.
+        client.renew(&alice, &1u32);
+        assert_eq!(client.metrics(), (2, 300));
+    }
 
-        // Advance past expiry.
-        set_time(&env, 1_000_000 + duration + 1);
+    #[test]
+    fn test_plan_revenue_credits_plan_charged() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
 
-        let status = client.status_of(&user);
-        assert!(status.has_subscription);
-        assert!(!status.is_active);
+        let duration: u64 = 86_400;
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &make_hash(&env, 33),
+        );
+        client.define_plan(
+            &admin,
+            &2u32,
+            &300i128,
+            &token_sac.address,
+            &duration,
+            &make_hash(&env, 34),
+        );
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        token_sac.mint(&alice, &10_000i128);
+        token_sac.mint(&bob, &10_000i128);
+
+        set_time(&env, 1_000_000);
+        client.subscribe(&alice, &1u32);
+        client.subscribe(&bob, &1u32);
+        client.renew(&alice, &1u32);
+        assert_eq!(client.plan_revenue(&1u32), 300);
+        assert_eq!(client.plan_revenue(&2u32), 0);
+
+        // Cross-plan renewal is credited to the plan being renewed onto.
+        client.renew(&bob, &2u32);
+        client.renew(&bob, &2u32);
+        assert_eq!(client.plan_revenue(&1u32), 300);
+        assert_eq!(client.plan_revenue(&2u32), 600);
+        assert_eq!(client.metrics().1, 900);
+
+        // The renewal's revenue event precedes its `Renewed` event.
+        let events = env.events().all().filter_by_contract(&client.address);
+        let events = events.events();
+        assert_eq!(
+            events[events.len() - 2],
+            PlanRevenueRecorded {
+                plan_id: 2,
+                amount: 300,
+                lifetime_revenue: 600,
+            }
+            .to_xdr(&env, &client.address)
+        );
     }
 
     // ------------------------------------------------------------------
-    // 6. Full lifecycle
+    // 7. Full lifecycle
     // ------------------------------------------------------------------
 
     #[test]
@@ -830,8 +2675,22 @@ mod test {
         let hash_pro = make_hash(&env, 21);
 
         // Define two plans.
-        client.define_plan(&admin, &1u32, &500i128, &duration, &hash_basic);
-        client.define_plan(&admin, &2u32, &1500i128, &duration, &hash_pro);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &500i128,
+            &token_sac.address,
+            &duration,
+            &hash_basic,
+        );
+        client.define_plan(
+            &admin,
+            &2u32,
+            &1500i128,
+            &token_sac.address,
+            &duration,
+            &hash_pro,
+        );
 
         let user = Address::generate(&env);
         token_sac.mint(&user, &10_000i128);
@@ -868,4 +2727,183 @@ mod test {
         assert!(status4.is_active);
         assert_eq!(status4.plan_id, 1);
     }
+
+    // ------------------------------------------------------------------
+    // Trials
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_first_subscribe_uses_trial_terms() {
+        let env = Env::default();
+        let (client, admin, treasury, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let duration: u64 = 86_400;
+        let trial_seconds: u64 = 3_600;
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &make_hash(&env, 30),
+        );
+        client.set_plan_trial(&admin, &1u32, &trial_seconds, &10i128);
+        assert_eq!(
+            client.trial_terms(&1u32),
+            Some(TrialTerms {
+                trial_seconds,
+                trial_price: 10,
+            })
+        );
+
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &1000i128);
+        let tc = TokenClient::new(&env, &treasury);
+
+        set_time(&env, 1_000_000);
+        assert!(!client.has_used_trial(&user, &1u32));
+        client.subscribe(&user, &1u32);
+        assert!(client.has_used_trial(&user, &1u32));
+        assert_eq!(
+            client.status_of(&user).expires_at,
+            1_000_000 + trial_seconds
+        );
+        assert_eq!(tc.balance(&treasury), 10);
+
+        // After the trial lapses, subscribing again uses the normal terms.
+        let later = 1_000_000 + trial_seconds + 1;
+        set_time(&env, later);
+        client.subscribe(&user, &1u32);
+        assert_eq!(client.status_of(&user).expires_at, later + duration);
+        assert_eq!(tc.balance(&treasury), 110);
+    }
+
+    #[test]
+    fn test_start_trial_is_free_and_one_per_plan() {
+        let env = Env::default();
+        let (client, admin, treasury, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let duration: u64 = 86_400;
+        let hash = make_hash(&env, 50);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &duration,
+            &hash,
+        );
+
+        let user = Address::generate(&env);
+        let token = TokenClient::new(&env, &token_sac.address);
+
+        set_time(&env, 1_000_000);
+        client.start_trial(&admin, &user, &1u32, &3_600u64);
+
+        let status = client.status_of(&user);
+        assert!(status.is_active);
+        assert_eq!(status.plan_id, 1);
+        assert_eq!(status.expires_at, 1_000_000 + 3_600);
+        assert_eq!(token.balance(&user), 0);
+        assert_eq!(token.balance(&treasury), 0);
+        assert_eq!(client.metrics(), (1, 0));
+        assert!(client.has_used_trial(&user, &1u32));
+
+        // A second trial of the same plan is rejected, even after expiry.
+        set_time(&env, 1_000_000 + 3_600);
+        assert_eq!(
+            client.try_start_trial(&admin, &user, &1u32, &3_600u64),
+            Err(Ok(Error::TrialAlreadyUsed))
+        );
+
+        // The user can still subscribe normally at full price.
+        token_sac.mint(&user, &100i128);
+        client.subscribe(&user, &1u32);
+        let status = client.status_of(&user);
+        assert!(status.is_active);
+        assert_eq!(status.expires_at, 1_000_000 + 3_600 + duration);
+        assert_eq!(token.balance(&user), 0);
+        assert_eq!(client.metrics(), (1, 100));
+    }
+
+    #[test]
+    fn test_start_trial_rejects_active_subscriber_and_non_admin() {
+        let env = Env::default();
+        let (client, admin, _, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        let hash = make_hash(&env, 51);
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &86_400u64,
+            &hash,
+        );
+        client.define_plan(
+            &admin,
+            &2u32,
+            &200i128,
+            &token_sac.address,
+            &86_400u64,
+            &hash,
+        );
+
+        let user = Address::generate(&env);
+        token_sac.mint(&user, &100i128);
+        set_time(&env, 1_000_000);
+        client.subscribe(&user, &1u32);
+
+        assert_eq!(
+            client.try_start_trial(&admin, &user, &2u32, &3_600u64),
+            Err(Ok(Error::AlreadySubscribed))
+        );
+
+        let other = Address::generate(&env);
+        assert_eq!(
+            client.try_start_trial(&user, &other, &2u32, &3_600u64),
+            Err(Ok(Error::NotAuthorized))
+        );
+    }
+
+    #[test]
+    fn test_free_trial_and_prior_subscribers() {
+        let env = Env::default();
+        let (client, admin, treasury, token_sac) = setup(&env);
+        env.mock_all_auths();
+
+        client.define_plan(
+            &admin,
+            &1u32,
+            &100i128,
+            &token_sac.address,
+            &86_400u64,
+            &make_hash(&env, 31),
+        );
+
+        // A user who subscribed before the trial existed does not get it.
+        let veteran = Address::generate(&env);
+        token_sac.mint(&veteran, &1000i128);
+        set_time(&env, 1_000_000);
+        client.subscribe(&veteran, &1u32);
+
+        assert_eq!(
+            client.try_set_plan_trial(&admin, &1u32, &3_600u64, &101i128),
+            Err(Ok(Error::InvalidInput))
+        );
+        client.set_plan_trial(&admin, &1u32, &3_600u64, &0i128);
+
+        let newcomer = Address::generate(&env);
+        client.subscribe(&newcomer, &1u32);
+        assert_eq!(client.status_of(&newcomer).expires_at, 1_000_000 + 3_600);
+        assert_eq!(client.plan_revenue(&1u32), 100);
+
+        set_time(&env, 1_000_000 + 86_401);
+        client.subscribe(&veteran, &1u32);
+        let tc = TokenClient::new(&env, &treasury);
+        assert_eq!(tc.balance(&treasury), 200);
+    }
 }